@@ -6,9 +6,17 @@ interface Props {
   onLoadFile: (file: File) => void;
   file?: File;
   buttonText?: string;
+  id?: string;
+  accept?: string;
 }
 
-export const FileInput = ({ buttonText, file, onLoadFile }: Props) => {
+export const FileInput = ({
+  buttonText,
+  file,
+  onLoadFile,
+  id = "file-upload",
+  accept = ".json,application/json",
+}: Props) => {
   const inputRef = useRef<HTMLInputElement>(null);
 
   useEffect(() => {
@@ -31,11 +39,11 @@ export const FileInput = ({ buttonText, file, onLoadFile }: Props) => {
         type="file"
         onChange={handleFileChange}
         className="hidden"
-        id="file-upload"
-        accept=".json,application/json"
+        id={id}
+        accept={accept}
       />
       <label
-        htmlFor="file-upload"
+        htmlFor={id}
         className={classNames(
           button({
             color: "primary",
@@ -31,6 +31,7 @@ const apps: ManualAppData[] = [
       "Button Channel",
       "Button CC / PC",
       "NRPN",
+      "14-bit CC",
     ],
     storage: [
       "Level (if 'Store state' enabled)",
@@ -69,6 +70,7 @@ const apps: ManualAppData[] = [
       "NRPN",
       "Send MIDI",
       "Grid Lock",
+      "14-bit CC",
     ],
     storage: ["Clocked", "Attenuation", "Speed", "Waveform", "Muted"],
     text: `LFO is a multi-shape oscillator with manually selectable waveforms. Press the button to cycle through shapes; the LED color shows the active waveform: sine (yellow), triangle (pink), ramp down (cyan), ramp up (red), square (white).
@@ -476,7 +478,14 @@ The output range is configured in the parameters: bipolar (−5V to +5V) or unip
     description: "Euclidean sequencer",
     color: "Orange",
     icon: "euclid",
-    params: ["MIDI Channel", "MIDI NOTE 1", "MIDI NOTE 2", "GATE %", "Color"],
+    params: [
+      "MIDI Channel",
+      "MIDI NOTE 1",
+      "MIDI NOTE 2",
+      "GATE %",
+      "Color",
+      "I2C gates",
+    ],
     storage: [
       "Length",
       "Fill",
@@ -527,7 +536,7 @@ The output range is configured in the parameters: bipolar (−5V to +5V) or unip
     description: "Sends random triggers on clock",
     color: "Cyan",
     icon: "die",
-    params: ["MIDI Channel", "MIDI NOTE", "GATE %", "Color"],
+    params: ["MIDI Channel", "MIDI NOTE", "GATE %", "Color", "I2C gates"],
     storage: ["Probability", "Muted", "Resolution"],
     text: "This app sends random trigger signals on clock. It can output MIDI notes and CV triggers. The fader sets the probability of a trigger occurring at each clock pulse. The button acts as a mute toggle. Shift + Fader sets the clock resolution, allowing for rhythmic variation. While adjusting resolution, the bottom LED is orange for triplet divisions and blue for straight divisions.",
     channels: [
@@ -740,9 +749,10 @@ The output range is configured in the parameters: bipolar (−5V to +5V) or unip
       "Note",
       "Color",
       "Velocity on Gate",
+      "I2C out",
     ],
     storage: ["Attenuation", "Muted"],
-    text: "This app converts MIDI messages into CV signals. It supports multiple modes, each with different output behaviors. The output range is typically 0–10V, except for Pitch Bend mode which uses ±5V. When the `Velocity on Gate` toggle is activated the gate voltage in `Gate` and `Note Gate` modes is directly related to the velocity of the MIDI note with the minimum velocity being 1V and maximum 10V. Parameters include MIDI channel, curve shaping (for CC and Aftertouch), pitch bend range. The Note Gate mode is especially useful for triggering drum modules, as it allows individual gate outputs to be assigned to specific MIDI notes—ideal for drum sequencing setups.",
+    text: "This app converts MIDI messages into CV signals. It supports multiple modes, each with different output behaviors. The output range is typically 0–10V, except for Pitch Bend mode which uses ±5V. When the `Velocity on Gate` toggle is activated the gate voltage in `Gate` and `Note Gate` modes is directly related to the velocity of the MIDI note with the minimum velocity being 1V and maximum 10V. Parameters include MIDI channel, curve shaping (for CC and Aftertouch), pitch bend range. The Note Gate mode is especially useful for triggering drum modules, as it allows individual gate outputs to be assigned to specific MIDI notes—ideal for drum sequencing setups. With `I2C out` enabled the app also drives I²C followers: Pitch mode plays notes on Just Friends, W/ and Disting EX, Gate mode sets gates, Note Gate mode fires triggers and CC mode sets the Disting EX parameter with the same number as the CC.",
     channels: [
       {
         jackTitle: "Output",
@@ -832,7 +842,14 @@ The output range is configured in the parameters: bipolar (−5V to +5V) or unip
     description: "Simple clock divider",
     color: "Orange",
     icon: "note-box",
-    params: ["MIDI Channel", "MIDI Note", "GATE %", "Divisions", "Color"],
+    params: [
+      "MIDI Channel",
+      "MIDI Note",
+      "GATE %",
+      "Divisions",
+      "Color",
+      "I2C gates",
+    ],
     storage: ["Division", "Muted", "Maximum division", "Minimum division"],
     text: "This is a simple clock divider app that was suggested by YouTuber and Discord member Synthdad. The app allows for a performative control of clock division/multiplication allowing for 'build ups and drops' for example. The maximum and minimum divisions can be user set using shift + fader and button + fader respectively. These are saved into the scenes allowing you to set different ranges depending on your needs. Button (no shift) mutes the output. The **Divisions** parameter selects which divider set is available to the fader: **Straight**, **Triplets**, or **Both**.",
     channels: [
@@ -872,6 +889,7 @@ The output range is configured in the parameters: bipolar (−5V to +5V) or unip
       "Color",
       "Store state",
       "NRPN",
+      "14-bit CC",
     ],
     storage: [
       "Level (if 'Store state' enabled)",
@@ -1417,6 +1435,7 @@ On a clock **Reset**, the step counter resets to step 1 — the pattern is not c
       "NRPN",
       "MIDI Out",
       "Resolution",
+      "14-bit CC",
     ],
     storage: [
       "Committed loop buffer",
@@ -1564,6 +1583,7 @@ On load, both registers are restored at the next phrase boundary so the recalled
       "GATE %",
       "Color",
       "MIDI Out",
+      "I2C gates",
     ],
     storage: ["Probability", "Resolution", "Muted A", "Muted B"],
     text: `This app is a two-output Bernoulli gate similar to Branches from Mutable Instruments. On each clock pulse, a weighted coin flip routes a gate and MIDI note to either Output A or Output B, never both. Fader 1 sets the probability that Output A fires (the remaining chance goes to Output B). Fader 2 sets the clock resolution shared by both outputs. Button 1 mutes Output A, Button 2 mutes Output B — each output can be silenced independently while the other keeps triggering on its own turns.
@@ -1595,6 +1615,99 @@ On load, both registers are restored at the next phrase boundary so the recalled
       },
     ],
   },
+  {
+    appId: 28,
+    title: "Chord",
+    description: "Harmonize one CV into four diatonic voices",
+    color: "Cyan",
+    icon: "note-grid",
+    params: ["MIDI Channel", "MIDI Out", "Color", "Range", "1V/Oct", "Scale"],
+    storage: ["Faders", "Voice leading", "Muted voices"],
+    text: "This app turns one pitch CV into a four-voice chord. Jack 1 is the pitch input, which is quantized to the current scale. Jacks 2 to 5 output the voices, lowest first. Chords are stacked from scale degrees, so they always stay in key: triad, sus2, sus4, sixth, seventh, add9 and quartal. Triads double the root an octave up as the fourth voice. Fader 1 picks the chord, Fader 2 the inversion, Fader 3 opens the voicing across octaves and Fader 4 strums the voices up to 100 ms apart. Fader 5 shifts the input by ±2 octaves. Button 1 enables voice leading: every new chord then moves each voice to its nearest chord tone instead of using the inversion and spread. Buttons 2 to 5 mute a voice. Each voice also plays a MIDI note that only retriggers when the voice moves.",
+    channels: [
+      {
+        jackTitle: "Input",
+        jackDescription: "Accepts ±5V or 0–10V pitch CV (set by Range)",
+        faderTitle: "Chord",
+        faderDescription: "Selects the chord type",
+        fnTitle: "Voice Leading",
+        fnDescription: "Enables/disables voice leading",
+        ledTop: "Positive root",
+        ledBottom: "Negative root",
+      },
+      {
+        jackTitle: "Voice 1",
+        jackDescription: "Lowest chord tone as V/Oct CV",
+        faderTitle: "Inversion",
+        faderDescription: "Raises the lowest 0–3 voices by an octave",
+        fnTitle: "Mute Voice 1",
+        fnDescription: "Holds the jack and stops the voice's MIDI notes",
+        ledTop: "Positive output",
+        ledBottom: "Negative output",
+      },
+      {
+        jackTitle: "Voice 2",
+        jackDescription: "Second chord tone as V/Oct CV",
+        faderTitle: "Spread",
+        faderDescription: "Opens the voicing by raising inner voices by octaves",
+        fnTitle: "Mute Voice 2",
+        fnDescription: "Holds the jack and stops the voice's MIDI notes",
+        ledTop: "Positive output",
+        ledBottom: "Negative output",
+      },
+      {
+        jackTitle: "Voice 3",
+        jackDescription: "Third chord tone as V/Oct CV",
+        faderTitle: "Strum",
+        faderDescription: "Delays each voice after the first by up to 100 ms",
+        fnTitle: "Mute Voice 3",
+        fnDescription: "Holds the jack and stops the voice's MIDI notes",
+        ledTop: "Positive output",
+        ledBottom: "Negative output",
+      },
+      {
+        jackTitle: "Voice 4",
+        jackDescription: "Highest chord tone as V/Oct CV",
+        faderTitle: "Octave Shift",
+        faderDescription: "Shifts the input by ±2 octaves",
+        fnTitle: "Mute Voice 4",
+        fnDescription: "Holds the jack and stops the voice's MIDI notes",
+        ledTop: "Positive output",
+        ledBottom: "Negative output",
+      },
+    ],
+  },
+  {
+    appId: 29,
+    title: "Key Follow",
+    description: "Set the global key from MIDI or CV",
+    color: "Yellow",
+    icon: "note-box",
+    params: [
+      "Source",
+      "MIDI In",
+      "MIDI Channel",
+      "Follow major/minor",
+      "Quantize",
+      "Range",
+      "1V/Oct",
+      "Color",
+    ],
+    storage: ["Transpose", "Active"],
+    text: "This app sets the tonic of the global quantizer in real time, so every quantized app follows a keyboard player or a transpose sequence. With the MIDI sources the tonic follows the lowest held note or the last pressed note on the selected channel, and releasing all keys keeps the current tonic. With Follow major/minor enabled, the scale also switches to major (Ionian) or minor (Aeolian) when the held notes contain a major or minor third above the lowest note. With the CV source the tonic follows a V/Oct CV on the jack, rounded to the nearest semitone. Quantize holds every change until the next beat or bar of the clock, so transpositions land in time; changes wait while the clock is stopped. The fader transposes the followed tonic by 0–11 semitones. The button pauses following.",
+    channels: [
+      {
+        jackTitle: "Transpose CV",
+        jackDescription: "V/Oct CV setting the tonic when Source is CV",
+        faderTitle: "Transpose",
+        faderDescription: "Transposes the followed tonic by 0–11 semitones",
+        fnTitle: "Active",
+        fnDescription: "Enables/disables following",
+        ledTop: "Transpose amount",
+        ledBottom: "Current tonic",
+      },
+    ],
+  },
 ];
 
 export const ManualTab = () => {
@@ -1686,9 +1799,15 @@ export const ManualTab = () => {
                   <li>
                     <Link to="#settings-voct">Custom V/Oct Calibration</Link>
                   </li>
+                  <li>
+                    <Link to="#settings-fader-curves">Custom Fader Curves</Link>
+                  </li>
                   <li>
                     <Link to="#settings-misc">Miscellaneous</Link>
                   </li>
+                  <li>
+                    <Link to="#settings-leds">LEDs</Link>
+                  </li>
                   <li>
                     <Link to="#settings-save-recall">Save & Recall Setup</Link>
                   </li>
@@ -4,11 +4,14 @@ import type {
   ClockSrc,
   FixedLengthArray,
   GlobalConfig,
+  I2cDeviceType,
   I2cMode,
-  Key,
+  I2cScaling,
   latch,
   MidiOutConfig,
   MidiOutMode,
+  MtcChaseSrc,
+  MtcFps,
   Note,
   ResetSrc,
 } from "@atov/fp-config";
@@ -16,22 +19,46 @@ import { useCallback, useEffect, useState } from "react";
 import { FormProvider, type SubmitHandler, useForm } from "react-hook-form";
 import { useStore } from "../store";
 import { setGlobalConfig } from "../utils/config";
+import {
+  keyFromString,
+  keyToString,
+  scaleNameToBytes,
+  scaleNameToString,
+} from "../utils/utils";
 import { ButtonPrimary } from "./Button";
 import { Icon } from "./Icon";
 import { SaveLoadSetup } from "./SaveLoadSetup";
 import { AuxSettings } from "./settings/AuxSettings";
 import { ClockSettings } from "./settings/ClockSettings";
+import { CustomCurvesSettings } from "./settings/CustomCurvesSettings";
+import { CustomScalesSettings } from "./settings/CustomScalesSettings";
 import { FactoryReset } from "./settings/FactoryReset";
+import { FaderCalibrationSettings } from "./settings/FaderCalibrationSettings";
 import { I2cSettings } from "./settings/I2cSettings";
+import { LedSettings } from "./settings/LedSettings";
 import { MidiSettings } from "./settings/MidiSettings";
 import { MiscSettings } from "./settings/MiscSettings";
 import { QuantizerSettings } from "./settings/QuantizerSettings";
+import { TuningSettings } from "./settings/TuningSettings";
 import { VoOctCurvesSettings } from "./settings/VoOctCurvesSettings";
+import { VoOctTrimsSettings } from "./settings/VoOctTrimsSettings";
 
 interface SettingsFormProps {
   config: GlobalConfig;
 }
 
+export interface I2cMapRow {
+  device: I2cDeviceType["tag"];
+  address: number;
+  port: number;
+  scaling: I2cScaling["tag"];
+}
+
+export interface CustomScaleRow {
+  name: string;
+  mask: number;
+}
+
 export interface Inputs {
   auxAtom: AuxJackMode["tag"];
   auxMeteor: AuxJackMode["tag"];
@@ -42,31 +69,48 @@ export interface Inputs {
   clockSrc: ClockSrc["tag"];
   extPpqn: number;
   i2cMode: I2cMode["tag"];
+  i2cMap: I2cMapRow[];
   internalBpm: number;
   swingAmount: number;
   ledBrightness: number;
   resetSrc: ResetSrc["tag"];
-  quantizerKey: Key["tag"];
+  // Built-in scale tag, or "Custom:N" for a custom scale
+  quantizerKey: string;
   quantizerTonic: Note["tag"];
+  customScales: CustomScaleRow[];
   takeoverMode: latch.TakeoverMode["tag"];
+  faderHysteresis: number;
+  faderSmoothing: number;
   // MIDI USB
   midiUsbMode: MidiOutMode["tag"];
   midiUsbSendClock: boolean;
   midiUsbSendTransport: boolean;
+  midiUsbSendMtc: boolean;
+  midiUsbClockOffsetMs: number;
+  midiUsbClockDiv: ClockDivision["tag"];
   midiUsbSourceUsb: boolean;
   midiUsbSourceDin: boolean;
   // MIDI Out 1
   midiOut1Mode: MidiOutMode["tag"];
   midiOut1SendClock: boolean;
   midiOut1SendTransport: boolean;
+  midiOut1SendMtc: boolean;
+  midiOut1ClockOffsetMs: number;
+  midiOut1ClockDiv: ClockDivision["tag"];
   midiOut1SourceUsb: boolean;
   midiOut1SourceDin: boolean;
   // MIDI Out 2
   midiOut2Mode: MidiOutMode["tag"];
   midiOut2SendClock: boolean;
   midiOut2SendTransport: boolean;
+  midiOut2SendMtc: boolean;
+  midiOut2ClockOffsetMs: number;
+  midiOut2ClockDiv: ClockDivision["tag"];
   midiOut2SourceUsb: boolean;
   midiOut2SourceDin: boolean;
+  // MIDI Timecode
+  mtcFps: MtcFps["tag"];
+  mtcChase: MtcChaseSrc["tag"];
 }
 
 const SettingsForm = ({ config }: SettingsFormProps) => {
@@ -82,6 +126,9 @@ const SettingsForm = ({ config }: SettingsFormProps) => {
         mode: mode,
         sendClock: out.send_clock,
         sendTransport: out.send_transport,
+        sendMtc: out.send_mtc,
+        clockOffsetMs: out.clock_offset_ms,
+        clockDiv: out.clock_div.tag,
         sourceUsb: out.mode.value.sources[0][0],
         sourceDin: out.mode.value.sources[0][1],
       };
@@ -91,6 +138,9 @@ const SettingsForm = ({ config }: SettingsFormProps) => {
       mode: mode as "None" | "Local",
       sendClock: out.send_clock,
       sendTransport: out.send_transport,
+      sendMtc: out.send_mtc,
+      clockOffsetMs: out.clock_offset_ms,
+      clockDiv: out.clock_div.tag,
       sourceUsb: false,
       sourceDin: false,
     };
@@ -117,28 +167,52 @@ const SettingsForm = ({ config }: SettingsFormProps) => {
       internalBpm: config.clock.internal_bpm,
       swingAmount: config.clock.swing_amount,
       i2cMode: config.i2c_mode.tag,
-      quantizerKey: config.quantizer.key.tag,
+      i2cMap: config.i2c_map.map((mapping) => ({
+        device: mapping.device.tag,
+        address: mapping.address,
+        port: mapping.port,
+        scaling: mapping.scaling.tag,
+      })),
+      quantizerKey: keyToString(config.quantizer.key),
       quantizerTonic: config.quantizer.tonic.tag,
+      customScales: config.custom_scales.map((scale) => ({
+        name: scaleNameToString(scale.name),
+        mask: scale.mask,
+      })),
       ledBrightness: config.led_brightness,
       takeoverMode: config.takeover_mode.tag,
+      faderHysteresis: config.fader.hysteresis,
+      faderSmoothing: config.fader.smoothing,
       // MIDI USB
       midiUsbMode: midiUsb.mode,
       midiUsbSendClock: midiUsb.sendClock,
       midiUsbSendTransport: midiUsb.sendTransport,
+      midiUsbSendMtc: midiUsb.sendMtc,
+      midiUsbClockOffsetMs: midiUsb.clockOffsetMs,
+      midiUsbClockDiv: midiUsb.clockDiv,
       midiUsbSourceUsb: midiUsb.sourceUsb,
       midiUsbSourceDin: midiUsb.sourceDin,
       // MIDI Out 1
       midiOut1Mode: midiOut1.mode,
       midiOut1SendClock: midiOut1.sendClock,
       midiOut1SendTransport: midiOut1.sendTransport,
+      midiOut1SendMtc: midiOut1.sendMtc,
+      midiOut1ClockOffsetMs: midiOut1.clockOffsetMs,
+      midiOut1ClockDiv: midiOut1.clockDiv,
       midiOut1SourceUsb: midiOut1.sourceUsb,
       midiOut1SourceDin: midiOut1.sourceDin,
       // MIDI Out 2
       midiOut2Mode: midiOut2.mode,
       midiOut2SendClock: midiOut2.sendClock,
       midiOut2SendTransport: midiOut2.sendTransport,
+      midiOut2SendMtc: midiOut2.sendMtc,
+      midiOut2ClockOffsetMs: midiOut2.clockOffsetMs,
+      midiOut2ClockDiv: midiOut2.clockDiv,
       midiOut2SourceUsb: midiOut2.sourceUsb,
       midiOut2SourceDin: midiOut2.sourceDin,
+      // MIDI Timecode
+      mtcFps: config.midi.mtc_fps.tag,
+      mtcChase: config.midi.mtc_chase.tag,
     },
   });
   const [saved, setSaved] = useState<boolean>(false);
@@ -180,10 +254,16 @@ const SettingsForm = ({ config }: SettingsFormProps) => {
         <ClockSettings />
         <AuxSettings />
         <QuantizerSettings />
+        <CustomScalesSettings />
+        <TuningSettings />
         <MidiSettings />
         <I2cSettings />
         <MiscSettings />
+        <LedSettings />
         <VoOctCurvesSettings config={config} />
+        <VoOctTrimsSettings />
+        <FaderCalibrationSettings />
+        <CustomCurvesSettings />
         <SaveLoadSetup />
         <FactoryReset />
         <div className="flex justify-between">
@@ -236,6 +316,9 @@ const buildMidiOutConfig = (
   mode: MidiOutMode["tag"],
   sendClock: boolean,
   sendTransport: boolean,
+  sendMtc: boolean,
+  clockOffsetMs: number,
+  clockDiv: ClockDivision["tag"],
   sourceUsb: boolean,
   sourceDin: boolean,
 ): MidiOutConfig => {
@@ -248,6 +331,9 @@ const buildMidiOutConfig = (
     return {
       send_clock: sendClock,
       send_transport: sendTransport,
+      send_mtc: sendMtc,
+      clock_offset_ms: clockOffsetMs,
+      clock_div: { tag: clockDiv },
       mode: {
         tag: mode,
         value: {
@@ -260,6 +346,9 @@ const buildMidiOutConfig = (
   return {
     send_clock: sendClock,
     send_transport: sendTransport,
+    send_mtc: sendMtc,
+    clock_offset_ms: clockOffsetMs,
+    clock_div: { tag: clockDiv },
     mode: { tag: mode },
   };
 };
@@ -279,6 +368,9 @@ const transformFormToGlobalConfig = (
       formValues.midiUsbMode,
       formValues.midiUsbSendClock,
       formValues.midiUsbSendTransport,
+      formValues.midiUsbSendMtc,
+      formValues.midiUsbClockOffsetMs,
+      formValues.midiUsbClockDiv,
       false, // USB output cannot route from USB input
       true, // USB output always routes from DIN (only valid source)
     ),
@@ -286,6 +378,9 @@ const transformFormToGlobalConfig = (
       formValues.midiOut1Mode,
       formValues.midiOut1SendClock,
       formValues.midiOut1SendTransport,
+      formValues.midiOut1SendMtc,
+      formValues.midiOut1ClockOffsetMs,
+      formValues.midiOut1ClockDiv,
       formValues.midiOut1SourceUsb,
       formValues.midiOut1SourceDin,
     ),
@@ -293,6 +388,9 @@ const transformFormToGlobalConfig = (
       formValues.midiOut2Mode,
       formValues.midiOut2SendClock,
       formValues.midiOut2SendTransport,
+      formValues.midiOut2SendMtc,
+      formValues.midiOut2ClockOffsetMs,
+      formValues.midiOut2ClockDiv,
       formValues.midiOut2SourceUsb,
       formValues.midiOut2SourceDin,
     ),
@@ -311,12 +409,28 @@ const transformFormToGlobalConfig = (
     led_brightness: formValues.ledBrightness,
     midi: {
       outs: midiOutsArray,
+      mtc_fps: { tag: formValues.mtcFps },
+      mtc_chase: { tag: formValues.mtcChase },
     },
     quantizer: {
-      key: { tag: formValues.quantizerKey },
+      key: keyFromString(formValues.quantizerKey),
       tonic: { tag: formValues.quantizerTonic },
     },
     takeover_mode: { tag: formValues.takeoverMode },
     custom_voct_curves: currentConfig.custom_voct_curves,
+    i2c_map: formValues.i2cMap.map((row) => ({
+      device: { tag: row.device },
+      address: row.address,
+      port: row.port,
+      scaling: { tag: row.scaling },
+    })) as GlobalConfig["i2c_map"],
+    custom_scales: formValues.customScales.map((scale) => ({
+      name: scaleNameToBytes(scale.name),
+      mask: scale.mask,
+    })) as GlobalConfig["custom_scales"],
+    fader: {
+      hysteresis: formValues.faderHysteresis,
+      smoothing: formValues.faderSmoothing,
+    },
   };
 };
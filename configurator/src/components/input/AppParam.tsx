@@ -20,8 +20,10 @@ import { ParamMidiIn } from "./ParamMidiIn.tsx";
 import { ParamMidiMode } from "./ParamMidiMode.tsx";
 import { ParamMidiNote } from "./ParamMidiNote.tsx";
 import { ParamMidiNrpn } from "./ParamMidiNrpn.tsx";
+import { ParamMidiCc14 } from "./ParamMidiCc14.tsx";
 import { ParamMidiOut } from "./ParamMidiOut.tsx";
 import { ParamVoltPerOct } from "./ParamVoltPerOct.tsx";
+import { ParamScale } from "./ParamScale.tsx";
 
 interface Props {
   defaultValue:
@@ -203,6 +205,15 @@ export const AppParam = ({
         />
       );
     }
+    case "MidiCc14": {
+      return (
+        <ParamMidiCc14
+          defaultValue={defaultValue as boolean}
+          paramIndex={paramIndex}
+          register={register}
+        />
+      );
+    }
     case "VoltPerOct": {
       return (
         <ParamVoltPerOct
@@ -212,6 +223,15 @@ export const AppParam = ({
         />
       );
     }
+    case "Scale": {
+      return (
+        <ParamScale
+          defaultValue={defaultValue as string}
+          paramIndex={paramIndex}
+          control={control}
+        />
+      );
+    }
     default: {
       return null;
     }
@@ -15,7 +15,14 @@ interface Props {
   variants: Curve[];
 }
 
-type Item = { key: Curve["tag"]; value: string; icon: string };
+type Item = { key: string; value: string; icon: string };
+
+// Drawn in the settings, offered by every curve param
+const CUSTOM_ITEMS: Item[] = [0, 1, 2, 3].map((idx) => ({
+  key: `Custom:${idx}`,
+  value: `Custom ${idx + 1}`,
+  icon: "fader",
+}));
 
 export const ParamCurve = ({
   defaultValue,
@@ -25,12 +32,16 @@ export const ParamCurve = ({
   variants,
 }: Props) => {
   const items = useMemo(
-    () =>
-      variants.map((variant) => ({
-        key: variant.tag,
-        value: variant.tag,
-        icon: pascalToKebab(variant.tag),
-      })),
+    () => [
+      ...variants
+        .filter((variant) => variant.tag !== "Custom")
+        .map((variant) => ({
+          key: variant.tag,
+          value: variant.tag,
+          icon: pascalToKebab(variant.tag),
+        })),
+      ...CUSTOM_ITEMS,
+    ],
     [variants],
   );
   return (
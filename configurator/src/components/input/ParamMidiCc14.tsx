@@ -0,0 +1,28 @@
+import { type UseFormRegister, type FieldValues } from "react-hook-form";
+import { Switch } from "@heroui/switch";
+
+interface Props {
+  paramIndex: number;
+  defaultValue: boolean;
+  register: UseFormRegister<FieldValues>;
+}
+
+export const ParamMidiCc14 = ({
+  defaultValue,
+  paramIndex,
+  register,
+}: Props) => (
+  <div className="flex w-40 items-start">
+    <Switch
+      defaultSelected={defaultValue}
+      {...register(`param-MidiCc14-${paramIndex}`)}
+      color="secondary"
+      classNames={{
+        base: "flex-col-reverse items-start justify-start w-full",
+        label: "ms-0 mb-2 text-sm font-medium",
+      }}
+    >
+      14-bit CC
+    </Switch>
+  </div>
+);
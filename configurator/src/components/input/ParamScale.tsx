@@ -0,0 +1,78 @@
+import { type FieldValues, Controller, type Control } from "react-hook-form";
+import { Select, SelectItem } from "@heroui/select";
+
+import { useStore } from "../../store";
+import { scaleNameToString } from "../../utils/utils";
+import { builtInKeyItems, tonicItems } from "../settings/QuantizerSettings";
+import { selectProps } from "./defaultProps";
+
+interface Props {
+  defaultValue: string;
+  paramIndex: number;
+  control: Control<FieldValues>;
+}
+
+type Item = { key: string; value: string };
+
+const GLOBAL_ITEM: Item = { key: "Global", value: "Global quantizer" };
+
+// The value is "Global", or scale and tonic as "Ionian/D"
+export const ParamScale = ({ defaultValue, paramIndex, control }: Props) => {
+  const { config } = useStore();
+  const keyItems: Item[] = [
+    GLOBAL_ITEM,
+    ...builtInKeyItems,
+    ...(config?.custom_scales ?? []).map((scale, i) => ({
+      key: `Custom:${i}`,
+      value: scaleNameToString(scale.name) || `Custom ${i + 1}`,
+    })),
+  ];
+
+  return (
+    <Controller
+      name={`param-Scale-${paramIndex}`}
+      control={control}
+      defaultValue={defaultValue}
+      render={({ field: { onChange, value } }) => {
+        const [key, tonic = "C"] = (value as string).split("/");
+        return (
+          <div className="flex flex-row gap-4">
+            <Select
+              {...selectProps}
+              label="Scale"
+              placeholder="Scale"
+              items={keyItems}
+              selectedKeys={[key]}
+              onSelectionChange={(keys) => {
+                const selected = keys.currentKey;
+                if (!selected) return;
+                onChange(
+                  selected === "Global" ? "Global" : `${selected}/${tonic}`,
+                );
+              }}
+            >
+              {(item: Item) => (
+                <SelectItem key={item.key}>{item.value}</SelectItem>
+              )}
+            </Select>
+            <Select
+              {...selectProps}
+              label="Tonic"
+              placeholder="Tonic"
+              items={tonicItems}
+              isDisabled={key === "Global"}
+              selectedKeys={[tonic]}
+              onSelectionChange={(keys) => {
+                const selected = keys.currentKey;
+                if (!selected) return;
+                onChange(`${key}/${selected}`);
+              }}
+            >
+              {(item) => <SelectItem key={item.key}>{item.value}</SelectItem>}
+            </Select>
+          </div>
+        );
+      }}
+    />
+  );
+};
@@ -293,6 +293,12 @@ export const Configurator = () => (
       <br />
       You can set this behavior in the Settings tab.
     </p>
+    <p>
+      As a <strong>Leader</strong>, each channel can be mapped to a device,
+      address and port on the bus. Channels left on <strong>Auto</strong> go
+      to every known device found at startup, like a 16n faderbank. Use{" "}
+      <strong>Rescan I²C bus</strong> to see which devices are connected.
+    </p>
 
     <H4 id="settings-aux">AUX Jacks</H4>
     <p>
@@ -409,6 +415,17 @@ export const Configurator = () => (
       needing to be reconfigured.
     </p>
 
+    <H4 id="settings-fader-curves">Custom Fader Curves</H4>
+    <p>
+      Draw up to <strong>four custom fader curves</strong> (Custom 1–4) that
+      set how a fader's position maps to its output. Pick a curve, choose 16,
+      24 or 32 points and drag them into shape, then click{" "}
+      <strong>Save</strong>. <strong>Reset</strong> turns the curve back into a
+      straight line. Apps with a curve parameter list the custom curves next to
+      the built-in ones. The curves are stored on the device and survive power
+      cycles, but are not part of an exported setup file.
+    </p>
+
     <H4 id="settings-misc">Miscellaneous</H4>
     <List>
       <li>
@@ -439,6 +456,29 @@ export const Configurator = () => (
       </li>
     </List>
 
+    <H4 id="settings-leds">LEDs</H4>
+    <p>
+      These apply as soon as you change them, without <strong>Save</strong>.
+      They are stored on the device, but are not part of an exported setup
+      file.
+    </p>
+    <List>
+      <li>
+        <strong>LED Theme</strong>: <strong>Night</strong> keeps the LEDs dim
+        and warm for dark stages, whatever the LED Brightness is set to.
+      </li>
+      <li>
+        <strong>LED Palette</strong>: <strong>Color-blind friendly</strong>{" "}
+        shifts reds towards orange and greens towards blue so they are easier
+        to tell apart.
+      </li>
+      <li>
+        <strong>LED Idle Dim</strong>: Dims the LEDs after this many minutes
+        without touching a fader or button. Any input brings them back. Set it
+        to 0 to never dim.
+      </li>
+    </List>
+
     <H4 id="settings-save-recall">Save & Recall Setup</H4>
     <p>
       At the bottom of the Settings tab, you'll find controls for saving and
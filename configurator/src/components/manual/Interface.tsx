@@ -146,6 +146,12 @@ export const Interface = () => (
         <strong>Hold scene and then press Shift</strong> →{" "}
         <strong>Starts/stops</strong> the internal clock
       </li>
+      <li>
+        <strong>Hold Shift, then Scene, and press buttons 4–15</strong> →{" "}
+        <strong>Toggles notes</strong> of the selected custom scale. With a
+        built-in scale selected, or on the other buttons, scenes load and save
+        as usual.
+      </li>
     </List>
     <p>
       These shortcuts allow quick access to essential performance parameters
@@ -161,6 +167,29 @@ export const Interface = () => (
       the intensity reflecting whether the note is in the scale or not. White
       keys are represented by a white LED and black by a yellow LED.
     </p>
+    <p className="mt-4">
+      Past <strong>Off</strong>, Fader 4 selects the four{" "}
+      <strong>custom scales</strong>, shown as a bright white LED. Custom
+      scales are named and edited in the configurator, or on the device: hold
+      Shift, then Scene, and press the buttons under the keyboard LEDs to add
+      or remove notes. An empty custom scale quantizes chromatically.
+    </p>
+    <p className="mt-4">
+      The quantizer can also use a <strong>microtonal tuning</strong>, set up
+      under Settings in the configurator: an equal division of the octave (EDO)
+      of up to 32 steps, or a tuning imported from a Scala <code>.scl</code>{" "}
+      file. A tuning replaces the scales and repeats every period, starting
+      from the root note. Tunings with twelve notes still follow the selected
+      scale. MIDI notes are sent as the nearest semitone plus pitch bend, so
+      set the receiving synth's bend range to 2 semitones.
+    </p>
+    <p className="mt-4">
+      Apps that quantize pitch have a <strong>Scale</strong> parameter. Left
+      at <strong>Global quantizer</strong> they follow Faders 4 and 5; set to
+      a scale and tonic of their own they keep it, so a bass line and a melody
+      can run in different modes. Custom scales and the tuning are always
+      shared.
+    </p>
     <p className="mt-4">
       For <strong>swing</strong> (Fader 15), the top LED shows both the
       direction and amount of swing: a{" "}
@@ -519,7 +548,9 @@ export const Interface = () => (
         </List>
         The takeover mode can be configured in the{" "}
         <strong>Miscellaneous</strong> section of the{" "}
-        <strong>Configurator</strong> Settings tab.
+        <strong>Configurator</strong> Settings tab. Every app also has a{" "}
+        <strong>Takeover</strong> parameter to override it for that app only,
+        e.g. to have an automation looper jump while a mixer fader picks up.
       </li>
     </List>
   </>
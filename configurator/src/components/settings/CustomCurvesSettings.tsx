@@ -0,0 +1,200 @@
+import type { custom_curve, FixedLengthArray } from "@atov/fp-config";
+import { Select, SelectItem } from "@heroui/select";
+import classNames from "classnames";
+import { type PointerEvent, useCallback, useEffect, useState } from "react";
+
+import { useStore } from "../../store";
+import { getCustomCurves, setCustomCurve } from "../../utils/config";
+import { ButtonPrimary, ButtonSecondary } from "../Button";
+import { selectProps } from "../input/defaultProps";
+
+// Matches libfp's MAX_CURVE_POINTS
+const MAX_POINTS = 32;
+const CURVE_MAX = 4095;
+const POINT_COUNTS = [16, 24, 32];
+
+const WIDTH = 480;
+const HEIGHT = 240;
+
+// Same interpolation as the firmware, used to keep the shape when the number
+// of points changes
+const curveAt = (points: number[], value: number) => {
+  const pos = (value / CURVE_MAX) * (points.length - 1);
+  const idx = Math.min(Math.floor(pos), points.length - 2);
+  const frac = pos - idx;
+  return points[idx] + (points[idx + 1] - points[idx]) * frac;
+};
+
+const resample = (points: number[], len: number) =>
+  Array.from({ length: len }, (_, i) =>
+    Math.round(curveAt(points, (i * CURVE_MAX) / (len - 1))),
+  );
+
+const linear = (len: number) => resample([0, CURVE_MAX], len);
+
+const toPoints = (curve: custom_curve.CustomCurve) =>
+  curve.points.slice(0, curve.len);
+
+const toCurve = (points: number[]): custom_curve.CustomCurve => ({
+  points: [
+    ...points,
+    ...Array(MAX_POINTS - points.length).fill(0),
+  ] as FixedLengthArray<number, 32>,
+  len: points.length,
+});
+
+const pointX = (i: number, len: number) => (i * WIDTH) / (len - 1);
+const pointY = (value: number) => HEIGHT - (value * HEIGHT) / CURVE_MAX;
+
+export const CustomCurvesSettings = () => {
+  const { device, isSimulator } = useStore();
+  const [curves, setCurves] = useState<number[][]>([]);
+  const [selected, setSelected] = useState(0);
+  const [dragging, setDragging] = useState<number | null>(null);
+
+  useEffect(() => {
+    if (!device || isSimulator) return;
+    getCustomCurves(device)
+      .then((loaded) =>
+        // A curve that was never drawn comes back as a straight line
+        setCurves(
+          loaded.map((curve) =>
+            curve.len === 2 ? linear(16) : toPoints(curve),
+          ),
+        ),
+      )
+      .catch(console.error);
+  }, [device, isSimulator]);
+
+  const points = curves[selected];
+
+  const updatePoints = useCallback(
+    (next: number[]) =>
+      setCurves((prev) => prev.map((p, i) => (i === selected ? next : p))),
+    [selected],
+  );
+
+  const moveTo = useCallback(
+    (e: PointerEvent<SVGSVGElement>, idx: number) => {
+      const rect = e.currentTarget.getBoundingClientRect();
+      const y = (e.clientY - rect.top) / rect.height;
+      const value = Math.round((1 - Math.min(Math.max(y, 0), 1)) * CURVE_MAX);
+      updatePoints(points.map((p, i) => (i === idx ? value : p)));
+    },
+    [points, updatePoints],
+  );
+
+  const handlePointerDown = useCallback(
+    (e: PointerEvent<SVGSVGElement>) => {
+      const rect = e.currentTarget.getBoundingClientRect();
+      const x = (e.clientX - rect.left) / rect.width;
+      const idx = Math.round(x * (points.length - 1));
+      e.currentTarget.setPointerCapture(e.pointerId);
+      setDragging(idx);
+      moveTo(e, idx);
+    },
+    [points, moveTo],
+  );
+
+  const handlePointerMove = useCallback(
+    (e: PointerEvent<SVGSVGElement>) => {
+      if (dragging !== null) moveTo(e, dragging);
+    },
+    [dragging, moveTo],
+  );
+
+  const handleSave = useCallback(async () => {
+    if (!device) return;
+    await setCustomCurve(device, selected, toCurve(points));
+  }, [device, selected, points]);
+
+  if (!device || isSimulator || !points) {
+    return null;
+  }
+
+  const path = points
+    .map((p, i) => `${pointX(i, points.length)},${pointY(p)}`)
+    .join(" ");
+
+  return (
+    <div className="mb-12">
+      <h2 className="text-yellow-fp mb-4 text-sm font-bold uppercase">
+        Custom Fader Curves
+      </h2>
+      <div className="flex flex-col gap-y-4 px-4">
+        <p className="text-sm text-gray-400">
+          Drag the points to shape how a fader responds. Apps with a curve
+          setting can then use Custom 1–4.
+        </p>
+        <div className="flex items-end gap-x-4">
+          <div className="flex gap-x-2">
+            {curves.map((_, i) => (
+              <button
+                key={i}
+                type="button"
+                aria-pressed={i === selected}
+                className={classNames(
+                  "rounded-xs px-3 py-1.5 text-sm",
+                  i === selected ? "bg-yellow-fp text-black" : "bg-default-100",
+                )}
+                onClick={() => setSelected(i)}
+              >
+                Custom {i + 1}
+              </button>
+            ))}
+          </div>
+          <Select
+            {...selectProps}
+            label="Points"
+            selectedKeys={[String(points.length)]}
+            onSelectionChange={(keys) => {
+              const len = Number([...keys][0]);
+              if (len) updatePoints(resample(points, len));
+            }}
+          >
+            {POINT_COUNTS.map((count) => (
+              <SelectItem key={String(count)}>{String(count)}</SelectItem>
+            ))}
+          </Select>
+        </div>
+        <svg
+          viewBox={`-6 -6 ${WIDTH + 12} ${HEIGHT + 12}`}
+          className="bg-default-100 w-full max-w-xl cursor-crosshair touch-none rounded-xs"
+          onPointerDown={handlePointerDown}
+          onPointerMove={handlePointerMove}
+          onPointerUp={() => setDragging(null)}
+        >
+          <line
+            x1={0}
+            y1={HEIGHT}
+            x2={WIDTH}
+            y2={0}
+            className="stroke-gray-600"
+            strokeDasharray="4 4"
+          />
+          <polyline
+            points={path}
+            fill="none"
+            className="stroke-yellow-fp"
+            strokeWidth={2}
+          />
+          {points.map((p, i) => (
+            <circle
+              key={i}
+              cx={pointX(i, points.length)}
+              cy={pointY(p)}
+              r={i === dragging ? 6 : 4}
+              className="fill-yellow-fp"
+            />
+          ))}
+        </svg>
+        <div className="flex gap-x-4">
+          <ButtonPrimary onPress={handleSave}>Save</ButtonPrimary>
+          <ButtonSecondary onPress={() => updatePoints(linear(points.length))}>
+            Reset
+          </ButtonSecondary>
+        </div>
+      </div>
+    </div>
+  );
+};
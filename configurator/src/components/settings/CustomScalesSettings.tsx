@@ -0,0 +1,85 @@
+import { Input } from "@heroui/input";
+import classNames from "classnames";
+import { Controller, useFormContext } from "react-hook-form";
+
+import { inputProps } from "../input/defaultProps";
+import type { Inputs } from "../SettingsTab";
+
+const NOTE_NAMES = [
+  "C",
+  "C♯",
+  "D",
+  "D♯",
+  "E",
+  "F",
+  "F♯",
+  "G",
+  "G♯",
+  "A",
+  "A♯",
+  "B",
+];
+
+// The mask holds C in bit 11 down to B in bit 0, like the built-in scales
+const noteBit = (note: number) => 1 << (11 - note);
+
+export const CustomScalesSettings = () => {
+  const { control, watch } = useFormContext<Inputs>();
+  const customScales = watch("customScales");
+
+  return (
+    <div className="mb-12">
+      <h2 className="text-yellow-fp mb-4 text-sm font-bold uppercase">
+        Custom Scales
+      </h2>
+      <div className="grid grid-cols-4 items-center gap-x-16 gap-y-4 px-4">
+        {customScales.map((_, i) => (
+          <div key={i} className="contents">
+            <Controller
+              name={`customScales.${i}.name`}
+              control={control}
+              render={({ field }) => (
+                <Input
+                  {...inputProps}
+                  label={`Custom ${i + 1}`}
+                  placeholder={`Custom ${i + 1}`}
+                  maxLength={8}
+                  value={field.value}
+                  onChange={(e) => field.onChange(e.target.value)}
+                  onBlur={field.onBlur}
+                />
+              )}
+            />
+            <Controller
+              name={`customScales.${i}.mask`}
+              control={control}
+              render={({ field }) => (
+                <div className="col-span-3 flex flex-row gap-1">
+                  {NOTE_NAMES.map((name, note) => {
+                    const on = (field.value & noteBit(note)) !== 0;
+                    return (
+                      <button
+                        key={name}
+                        type="button"
+                        aria-pressed={on}
+                        className={classNames(
+                          "h-8 w-8 rounded-xs text-xs",
+                          on ? "bg-yellow-fp text-black" : "bg-default-100",
+                        )}
+                        onClick={() =>
+                          field.onChange(field.value ^ noteBit(note))
+                        }
+                      >
+                        {name}
+                      </button>
+                    );
+                  })}
+                </div>
+              )}
+            />
+          </div>
+        ))}
+      </div>
+    </div>
+  );
+};
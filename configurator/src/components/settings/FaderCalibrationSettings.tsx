@@ -0,0 +1,103 @@
+import type { types } from "@atov/fp-config";
+import { useCallback, useEffect, useState } from "react";
+
+import { useStore } from "../../store";
+import {
+  finishFaderCalibration,
+  getFaderCalibration,
+  startFaderCalibration,
+} from "../../utils/config";
+import { ButtonPrimary, ButtonSecondary } from "../Button";
+
+// Matches libfp's FADER_CALIB_MIN_SPAN, less than that is read raw
+const MIN_SPAN = 2048;
+
+const isCalibrated = (calibration: types.FaderCalibration, fader: number) =>
+  calibration.max[fader] >= calibration.min[fader] + MIN_SPAN;
+
+export const FaderCalibrationSettings = () => {
+  const { device, isSimulator } = useStore();
+  const [calibration, setCalibration] =
+    useState<types.FaderCalibration | null>(null);
+  const [sweeping, setSweeping] = useState(false);
+  const [error, setError] = useState<string>();
+
+  useEffect(() => {
+    if (!device || isSimulator) return;
+    getFaderCalibration(device).then(setCalibration).catch(console.error);
+  }, [device, isSimulator]);
+
+  // Leaving the page loses the sweep, which the device only drops after five
+  // minutes, so ask first
+  useEffect(() => {
+    if (!sweeping) return;
+    const handler = (e: BeforeUnloadEvent) => {
+      e.preventDefault();
+      e.returnValue = "";
+    };
+    window.addEventListener("beforeunload", handler);
+    return () => window.removeEventListener("beforeunload", handler);
+  }, [sweeping]);
+
+  const handleStart = useCallback(async () => {
+    if (!device) return;
+    setError(undefined);
+    await startFaderCalibration(device);
+    setSweeping(true);
+  }, [device]);
+
+  const handleFinish = useCallback(async () => {
+    if (!device) return;
+    try {
+      setCalibration(await finishFaderCalibration(device));
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+    } finally {
+      setSweeping(false);
+    }
+  }, [device]);
+
+  if (!device || isSimulator) {
+    return null;
+  }
+
+  return (
+    <div className="mb-12">
+      <h2 className="text-yellow-fp mb-4 text-sm font-bold uppercase">
+        Fader Calibration
+      </h2>
+      <div className="flex flex-col gap-y-4 px-4">
+        {sweeping ? (
+          <p className="text-sm">
+            Move every fader all the way down and all the way up, then press
+            Done. Faders you don&apos;t move keep their calibration.
+          </p>
+        ) : (
+          <p className="text-sm text-gray-400">
+            Calibrate worn faders that don&apos;t reach their ends anymore.
+          </p>
+        )}
+        <div className="grid grid-cols-8 gap-x-4 gap-y-2">
+          {Array.from({ length: 16 }, (_, fader) => (
+            <div key={fader} className="flex flex-col text-xs">
+              <span className="font-medium">Fader {fader + 1}</span>
+              <span className="text-gray-400">
+                {calibration && isCalibrated(calibration, fader)
+                  ? `${calibration.min[fader]}–${calibration.max[fader]}`
+                  : "Not calibrated"}
+              </span>
+            </div>
+          ))}
+        </div>
+        {error && <p className="text-sm text-red-400">{error}</p>}
+        <div className="flex gap-x-4">
+          {sweeping ? (
+            <ButtonPrimary onPress={handleFinish}>Done</ButtonPrimary>
+          ) : (
+            <ButtonSecondary onPress={handleStart}>Calibrate</ButtonSecondary>
+          )}
+        </div>
+      </div>
+    </div>
+  );
+};
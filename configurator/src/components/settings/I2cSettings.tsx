@@ -1,7 +1,13 @@
-import type { I2cMode } from "@atov/fp-config";
+import type { I2cDeviceType, I2cMode, I2cScaling } from "@atov/fp-config";
+import { useCallback, useState } from "react";
+import { Input } from "@heroui/input";
 import { SelectItem } from "@heroui/select";
-import { useFormContext } from "react-hook-form";
+import { Controller, useFormContext } from "react-hook-form";
 
+import { useStore } from "../../store";
+import { scanI2c } from "../../utils/config";
+import { ButtonSecondary } from "../Button";
+import { inputProps } from "../input/defaultProps";
 import type { Inputs } from "../SettingsTab";
 import { ControlledSelect } from "./ControlledFields";
 
@@ -15,8 +21,87 @@ const i2cModeItems: I2cModeItem[] = [
   { key: "Leader", value: "Leader" },
 ];
 
+interface I2cDeviceItem {
+  key: I2cDeviceType["tag"];
+  value: string;
+}
+
+const i2cDeviceItems: I2cDeviceItem[] = [
+  { key: "Auto", value: "Auto (16n)" },
+  { key: "Off", value: "Off" },
+  { key: "Ansible", value: "Ansible" },
+  { key: "Er301", value: "ER-301" },
+  { key: "Txo", value: "TXo" },
+  { key: "JustFriends", value: "Just Friends" },
+  { key: "WSynth", value: "W/ synth" },
+  { key: "WTape", value: "W/ tape" },
+  { key: "DistingEx", value: "Disting EX" },
+  { key: "Crow", value: "crow" },
+];
+
+// Address of the first follower of each type, matching libfp::i2c_leader
+const defaultAddresses: Partial<Record<I2cDeviceType["tag"], number>> = {
+  Ansible: 0x20,
+  Er301: 0x31,
+  Txo: 0x60,
+  JustFriends: 0x70,
+  WSynth: 0x76,
+  WTape: 0x71,
+  DistingEx: 0x41,
+  Crow: 0x01,
+};
+
+interface I2cScalingItem {
+  key: I2cScaling["tag"];
+  value: string;
+}
+
+const i2cScalingItems: I2cScalingItem[] = [
+  { key: "Full", value: "0-10V" },
+  { key: "Volts", value: "App range" },
+];
+
+const deviceName = (tag: I2cDeviceType["tag"]) =>
+  i2cDeviceItems.find((item) => item.key === tag)?.value ?? tag;
+
+const hex = (address: number) =>
+  `0x${address.toString(16).toUpperCase().padStart(2, "0")}`;
+
+const I2cBusScan = () => {
+  const { device, isSimulator } = useStore();
+  const [found, setFound] = useState<
+    { device: I2cDeviceType; address: number }[] | null
+  >(null);
+
+  const handleScan = useCallback(async () => {
+    if (!device) return;
+    setFound(await scanI2c(device));
+  }, [device]);
+
+  if (!device || isSimulator) {
+    return null;
+  }
+
+  return (
+    <div className="col-span-4 flex flex-row items-center gap-6">
+      <ButtonSecondary onPress={handleScan}>Rescan I²C bus</ButtonSecondary>
+      {found && (
+        <span className="text-sm">
+          {found.length > 0
+            ? found
+                .map((f) => `${deviceName(f.device.tag)} (${hex(f.address)})`)
+                .join(", ")
+            : "No known devices found"}
+        </span>
+      )}
+    </div>
+  );
+};
+
 export const I2cSettings = () => {
-  const { control } = useFormContext<Inputs>();
+  const { control, setValue, watch } = useFormContext<Inputs>();
+  const i2cMode = watch("i2cMode");
+  const i2cMap = watch("i2cMap");
 
   return (
     <div className="mb-12">
@@ -31,7 +116,89 @@ export const I2cSettings = () => {
         >
           {(item) => <SelectItem>{item.value}</SelectItem>}
         </ControlledSelect>
+        {i2cMode === "Leader" && <I2cBusScan />}
       </div>
+      {i2cMode === "Leader" && (
+        <div className="mt-8 grid grid-cols-5 items-center gap-x-8 gap-y-4 px-4">
+          {i2cMap.map((row, i) => {
+            const mapped = row.device !== "Auto" && row.device !== "Off";
+            return (
+              <div key={i} className="contents">
+                <span className="text-sm">Channel {i + 1}</span>
+                <ControlledSelect
+                  name={`i2cMap.${i}.device`}
+                  control={control}
+                  items={i2cDeviceItems}
+                  label="Device"
+                  placeholder="Device"
+                  selectProps={{
+                    onSelectionChange: (keys) => {
+                      const tag = keys.currentKey as I2cDeviceType["tag"];
+                      setValue(`i2cMap.${i}.device`, tag);
+                      setValue(
+                        `i2cMap.${i}.address`,
+                        defaultAddresses[tag] ?? 0,
+                      );
+                    },
+                  }}
+                >
+                  {(item) => <SelectItem>{item.value}</SelectItem>}
+                </ControlledSelect>
+                <Controller
+                  name={`i2cMap.${i}.address`}
+                  control={control}
+                  render={({ field }) => (
+                    <Input
+                      {...inputProps}
+                      label="Address"
+                      type="number"
+                      inputMode="numeric"
+                      min={0}
+                      max={127}
+                      step={1}
+                      isDisabled={!mapped}
+                      value={String(field.value)}
+                      onChange={(e) => field.onChange(Number(e.target.value))}
+                      onBlur={field.onBlur}
+                    />
+                  )}
+                />
+                <Controller
+                  name={`i2cMap.${i}.port`}
+                  control={control}
+                  render={({ field }) => (
+                    <Input
+                      {...inputProps}
+                      label="Port"
+                      type="number"
+                      inputMode="numeric"
+                      min={1}
+                      max={256}
+                      step={1}
+                      isDisabled={!mapped}
+                      value={String(field.value + 1)}
+                      onChange={(e) =>
+                        field.onChange(Math.max(0, Number(e.target.value) - 1))
+                      }
+                      onBlur={field.onBlur}
+                    />
+                  )}
+                />
+                <ControlledSelect
+                  name={`i2cMap.${i}.scaling`}
+                  control={control}
+                  items={i2cScalingItems}
+                  label="Scaling"
+                  placeholder="Scaling"
+                  isDisabled={row.device === "Off"}
+                >
+                  {(item) => <SelectItem>{item.value}</SelectItem>}
+                </ControlledSelect>
+              </div>
+            );
+          })}
+        </div>
+      )}
     </div>
   );
 };
@@ -0,0 +1,92 @@
+import type { LedConfig, LedPalette, LedTheme } from "@atov/fp-config";
+import { Select, SelectItem } from "@heroui/select";
+import { Slider } from "@heroui/slider";
+import { useCallback, useEffect, useState } from "react";
+
+import { useStore } from "../../store";
+import { getLedConfig, setLedConfig } from "../../utils/config";
+import { selectProps } from "../input/defaultProps";
+
+const ledThemeItems: { key: LedTheme["tag"]; value: string }[] = [
+  { key: "Day", value: "Day (Default)" },
+  { key: "Night", value: "Night" },
+];
+
+const ledPaletteItems: { key: LedPalette["tag"]; value: string }[] = [
+  { key: "Standard", value: "Standard (Default)" },
+  { key: "ColorBlind", value: "Color-blind friendly" },
+];
+
+export const LedSettings = () => {
+  const { device, isSimulator } = useStore();
+  const [config, setConfig] = useState<LedConfig | null>(null);
+
+  useEffect(() => {
+    if (!device || isSimulator) return;
+    getLedConfig(device).then(setConfig).catch(console.error);
+  }, [device, isSimulator]);
+
+  // Applied and stored right away, apart from the global config
+  const apply = useCallback(
+    async (next: LedConfig) => {
+      if (!device) return;
+      setConfig(next);
+      await setLedConfig(device, next);
+    },
+    [device],
+  );
+
+  if (!device || isSimulator || !config) {
+    return null;
+  }
+
+  return (
+    <div className="mb-12">
+      <h2 className="text-yellow-fp mb-4 text-sm font-bold uppercase">LEDs</h2>
+      <div className="grid grid-cols-4 gap-x-16 gap-y-8 px-4">
+        <Select
+          {...selectProps}
+          label="LED Theme"
+          placeholder="Select theme"
+          items={ledThemeItems}
+          selectedKeys={[config.theme.tag]}
+          onSelectionChange={(keys) => {
+            const tag = keys.currentKey as LedTheme["tag"] | undefined;
+            if (tag) apply({ ...config, theme: { tag } });
+          }}
+        >
+          {(item) => <SelectItem>{item.value}</SelectItem>}
+        </Select>
+        <Select
+          {...selectProps}
+          label="LED Palette"
+          placeholder="Select palette"
+          items={ledPaletteItems}
+          selectedKeys={[config.palette.tag]}
+          onSelectionChange={(keys) => {
+            const tag = keys.currentKey as LedPalette["tag"] | undefined;
+            if (tag) apply({ ...config, palette: { tag } });
+          }}
+        >
+          {(item) => <SelectItem>{item.value}</SelectItem>}
+        </Select>
+        <Slider
+          label="LED Idle Dim (min)"
+          minValue={0}
+          maxValue={60}
+          value={config.idle_dim_minutes}
+          onChange={(value) => {
+            if (!Array.isArray(value)) {
+              setConfig({ ...config, idle_dim_minutes: value });
+            }
+          }}
+          onChangeEnd={(value) => {
+            if (!Array.isArray(value)) {
+              apply({ ...config, idle_dim_minutes: value });
+            }
+          }}
+        />
+      </div>
+    </div>
+  );
+};
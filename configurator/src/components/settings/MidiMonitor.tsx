@@ -0,0 +1,120 @@
+import type { MidiLogEntry, MidiLogPort } from "@atov/fp-config";
+import { useEffect, useRef, useState } from "react";
+import { Checkbox } from "@heroui/checkbox";
+
+import { useStore } from "../../store";
+import { getMidiLog, setMidiLogFilter } from "../../utils/config";
+import { ButtonSecondary } from "../Button";
+
+const POLL_INTERVAL_MS = 250;
+const MAX_LINES = 100;
+
+const portLabels: Record<MidiLogPort["tag"], string> = {
+  UsbIn: "USB in",
+  DinIn: "DIN in",
+  UsbOut: "USB out",
+  Out1: "Out 1",
+  Out2: "Out 2",
+};
+
+const formatBytes = (entry: MidiLogEntry) => {
+  const shown = Math.min(entry.len, entry.bytes.length);
+  const hex = entry.bytes
+    .slice(0, shown)
+    .map((b) => b.toString(16).toUpperCase().padStart(2, "0"))
+    .join(" ");
+  return entry.len > shown ? `${hex} … (${entry.len} bytes)` : hex;
+};
+
+export const MidiMonitor = () => {
+  const { device, isSimulator } = useStore();
+  const [running, setRunning] = useState(false);
+  const [showClock, setShowClock] = useState(false);
+  const [showTimecode, setShowTimecode] = useState(false);
+  const [entries, setEntries] = useState<MidiLogEntry[]>([]);
+  const since = useRef(0);
+
+  useEffect(() => {
+    if (!device || isSimulator || !running) return;
+    let cancelled = false;
+    let timeout: ReturnType<typeof setTimeout>;
+
+    const poll = async () => {
+      try {
+        const logged = await getMidiLog(device, since.current);
+        if (cancelled) return;
+        if (logged.length > 0) {
+          since.current = logged[logged.length - 1].seq + 1;
+          setEntries((prev) => [...prev, ...logged].slice(-MAX_LINES));
+        }
+      } catch {
+        // Keep polling, the next request may well go through
+      }
+      if (!cancelled) {
+        timeout = setTimeout(poll, POLL_INTERVAL_MS);
+      }
+    };
+
+    const start = async () => {
+      await setMidiLogFilter(device, {
+        inputs: true,
+        outputs: true,
+        clock: showClock,
+        timecode: showTimecode,
+        channels: 0xffff,
+      });
+      // Setting the filter clears the log, so read from the top
+      since.current = 0;
+      poll();
+    };
+    start();
+
+    return () => {
+      cancelled = true;
+      clearTimeout(timeout);
+    };
+  }, [device, isSimulator, running, showClock, showTimecode]);
+
+  if (!device || isSimulator) {
+    return null;
+  }
+
+  return (
+    <div>
+      <div className="mb-2 flex flex-row items-center gap-6">
+        <ButtonSecondary onPress={() => setRunning((r) => !r)}>
+          {running ? "Stop MIDI monitor" : "Start MIDI monitor"}
+        </ButtonSecondary>
+        <Checkbox
+          color="secondary"
+          isSelected={showClock}
+          onValueChange={setShowClock}
+        >
+          Clock
+        </Checkbox>
+        <Checkbox
+          color="secondary"
+          isSelected={showTimecode}
+          onValueChange={setShowTimecode}
+        >
+          Timecode
+        </Checkbox>
+      </div>
+      {entries.length > 0 && (
+        <div className="bg-default-50 max-h-64 overflow-y-auto rounded p-2">
+          {entries.map((entry) => (
+            <div
+              key={entry.seq}
+              className="grid grid-cols-4 gap-4 font-mono text-xs"
+            >
+              <span>{(entry.time_ms / 1000).toFixed(3)}s</span>
+              <span>{portLabels[entry.port.tag]}</span>
+              <span>{entry.thru ? "thru" : ""}</span>
+              <span>{formatBytes(entry)}</span>
+            </div>
+          ))}
+        </div>
+      )}
+    </div>
+  );
+};
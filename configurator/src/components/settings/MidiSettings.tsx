@@ -1,13 +1,26 @@
-import type { MidiOutMode } from "@atov/fp-config";
+import type {
+  ClockDivision,
+  MidiOutMode,
+  MidiOutStats,
+  MtcChaseSrc,
+  MtcFps,
+} from "@atov/fp-config";
+import { useCallback, useState } from "react";
+import { Input } from "@heroui/input";
 import { SelectItem } from "@heroui/select";
-import { useFormContext } from "react-hook-form";
+import { Controller, useFormContext } from "react-hook-form";
 
+import { useStore } from "../../store";
+import { getMidiOutStats } from "../../utils/config";
+import { ButtonSecondary } from "../Button";
+import { inputProps } from "../input/defaultProps";
 import type { Inputs } from "../SettingsTab";
 import {
   ControlledSelect,
   ControlledSwitch,
   ControlledCheckbox,
 } from "./ControlledFields";
+import { MidiMonitor } from "./MidiMonitor";
 
 interface MidiOutModeItem {
   key: MidiOutMode["tag"];
@@ -30,6 +43,44 @@ const midiOutModeItems: MidiOutModeItem[] = [
   },
 ];
 
+interface MtcFpsItem {
+  key: MtcFps["tag"];
+  value: string;
+}
+
+const mtcFpsItems: MtcFpsItem[] = [
+  { key: "Fps24", value: "24 fps" },
+  { key: "Fps25", value: "25 fps" },
+  { key: "Fps2997", value: "29.97 fps (drop-frame)" },
+  { key: "Fps30", value: "30 fps" },
+];
+
+interface MtcChaseItem {
+  key: MtcChaseSrc["tag"];
+  value: string;
+}
+
+const mtcChaseItems: MtcChaseItem[] = [
+  { key: "None", value: "Off" },
+  { key: "MidiIn", value: "MIDI In" },
+  { key: "MidiUsb", value: "MIDI USB" },
+];
+
+interface ClockDivItem {
+  key: ClockDivision["tag"];
+  value: string;
+}
+
+const clockDivItems: ClockDivItem[] = [
+  { key: "_1", value: "24 PPQN (MIDI clock)" },
+  { key: "_2", value: "12 PPQN" },
+  { key: "_4", value: "6 PPQN" },
+  { key: "_6", value: "4 PPQN" },
+  { key: "_8", value: "3 PPQN" },
+  { key: "_12", value: "2 PPQN" },
+  { key: "_24", value: "1 PPQN (DIN sync)" },
+];
+
 const MIDI_OUTPUTS = [
   { key: "usb", label: "USB", index: 0 },
   { key: "out1", label: "Out 1", index: 1 },
@@ -41,8 +92,23 @@ const switchClassNames = {
   label: "ms-0 mb-2 text-sm font-medium",
 };
 
+// DIN outputs only: USB is not rate limited
+const dinStatsIndex = (key: (typeof MIDI_OUTPUTS)[number]["key"]) =>
+  key === "out1" ? 0 : key === "out2" ? 1 : undefined;
+
 export const MidiSettings = () => {
   const { control, watch } = useFormContext<Inputs>();
+  const { device, isSimulator } = useStore();
+  const [outStats, setOutStats] = useState<MidiOutStats[] | undefined>();
+
+  const handleRefreshStats = useCallback(async () => {
+    if (!device || isSimulator) return;
+    try {
+      setOutStats(await getMidiOutStats(device));
+    } catch {
+      setOutStats(undefined);
+    }
+  }, [device, isSimulator]);
 
   const midiUsbMode = watch("midiUsbMode");
   const midiOut1Mode = watch("midiOut1Mode");
@@ -66,13 +132,16 @@ export const MidiSettings = () => {
               : output.key === "out1"
                 ? midiOut1Mode
                 : midiOut2Mode;
+          const statsIndex = dinStatsIndex(output.key);
+          const stats =
+            statsIndex !== undefined ? outStats?.[statsIndex] : undefined;
 
           return (
             <div key={output.key}>
               <h3 className="text-yellow-fp mb-2 text-xs font-semibold uppercase">
                 {output.label}
               </h3>
-              <div className="grid grid-cols-4 items-start gap-x-16 px-4">
+              <div className="grid grid-cols-5 items-start gap-x-8 px-4">
                 <ControlledSelect
                   name={modeKey}
                   control={control}
@@ -115,6 +184,17 @@ export const MidiSettings = () => {
                   Send Transport
                 </ControlledSwitch>
 
+                <ControlledSwitch
+                  name={`midi${prefix}SendMtc` as keyof Inputs}
+                  control={control}
+                  switchProps={{
+                    color: "secondary",
+                    classNames: switchClassNames,
+                  }}
+                >
+                  Send MTC
+                </ControlledSwitch>
+
                 {(mode === "MidiThru" || mode === "MidiMerge") && (
                   <div className="flex flex-col">
                     {output.key === "usb" ? (
@@ -148,9 +228,81 @@ export const MidiSettings = () => {
                   </div>
                 )}
               </div>
+              <div className="mt-4 grid grid-cols-5 items-start gap-x-8 px-4">
+                <Controller
+                  name={`midi${prefix}ClockOffsetMs` as keyof Inputs}
+                  control={control}
+                  render={({ field }) => (
+                    <Input
+                      {...inputProps}
+                      label="Clock offset (ms)"
+                      type="number"
+                      inputMode="numeric"
+                      min={-100}
+                      max={100}
+                      step={1}
+                      value={String(field.value)}
+                      onChange={(e) => field.onChange(Number(e.target.value))}
+                      onBlur={field.onBlur}
+                    />
+                  )}
+                />
+                <ControlledSelect
+                  name={`midi${prefix}ClockDiv` as keyof Inputs}
+                  control={control}
+                  items={clockDivItems}
+                  label="Clock division"
+                  placeholder="Division"
+                >
+                  {(item) => <SelectItem>{item.value}</SelectItem>}
+                </ControlledSelect>
+              </div>
+              {stats && (
+                <p className="text-default-500 mt-2 px-4 text-xs">
+                  Dropped: {stats.dropped} · Coalesced: {stats.coalesced}
+                </p>
+              )}
             </div>
           );
         })}
+        <div>
+          <h3 className="text-yellow-fp mb-2 text-xs font-semibold uppercase">
+            Timecode
+          </h3>
+          <div className="grid grid-cols-5 items-start gap-x-8 px-4">
+            <ControlledSelect
+              name="mtcFps"
+              control={control}
+              items={mtcFpsItems}
+              label="MTC frame rate"
+              placeholder="Frame rate"
+            >
+              {(item) => <SelectItem>{item.value}</SelectItem>}
+            </ControlledSelect>
+            <ControlledSelect
+              name="mtcChase"
+              control={control}
+              items={mtcChaseItems}
+              label="Chase MTC from"
+              placeholder="Chase source"
+            >
+              {(item) => <SelectItem>{item.value}</SelectItem>}
+            </ControlledSelect>
+          </div>
+          <p className="text-default-500 mt-2 px-4 text-xs">
+            MTC is sent and chased with the internal clock source only.
+            Negative clock offsets send clock early, which also needs the
+            internal clock source.
+          </p>
+        </div>
+        {device && !isSimulator && (
+          <div>
+            <ButtonSecondary onPress={handleRefreshStats}>
+              Show DIN output stats
+            </ButtonSecondary>
+          </div>
+        )}
+        <MidiMonitor />
       </div>
     </div>
   );
@@ -74,6 +74,20 @@ export const MiscSettings = () => {
         >
           {(item) => <SelectItem>{item.value}</SelectItem>}
         </ControlledSelect>
+        <ControlledSlider
+          name="faderHysteresis"
+          control={control}
+          label="Fader Hysteresis"
+          minValue={1}
+          maxValue={64}
+        />
+        <ControlledSlider
+          name="faderSmoothing"
+          control={control}
+          label="Fader Smoothing"
+          minValue={0}
+          maxValue={4}
+        />
       </div>
     </div>
   );
@@ -1,4 +1,4 @@
-import type { Key, Note } from "@atov/fp-config";
+import type { Note } from "@atov/fp-config";
 import { SelectItem } from "@heroui/select";
 
 import type { Inputs } from "../SettingsTab";
@@ -11,7 +11,8 @@ import {
 import { ControlledSelect } from "./ControlledFields";
 
 interface QuantizerKeyItem {
-  key: Key["tag"];
+  // Built-in scale tag, or "Custom:N"
+  key: string;
   value: string;
 }
 
@@ -20,7 +21,7 @@ interface QuantizerTonicItem {
   value: string;
 }
 
-const keyItems: QuantizerKeyItem[] = [
+export const builtInKeyItems: QuantizerKeyItem[] = [
   { key: "Chromatic", value: "Chromatic" },
   { key: "Ionian", value: "Ionian" },
   { key: "Dorian", value: "Dorian" },
@@ -40,7 +41,7 @@ const keyItems: QuantizerKeyItem[] = [
   { key: "Off", value: "Off (passthrough)" },
 ];
 
-const tonicItems: QuantizerTonicItem[] = [
+export const tonicItems: QuantizerTonicItem[] = [
   "C",
   "CSharp",
   "D",
@@ -59,7 +60,15 @@ const tonicItems: QuantizerTonicItem[] = [
 }));
 
 export const QuantizerSettings = () => {
-  const { control } = useFormContext<Inputs>();
+  const { control, watch } = useFormContext<Inputs>();
+  const customScales = watch("customScales");
+  const keyItems: QuantizerKeyItem[] = [
+    ...builtInKeyItems,
+    ...customScales.map((scale, i) => ({
+      key: `Custom:${i}`,
+      value: scale.name || `Custom ${i + 1}`,
+    })),
+  ];
 
   return (
     <div className="mb-12">
@@ -81,7 +90,9 @@ export const QuantizerSettings = () => {
                   className={classNames(
                     "h-5",
                     "w-5",
-                    QUANTIZER_KEY_COLORS[(item as QuantizerKeyItem).key],
+                    QUANTIZER_KEY_COLORS[
+                      (item as QuantizerKeyItem).key.split(":")[0]
+                    ],
                     (item as QuantizerKeyItem).key === "Chromatic" &&
                       "border border-gray-300",
                   )}
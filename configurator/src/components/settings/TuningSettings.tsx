@@ -0,0 +1,98 @@
+import { useCallback, useEffect, useState } from "react";
+import { Input } from "@heroui/input";
+
+import { useStore } from "../../store";
+import { getTuning, setTuning } from "../../utils/config";
+import {
+  describeTuning,
+  edoTuning,
+  MAX_TUNING_STEPS,
+  parseScala,
+  tuningFromCents,
+  twelveTet,
+  type Tuning,
+} from "../../utils/scala";
+import { ButtonSecondary } from "../Button";
+import { FileInput } from "../FileInput";
+import { inputProps } from "../input/defaultProps";
+
+export const TuningSettings = () => {
+  const { device, isSimulator } = useStore();
+  const [current, setCurrent] = useState<Tuning | null>(null);
+  const [divisions, setDivisions] = useState(19);
+  const [error, setError] = useState<string>();
+
+  useEffect(() => {
+    if (!device || isSimulator) return;
+    getTuning(device).then(setCurrent).catch(console.error);
+  }, [device, isSimulator]);
+
+  const apply = useCallback(
+    async (tuning: Tuning) => {
+      if (!device) return;
+      setError(undefined);
+      await setTuning(device, tuning);
+      setCurrent(tuning);
+    },
+    [device],
+  );
+
+  const handleLoadFile = useCallback(
+    async (file: File) => {
+      try {
+        const { cents } = parseScala(await file.text());
+        await apply(tuningFromCents(cents));
+      } catch (err) {
+        const message = err instanceof Error ? err.message : String(err);
+        setError(`Could not import ${file.name}: ${message}`);
+      }
+    },
+    [apply],
+  );
+
+  if (!device || isSimulator) {
+    return null;
+  }
+
+  return (
+    <div className="mb-12">
+      <h2 className="text-yellow-fp mb-4 text-sm font-bold uppercase">
+        Tuning
+      </h2>
+      <div className="grid grid-cols-4 items-center gap-x-16 gap-y-8 px-4">
+        <span className="text-sm">
+          Current: {current ? describeTuning(current) : "-"}
+        </span>
+        <div className="col-span-3 flex flex-row items-center gap-6">
+          <Input
+            {...inputProps}
+            className="max-w-32"
+            label="EDO"
+            type="number"
+            inputMode="numeric"
+            min={1}
+            max={MAX_TUNING_STEPS}
+            step={1}
+            value={String(divisions)}
+            onChange={(e) => setDivisions(Number(e.target.value))}
+          />
+          <ButtonSecondary onPress={() => apply(edoTuning(divisions))}>
+            Apply EDO
+          </ButtonSecondary>
+          <ButtonSecondary onPress={() => apply(twelveTet())}>
+            Reset to 12-TET
+          </ButtonSecondary>
+        </div>
+        <div className="col-span-4">
+          <FileInput
+            id="scala-upload"
+            accept=".scl"
+            buttonText="Import Scala file"
+            onLoadFile={handleLoadFile}
+          />
+          {error && <p className="text-danger mt-2 text-sm">{error}</p>}
+        </div>
+      </div>
+    </div>
+  );
+};
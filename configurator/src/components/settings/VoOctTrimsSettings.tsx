@@ -0,0 +1,352 @@
+import type { FixedLengthArray, VoOctTrim } from "@atov/fp-config";
+import { useCallback, useEffect, useState } from "react";
+import {
+  Modal,
+  ModalBody,
+  ModalContent,
+  ModalFooter,
+  ModalHeader,
+} from "@heroui/modal";
+import { Select, SelectItem } from "@heroui/select";
+
+import { useStore } from "../../store";
+import { getVoOctTrims, setVoOctTrims } from "../../utils/config";
+import { sendAndReceive } from "../../utils/midi-protocol";
+import { ButtonPrimary, ButtonSecondary } from "../Button";
+
+type Trims = FixedLengthArray<VoOctTrim, 16>;
+
+const LOW_DAC_COUNTS = 410; // 1V
+const HIGH_DAC_COUNTS = 1638; // 4V
+const OCTAVE_SPAN = 3.0; // 4V - 1V at standard 1V/oct
+// Bounds of libfp's VoOctTrim::is_valid, the firmware ignores anything beyond
+const MAX_OFFSET_CENTS = 1200;
+const MAX_SCALE_PPM = 100_000;
+// See VoOctCurvesSettings, MeasureVoOct can take up to 30s
+const MEASURE_TIMEOUT_MS = 32_000;
+
+type WizardStep =
+  | { type: "setup" }
+  | { type: "measuring1" }
+  | { type: "measuring2"; f1: number }
+  | { type: "confirm"; trim: VoOctTrim; f0: number; note: string }
+  | { type: "error"; message: string };
+
+const AUX_OPTIONS = [
+  { key: "0", label: "Atom" },
+  { key: "1", label: "Meteor" },
+  { key: "2", label: "Cube" },
+];
+
+const formatTrim = ({ offset_cents, scale_ppm }: VoOctTrim): string => {
+  if (offset_cents === 0 && scale_ppm === 0) {
+    return "Not trimmed";
+  }
+  const sign = (n: number) => (n > 0 ? "+" : "");
+  return `${sign(offset_cents)}${offset_cents} ct, ${sign(scale_ppm)}${scale_ppm} ppm`;
+};
+
+// Compute the trim from the untrimmed frequencies at 1V and 4V. The scale
+// brings the tracking to exactly one octave per volt, the offset then moves
+// 0V onto the nearest C (12-TET, A = 440 Hz).
+const computeTrim = (
+  f1: number,
+  f2: number,
+): { trim: VoOctTrim; f0: number; note: string } | null => {
+  // Octaves the oscillator moves per volt
+  const tracking = Math.log2(f2 / f1) / OCTAVE_SPAN;
+  if (!(tracking > 0)) return null;
+  const f0 = f1 / 2 ** tracking;
+
+  const semitonesFromC = 12 * Math.log2(f0 / 440) + 9;
+  const nearestC = Math.round(semitonesFromC / 12) * 12;
+  const correctionCents = (nearestC - semitonesFromC) * 100;
+  const octave = nearestC / 12 + 4;
+
+  const trim = {
+    offset_cents: Math.round(correctionCents / tracking),
+    scale_ppm: Math.round((1 / tracking - 1) * 1_000_000),
+  };
+  if (
+    Math.abs(trim.offset_cents) > MAX_OFFSET_CENTS ||
+    Math.abs(trim.scale_ppm) > MAX_SCALE_PPM
+  ) {
+    return null;
+  }
+  return { trim, f0, note: `C${octave}` };
+};
+
+export const VoOctTrimsSettings = () => {
+  const { device, isSimulator, setSuspendHealthCheck } = useStore();
+
+  const [trims, setTrims] = useState<Trims | null>(null);
+  const [openJack, setOpenJack] = useState<number | null>(null);
+  const [auxInput, setAuxInput] = useState("0");
+  const [wizardStep, setWizardStep] = useState<WizardStep>({ type: "setup" });
+
+  useEffect(() => {
+    if (!device || isSimulator) return;
+    getVoOctTrims(device).then(setTrims).catch(console.error);
+  }, [device, isSimulator]);
+
+  // See VoOctCurvesSettings, leaving mid-measurement strands the jack's app
+  useEffect(() => {
+    if (openJack === null) return;
+    const handler = (e: BeforeUnloadEvent) => {
+      e.preventDefault();
+      e.returnValue = "";
+    };
+    window.addEventListener("beforeunload", handler);
+    return () => window.removeEventListener("beforeunload", handler);
+  }, [openJack]);
+
+  const storeTrim = useCallback(
+    async (jack: number, trim: VoOctTrim) => {
+      if (!device || !trims) return;
+      const updated = [...trims] as Trims;
+      updated[jack] = trim;
+      await setVoOctTrims(device, updated);
+      setTrims(updated);
+    },
+    [device, trims],
+  );
+
+  const handleOpenWizard = useCallback((jack: number) => {
+    setOpenJack(jack);
+    setWizardStep({ type: "setup" });
+  }, []);
+
+  const measure = useCallback(
+    async (jack: number, aux: number, dacCounts: number) => {
+      if (!device) return null;
+      const response = await sendAndReceive(
+        device,
+        {
+          tag: "MeasureVoOct",
+          value: { output_jack: jack, aux_input: aux, dac_counts: dacCounts },
+        },
+        MEASURE_TIMEOUT_MS,
+      );
+      return response.tag === "VoOctFrequency" ? response.value.freq_hz : null;
+    },
+    [device],
+  );
+
+  const handleStart = useCallback(async () => {
+    if (openJack === null) return;
+    const aux = parseInt(auxInput, 10);
+
+    // The firmware measures the jack without its current trim, so the
+    // result doesn't depend on what was stored before.
+    setSuspendHealthCheck(true);
+    try {
+      setWizardStep({ type: "measuring1" });
+      const f1 = await measure(openJack, aux, LOW_DAC_COUNTS);
+      if (f1 === null) {
+        setWizardStep({
+          type: "error",
+          message:
+            "No signal detected at 1V. Check AUX jack and VCO connections.",
+        });
+        return;
+      }
+
+      setWizardStep({ type: "measuring2", f1 });
+      const f2 = await measure(openJack, aux, HIGH_DAC_COUNTS);
+      if (f2 === null) {
+        setWizardStep({
+          type: "error",
+          message:
+            "No signal detected at 4V. Check AUX jack and VCO connections.",
+        });
+        return;
+      }
+
+      const result = computeTrim(f1, f2);
+      if (!result) {
+        setWizardStep({
+          type: "error",
+          message: `The VCO tracking (${f1.toFixed(1)} Hz at 1V, ${f2.toFixed(1)} Hz at 4V) is too far off to trim. Check output jack and VCO V/Oct wiring.`,
+        });
+        return;
+      }
+      setWizardStep({ type: "confirm", ...result });
+    } catch (e) {
+      setWizardStep({ type: "error", message: String(e) });
+    } finally {
+      setSuspendHealthCheck(false);
+    }
+  }, [openJack, auxInput, measure, setSuspendHealthCheck]);
+
+  const handleSave = useCallback(async () => {
+    if (openJack === null || wizardStep.type !== "confirm") return;
+    await storeTrim(openJack, wizardStep.trim);
+    setOpenJack(null);
+  }, [openJack, wizardStep, storeTrim]);
+
+  if (!device || isSimulator) {
+    return null;
+  }
+
+  const isMeasuring =
+    wizardStep.type === "measuring1" || wizardStep.type === "measuring2";
+
+  return (
+    <div className="mb-12">
+      <h2 className="text-yellow-fp mb-4 text-sm font-bold uppercase">
+        V/Oct Trims
+      </h2>
+      <div className="grid grid-cols-2 gap-x-16 gap-y-4 px-4">
+        {Array.from({ length: 16 }, (_, jack) => {
+          const trim = trims?.[jack];
+          const isTrimmed =
+            !!trim && (trim.offset_cents !== 0 || trim.scale_ppm !== 0);
+          return (
+            <div key={jack} className="flex items-center justify-between">
+              <span className="text-sm font-medium">Jack {jack + 1}</span>
+              <span className="text-sm text-gray-400">
+                {trim ? formatTrim(trim) : "-"}
+              </span>
+              <div className="flex gap-2">
+                <ButtonSecondary
+                  size="sm"
+                  onPress={() => handleOpenWizard(jack)}
+                  isDisabled={!trims}
+                >
+                  Tune
+                </ButtonSecondary>
+                <ButtonSecondary
+                  size="sm"
+                  onPress={() =>
+                    storeTrim(jack, { offset_cents: 0, scale_ppm: 0 })
+                  }
+                  isDisabled={!isTrimmed}
+                >
+                  Reset
+                </ButtonSecondary>
+              </div>
+            </div>
+          );
+        })}
+      </div>
+
+      <Modal
+        isOpen={openJack !== null}
+        isDismissable={!isMeasuring}
+        isKeyboardDismissDisabled={isMeasuring}
+        hideCloseButton={isMeasuring}
+        onOpenChange={(open) => {
+          if (!open) setOpenJack(null);
+        }}
+      >
+        <ModalContent>
+          {(onClose) => (
+            <>
+              <ModalHeader className="flex flex-col items-start gap-y-1">
+                <span>Tune Jack {openJack !== null ? openJack + 1 : ""}</span>
+                <span className="text-xs font-normal text-gray-400">
+                  Don&apos;t refresh or close this page while measuring.
+                </span>
+              </ModalHeader>
+
+              <ModalBody>
+                {wizardStep.type === "setup" && (
+                  <div className="flex flex-col gap-y-4">
+                    <Select
+                      label="AUX input (from VCO audio out)"
+                      selectedKeys={[auxInput]}
+                      disallowEmptySelection
+                      onSelectionChange={(keys) => {
+                        const first = [...keys][0];
+                        if (
+                          typeof first === "string" ||
+                          typeof first === "number"
+                        )
+                          setAuxInput(String(first));
+                      }}
+                      items={AUX_OPTIONS}
+                    >
+                      {(item) => (
+                        <SelectItem key={item.key}>{item.label}</SelectItem>
+                      )}
+                    </Select>
+                    <p className="text-xs text-gray-400">
+                      Patch the jack to the V/Oct input of your VCO. Faderpunk
+                      will output 1V and 4V, measure the VCO frequency via the
+                      AUX jack and trim the jack so the VCO tracks 1V/Oct with
+                      0V on a C.
+                    </p>
+                  </div>
+                )}
+
+                {wizardStep.type === "measuring1" && (
+                  <p className="animate-pulse text-sm">
+                    Outputting 1V — measuring frequency…
+                  </p>
+                )}
+
+                {wizardStep.type === "measuring2" && (
+                  <div className="flex flex-col gap-y-2">
+                    <p className="text-sm text-gray-400">
+                      1V: {wizardStep.f1.toFixed(1)} Hz ✓
+                    </p>
+                    <p className="animate-pulse text-sm">
+                      Outputting 4V — measuring frequency…
+                    </p>
+                  </div>
+                )}
+
+                {wizardStep.type === "confirm" && (
+                  <div className="flex flex-col gap-y-1">
+                    <p className="text-sm font-medium">
+                      {formatTrim(wizardStep.trim)}
+                    </p>
+                    <p className="text-xs text-gray-400">
+                      0V: {wizardStep.f0.toFixed(1)} Hz, tuned to{" "}
+                      {wizardStep.note}
+                    </p>
+                  </div>
+                )}
+
+                {wizardStep.type === "error" && (
+                  <p className="text-sm text-red-400">{wizardStep.message}</p>
+                )}
+              </ModalBody>
+
+              <ModalFooter>
+                {wizardStep.type === "setup" && (
+                  <>
+                    <ButtonPrimary onPress={handleStart}>Start</ButtonPrimary>
+                    <ButtonSecondary onPress={onClose}>Cancel</ButtonSecondary>
+                  </>
+                )}
+
+                {wizardStep.type === "confirm" && (
+                  <>
+                    <ButtonPrimary onPress={handleSave}>Save</ButtonPrimary>
+                    <ButtonSecondary
+                      onPress={() => setWizardStep({ type: "setup" })}
+                    >
+                      Measure again
+                    </ButtonSecondary>
+                  </>
+                )}
+
+                {wizardStep.type === "error" && (
+                  <>
+                    <ButtonPrimary
+                      onPress={() => setWizardStep({ type: "setup" })}
+                    >
+                      Retry
+                    </ButtonPrimary>
+                    <ButtonSecondary onPress={onClose}>Cancel</ButtonSecondary>
+                  </>
+                )}
+              </ModalFooter>
+            </>
+          )}
+        </ModalContent>
+      </Modal>
+    </div>
+  );
+};
@@ -201,4 +201,18 @@ export const APP_LIBRARY: AppLibraryEntry[] = [
     color: "Cyan",
     icon: "die",
   },
+  {
+    id: 28,
+    name: "Chord",
+    description: "Harmonize one CV into four diatonic voices",
+    color: "Cyan",
+    icon: "note-grid",
+  },
+  {
+    id: 29,
+    name: "Key Follow",
+    description: "Set the global key from MIDI or CV",
+    color: "Yellow",
+    icon: "note-box",
+  },
 ];
@@ -66,6 +66,8 @@ export const QUANTIZER_KEY_COLORS: Record<string, string> = {
   Gamelan: "bg-palette-light-blue",
   HungarianMin: "bg-palette-rose",
   Off: "bg-black",
+  // Shown brighter than Chromatic on the device
+  Custom: "bg-palette-white",
 };
 
 export const QUANTIZER_TONIC_COLORS: Record<string, string> = {
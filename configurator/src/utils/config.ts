@@ -1,9 +1,15 @@
 import type {
+  custom_curve,
   Layout,
   GlobalConfig,
+  LedConfig,
   Value,
   FixedLengthArray,
   ConfigMsgOut,
+  MidiLogEntry,
+  MidiLogFilter,
+  types,
+  VoOctTrim,
 } from "@atov/fp-config";
 
 import type {
@@ -22,6 +28,7 @@ import {
   sendMessage,
   type FpMidiDevice,
 } from "../utils/midi-protocol";
+import type { Tuning } from "./scala";
 import { getFixedLengthParamArray } from "./utils";
 import {
   parseParamValueFromFile,
@@ -367,6 +374,270 @@ export const deserializeLayout = (json: string) => {
   });
 };
 
+export const getMidiOutStats = async (dev: FpMidiDevice) => {
+  const response = await sendAndReceive(dev, {
+    tag: "GetMidiOutStats",
+  });
+
+  if (response.tag !== "MidiOutStats") {
+    throw new Error(
+      `Could not fetch MIDI out stats. Unexpected repsonse tag: ${response.tag}`,
+    );
+  }
+
+  return response.value;
+};
+
+export const getMidiLog = async (
+  dev: FpMidiDevice,
+  since: number,
+): Promise<MidiLogEntry[]> => {
+  const response = await sendAndReceive(dev, {
+    tag: "GetMidiLog",
+    value: { since },
+  });
+
+  if (response.tag !== "BatchMsgStart") {
+    throw new Error(
+      `Could not fetch MIDI log. Unexpected repsonse tag: ${response.tag}`,
+    );
+  }
+
+  const messages = await receiveBatchMessages(dev, response.value);
+  return messages
+    .filter(
+      (item): item is Extract<ConfigMsgOut, { tag: "MidiLogEntry" }> =>
+        item.tag === "MidiLogEntry",
+    )
+    .map(({ value }) => value);
+};
+
+export const setMidiLogFilter = async (
+  dev: FpMidiDevice,
+  filter: MidiLogFilter,
+) => {
+  await sendMessage(dev, {
+    tag: "SetMidiLogFilter",
+    value: filter,
+  });
+};
+
+export const scanI2c = async (dev: FpMidiDevice) => {
+  const response = await sendAndReceive(dev, { tag: "ScanI2c" });
+
+  if (response.tag !== "BatchMsgStart") {
+    throw new Error(
+      `Could not scan the I2C bus. Unexpected repsonse tag: ${response.tag}`,
+    );
+  }
+
+  const messages = await receiveBatchMessages(dev, response.value);
+  return messages
+    .filter(
+      (item): item is Extract<ConfigMsgOut, { tag: "I2cDevice" }> =>
+        item.tag === "I2cDevice",
+    )
+    .map(({ value }) => value);
+};
+
+export const getTuning = async (dev: FpMidiDevice) => {
+  const response = await sendAndReceive(dev, { tag: "GetTuning" });
+
+  if (response.tag !== "Tuning") {
+    throw new Error(
+      `Could not fetch tuning. Unexpected repsonse tag: ${response.tag}`,
+    );
+  }
+
+  return response.value;
+};
+
+export const setTuning = async (dev: FpMidiDevice, tuning: Tuning) => {
+  await sendMessage(dev, {
+    tag: "SetTuning",
+    value: tuning,
+  });
+};
+
+export const getVoOctTrims = async (dev: FpMidiDevice) => {
+  const response = await sendAndReceive(dev, { tag: "GetVoOctTrims" });
+
+  if (response.tag !== "VoOctTrims") {
+    throw new Error(
+      `Could not fetch V/Oct trims. Unexpected repsonse tag: ${response.tag}`,
+    );
+  }
+
+  return response.value;
+};
+
+export const setVoOctTrims = async (
+  dev: FpMidiDevice,
+  trims: FixedLengthArray<VoOctTrim, 16>,
+) => {
+  await sendMessage(dev, {
+    tag: "SetVoOctTrims",
+    value: trims,
+  });
+};
+
+export const getFaderCalibration = async (dev: FpMidiDevice) => {
+  const response = await sendAndReceive(dev, { tag: "GetFaderCalibration" });
+
+  if (response.tag !== "FaderCalibration") {
+    throw new Error(
+      `Could not fetch fader calibration. Unexpected repsonse tag: ${response.tag}`,
+    );
+  }
+
+  return response.value;
+};
+
+export const startFaderCalibration = async (dev: FpMidiDevice) => {
+  await sendMessage(dev, { tag: "StartFaderCalibration" });
+};
+
+export const finishFaderCalibration = async (dev: FpMidiDevice) => {
+  const response = await sendAndReceive(dev, {
+    tag: "FinishFaderCalibration",
+  });
+
+  if (response.tag !== "FaderCalibration") {
+    throw new Error(
+      `Could not store fader calibration. Unexpected repsonse tag: ${response.tag}`,
+    );
+  }
+
+  return response.value;
+};
+
+export const getCustomCurves = async (dev: FpMidiDevice) => {
+  const response = await sendAndReceive(dev, { tag: "GetCustomCurves" });
+
+  if (response.tag !== "CustomCurves") {
+    throw new Error(
+      `Could not fetch custom curves. Unexpected repsonse tag: ${response.tag}`,
+    );
+  }
+
+  return response.value;
+};
+
+export const setCustomCurve = async (
+  dev: FpMidiDevice,
+  index: number,
+  curve: custom_curve.CustomCurve,
+) => {
+  await sendMessage(dev, {
+    tag: "SetCustomCurve",
+    value: [index, curve],
+  });
+};
+
+export const getLedConfig = async (dev: FpMidiDevice) => {
+  const response = await sendAndReceive(dev, { tag: "GetLedConfig" });
+
+  if (response.tag !== "LedConfig") {
+    throw new Error(
+      `Could not fetch LED config. Unexpected repsonse tag: ${response.tag}`,
+    );
+  }
+
+  return response.value;
+};
+
+export const setLedConfig = async (dev: FpMidiDevice, config: LedConfig) => {
+  await sendMessage(dev, {
+    tag: "SetLedConfig",
+    value: config,
+  });
+};
+
+export const runCalibrationCheck = async (
+  dev: FpMidiDevice,
+  outputJack: number,
+  inputJack: number,
+) => {
+  const response = await sendAndReceive(dev, {
+    tag: "RunCalibrationCheck",
+    value: { output_jack: outputJack, input_jack: inputJack },
+  });
+
+  if (response.tag !== "BatchMsgStart") {
+    throw new Error(
+      `Could not run calibration check. Unexpected repsonse tag: ${response.tag}`,
+    );
+  }
+
+  const messages = await receiveBatchMessages(dev, response.value);
+  return messages
+    .filter(
+      (
+        item,
+      ): item is Extract<ConfigMsgOut, { tag: "CalibrationCheckPoint" }> =>
+        item.tag === "CalibrationCheckPoint",
+    )
+    .map(({ value }) => value);
+};
+
+export const getCalibration = async (dev: FpMidiDevice) => {
+  const calibResponse = await sendAndReceive(dev, { tag: "GetCalibFile" });
+
+  if (calibResponse.tag !== "CalibFile") {
+    throw new Error(
+      `Could not fetch calibration. Unexpected repsonse tag: ${calibResponse.tag}`,
+    );
+  }
+
+  const piecewiseResponse = await sendAndReceive(dev, {
+    tag: "GetPiecewiseFile",
+  });
+
+  if (piecewiseResponse.tag !== "PiecewiseFile") {
+    throw new Error(
+      `Could not fetch calibration. Unexpected repsonse tag: ${piecewiseResponse.tag}`,
+    );
+  }
+
+  return {
+    calibFile: calibResponse.value,
+    piecewiseFile: piecewiseResponse.value,
+  };
+};
+
+export const setCalibration = async (
+  dev: FpMidiDevice,
+  calibFile: types.CalibFile,
+  piecewiseFile?: types.PiecewiseFile,
+) => {
+  const response = await sendAndReceive(dev, {
+    tag: "SetCalibFile",
+    value: calibFile,
+  });
+
+  if (response.tag !== "CalibrationStored") {
+    throw new Error(
+      `Could not store calibration. Unexpected repsonse tag: ${response.tag}`,
+    );
+  }
+  if (!response.value || !piecewiseFile) {
+    return response.value;
+  }
+
+  const piecewiseResponse = await sendAndReceive(dev, {
+    tag: "SetPiecewiseFile",
+    value: piecewiseFile,
+  });
+
+  if (piecewiseResponse.tag !== "CalibrationStored") {
+    throw new Error(
+      `Could not store calibration. Unexpected repsonse tag: ${piecewiseResponse.tag}`,
+    );
+  }
+
+  return piecewiseResponse.value;
+};
+
 export const factoryReset = async (dev: FpMidiDevice) => {
   await sendMessage(dev, {
     tag: "FactoryReset",
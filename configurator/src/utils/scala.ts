@@ -0,0 +1,102 @@
+import type { FixedLengthArray, tuning } from "@atov/fp-config";
+
+export type Tuning = tuning.Tuning;
+
+// Matches libfp::tuning
+export const MAX_TUNING_STEPS = 32;
+const OCTAVE_DECICENTS = 12000;
+
+export interface ScalaScale {
+  description: string;
+  // Every degree above the tonic in cents, the last one being the period
+  cents: number[];
+}
+
+// Tenths of a cent to the quantizer's semitones * 128, as in libfp::tuning
+const decicentsToFixed = (decicents: number) =>
+  Math.floor((decicents * 128 + 500) / 1000);
+
+const ratioToCents = (num: number, den: number) => 1200 * Math.log2(num / den);
+
+// A pitch line holds cents when it contains a period, a ratio otherwise.
+// Anything after the value is a comment.
+const parsePitch = (line: string) => {
+  const value = line.trim().split(/\s+/)[0];
+  if (value.includes(".")) {
+    return Number(value);
+  }
+  const [num, den = "1"] = value.split("/");
+  return ratioToCents(Number(num), Number(den));
+};
+
+/** Parses a Scala `.scl` file */
+export const parseScala = (text: string): ScalaScale => {
+  const lines = text.split(/\r?\n/).filter((line) => !line.startsWith("!"));
+  if (lines.length < 2) {
+    throw new Error("Not a Scala file");
+  }
+  const description = lines[0].trim();
+  const count = parseInt(lines[1].trim(), 10);
+  if (!Number.isInteger(count) || count < 1) {
+    throw new Error("Invalid number of notes");
+  }
+  const cents = lines
+    .slice(2)
+    .filter((line) => line.trim() !== "")
+    .slice(0, count)
+    .map(parsePitch);
+  if (cents.length !== count || cents.some((c) => !Number.isFinite(c))) {
+    throw new Error("Invalid pitch values");
+  }
+  return { description, cents };
+};
+
+type Steps = FixedLengthArray<number, typeof MAX_TUNING_STEPS>;
+
+const emptySteps = () => new Array(MAX_TUNING_STEPS).fill(0) as Steps;
+
+/** Plain 12-TET, which keeps the quantizer on its scales */
+export const twelveTet = (): Tuning => ({
+  steps: emptySteps(),
+  len: 0,
+  period: OCTAVE_DECICENTS,
+});
+
+/** `divisions` equal steps to the octave */
+export const edoTuning = (divisions: number): Tuning => {
+  const n = Math.min(Math.max(Math.round(divisions), 1), MAX_TUNING_STEPS);
+  const steps = emptySteps();
+  for (let i = 0; i < n; i++) {
+    steps[i] = Math.round((i * OCTAVE_DECICENTS) / n);
+  }
+  return { steps, len: n, period: OCTAVE_DECICENTS };
+};
+
+/** Converts Scala cents to the firmware's tenths of a cent */
+export const tuningFromCents = (cents: number[]): Tuning => {
+  if (cents.length < 1 || cents.length > MAX_TUNING_STEPS) {
+    throw new Error(`Tunings can have 1 to ${MAX_TUNING_STEPS} notes`);
+  }
+  const decicents = cents.map((c) => Math.round(c * 10));
+  if (decicents.some((d, i) => d <= (i > 0 ? decicents[i - 1] : 0))) {
+    throw new Error("Pitches must ascend");
+  }
+  const period = decicents[decicents.length - 1];
+  if (period > 0xffff) {
+    throw new Error("Period is too large");
+  }
+  const fixed = [0, ...decicents].map(decicentsToFixed);
+  if (fixed.some((f, i) => i > 0 && f <= fixed[i - 1])) {
+    throw new Error("Pitches are too close together");
+  }
+  const steps = emptySteps();
+  decicents.slice(0, -1).forEach((d, i) => {
+    steps[i + 1] = d;
+  });
+  return { steps, len: cents.length, period };
+};
+
+export const describeTuning = (tuning: Tuning) =>
+  tuning.len === 0
+    ? "12-TET"
+    : `${tuning.len} notes, period ${(tuning.period / 10).toFixed(1)} cents`;
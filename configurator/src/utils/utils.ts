@@ -1,7 +1,9 @@
 import {
+  type AppScale,
   type Color,
   type Curve,
   type FixedLengthArray,
+  type Key,
   type Note,
   type Range,
   type Value,
@@ -49,7 +51,8 @@ export const getDefaultValue = (val: Value) => {
       return val.value;
     }
     case "Curve": {
-      return val.value.tag;
+      const curve = val.value;
+      return curve.tag === "Custom" ? `Custom:${curve.value}` : curve.tag;
     }
     case "Waveform": {
       return val.value.tag;
@@ -86,10 +89,16 @@ export const getDefaultValue = (val: Value) => {
     case "MidiNrpn": {
       return val.value;
     }
+    case "MidiCc14": {
+      return val.value;
+    }
     case "VoltPerOct": {
       const vpo = val.value;
       return vpo.tag === "Custom" ? `Custom:${vpo.value}` : vpo.tag;
     }
+    case "Scale": {
+      return appScaleToString(val.value);
+    }
   }
 };
 
@@ -106,8 +115,19 @@ const getParamValue = (
       return { tag: "bool", value: value as boolean };
     case "Enum":
       return { tag: "Enum", value: BigInt(value as string) };
-    case "Curve":
-      return { tag: "Curve", value: { tag: value as Curve["tag"] } };
+    case "Curve": {
+      const key = value as string;
+      if (key.startsWith("Custom:")) {
+        return {
+          tag: "Curve",
+          value: { tag: "Custom", value: parseInt(key.slice(7), 10) },
+        };
+      }
+      return {
+        tag: "Curve",
+        value: { tag: key as Exclude<Curve["tag"], "Custom"> },
+      };
+    }
     case "Waveform":
       return { tag: "Waveform", value: { tag: value as Waveform["tag"] } };
     case "Color":
@@ -144,6 +164,8 @@ const getParamValue = (
       return { tag: "MidiMode", value: { tag: value as MidiModeTag } };
     case "MidiNrpn":
       return { tag: "MidiNrpn", value: value as boolean };
+    case "MidiCc14":
+      return { tag: "MidiCc14", value: value as boolean };
     case "VoltPerOct": {
       const key = value as string;
       if (key.startsWith("Custom:")) {
@@ -157,6 +179,8 @@ const getParamValue = (
         value: { tag: key as Exclude<VoltPerOct["tag"], "Custom"> },
       };
     }
+    case "Scale":
+      return { tag: "Scale", value: appScaleFromString(value as string) };
     default:
       return undefined;
   }
@@ -340,3 +364,46 @@ export const addAppToLayout = (layout: AppLayout, appToAdd: App) => {
 export const delay = (ms: number): Promise<void> => {
   return new Promise((resolve) => setTimeout(resolve, ms));
 };
+
+// Custom scale names are 8 ASCII bytes on the device, padded with zeroes
+const SCALE_NAME_LENGTH = 8;
+
+export const scaleNameToString = (bytes: number[]): string =>
+  String.fromCharCode(...bytes.filter((b) => b !== 0));
+
+export const scaleNameToBytes = (name: string): number[] =>
+  Array.from({ length: SCALE_NAME_LENGTH }, (_, i) => {
+    const code = name.charCodeAt(i);
+    return code >= 0x20 && code < 0x7f ? code : 0;
+  });
+
+// Built-in scale tag, or "Custom:N" for a custom scale
+export const keyToString = (key: Key): string =>
+  key.tag === "Custom" ? `Custom:${key.value}` : key.tag;
+
+export const keyFromString = (key: string): Key => {
+  if (key.startsWith("Custom:")) {
+    return { tag: "Custom", value: parseInt(key.slice(7), 10) };
+  }
+  return { tag: key as Exclude<Key["tag"], "Custom"> };
+};
+
+// "Global", or scale and tonic as "Ionian/D"
+export const appScaleToString = (scale: AppScale): string =>
+  scale.tag === "Global"
+    ? "Global"
+    : `${keyToString(scale.value.key)}/${scale.value.tonic.tag}`;
+
+export const appScaleFromString = (scale: string): AppScale => {
+  const [key, tonic] = scale.split("/");
+  if (!tonic) {
+    return { tag: "Global" };
+  }
+  return {
+    tag: "Fixed",
+    value: {
+      key: keyFromString(key),
+      tonic: { tag: tonic as Note["tag"] },
+    },
+  };
+};
@@ -46,7 +46,27 @@ export const getParamSchema = (param: Param) => {
         })
         .catch({ tag: "Enum", value: BigInt(choices[0]) });
     }
-    case "Curve":
+    case "Curve": {
+      // Every curve param offers the custom curves as well
+      const choices = param.value.variants.map((v) => v.tag);
+      if (choices.length === 0) return z.never();
+      const enumSchema = z.enum(choices as [string, ...string[]]);
+      return z
+        .object({
+          tag: z.literal("Curve"),
+          value: z.union([
+            z.object({ tag: enumSchema }),
+            z.object({
+              tag: z.literal("Custom"),
+              value: z.number().int().min(0).max(3),
+            }),
+          ]),
+        })
+        .catch({
+          tag: "Curve",
+          value: { tag: choices[0] },
+        });
+    }
     case "Waveform":
     case "Range":
     case "Note": {
@@ -127,6 +147,14 @@ export const getParamSchema = (param: Param) => {
         })
         .default({ tag: "MidiNrpn", value: false });
     }
+    case "MidiCc14": {
+      return z
+        .object({
+          tag: z.literal("MidiCc14"),
+          value: z.boolean(),
+        })
+        .default({ tag: "MidiCc14", value: false });
+    }
     case "MidiMode": {
       // MidiMode is still enum-like with tag-based variants
       return z
@@ -156,6 +184,26 @@ export const getParamSchema = (param: Param) => {
           value: { tag: "Standard" },
         });
     }
+    case "Scale": {
+      return z
+        .object({
+          tag: z.literal("Scale"),
+          value: z.union([
+            z.object({ tag: z.literal("Global") }),
+            z.object({
+              tag: z.literal("Fixed"),
+              value: z.object({
+                key: taggedObjectSchema,
+                tonic: taggedObjectSchema,
+              }),
+            }),
+          ]),
+        })
+        .catch({
+          tag: "Scale",
+          value: { tag: "Global" },
+        });
+    }
     default: {
       return z.never();
     }
@@ -203,18 +251,29 @@ export const defaultGlobalConfig: GlobalConfig = {
         send_clock: true,
         send_transport: true,
         mode: { tag: "Local" },
+        send_mtc: false,
+        clock_offset_ms: 0,
+        clock_div: { tag: "_1" },
       },
       {
         send_clock: true,
         send_transport: true,
         mode: { tag: "Local" },
+        send_mtc: false,
+        clock_offset_ms: 0,
+        clock_div: { tag: "_1" },
       },
       {
         send_clock: true,
         send_transport: true,
         mode: { tag: "Local" },
+        send_mtc: false,
+        clock_offset_ms: 0,
+        clock_div: { tag: "_1" },
       },
     ],
+    mtc_fps: { tag: "Fps25" },
+    mtc_chase: { tag: "None" },
   },
   quantizer: {
     key: { tag: "Chromatic" },
@@ -227,6 +286,17 @@ export const defaultGlobalConfig: GlobalConfig = {
     { counts_per_oct: 0 },
     { counts_per_oct: 0 },
   ] as GlobalConfig["custom_voct_curves"],
+  i2c_map: Array.from({ length: 16 }, () => ({
+    device: { tag: "Auto" },
+    address: 0,
+    port: 0,
+    scaling: { tag: "Full" },
+  })) as GlobalConfig["i2c_map"],
+  custom_scales: Array.from({ length: 4 }, () => ({
+    name: [0, 0, 0, 0, 0, 0, 0, 0],
+    mask: 0,
+  })) as GlobalConfig["custom_scales"],
+  fader: { hysteresis: 4, smoothing: 0 },
 };
 
 // Lenient schema that validates structure but allows any valid tag values
@@ -257,9 +327,16 @@ const globalConfigSchema = z.object({
           send_clock: z.boolean(),
           send_transport: z.boolean(),
           mode: taggedObjectSchema,
+          // Absent in setup files saved before MIDI Timecode existed
+          send_mtc: z.boolean().default(false),
+          // Absent in setup files saved before per-output clock offsets
+          clock_offset_ms: z.number().int().min(-100).max(100).default(0),
+          clock_div: taggedObjectSchema.default({ tag: "_1" }),
         }),
       )
       .length(3),
+    mtc_fps: taggedObjectSchema.default({ tag: "Fps25" }),
+    mtc_chase: taggedObjectSchema.default({ tag: "None" }),
   }),
   quantizer: z.object({
     key: taggedObjectSchema,
@@ -277,6 +354,35 @@ const globalConfigSchema = z.object({
       { counts_per_oct: 0 },
       { counts_per_oct: 0 },
     ]),
+  // Absent in setup files saved before the I2C leader mapping existed
+  i2c_map: z
+    .array(
+      z.object({
+        device: taggedObjectSchema,
+        address: z.number().int().min(0).max(127),
+        port: z.number().int().min(0).max(255),
+        scaling: taggedObjectSchema,
+      }),
+    )
+    .length(16)
+    .default(() => [...defaultGlobalConfig.i2c_map]),
+  // Absent in setup files saved before custom scales existed
+  custom_scales: z
+    .array(
+      z.object({
+        name: z.array(z.number().int().min(0).max(255)).length(8),
+        mask: z.number().int().min(0).max(0xfff),
+      }),
+    )
+    .length(4)
+    .default(() => [...defaultGlobalConfig.custom_scales]),
+  // Absent in setup files saved before the fader settings existed
+  fader: z
+    .object({
+      hysteresis: z.number().int().min(1).max(64),
+      smoothing: z.number().int().min(0).max(4),
+    })
+    .default(() => ({ ...defaultGlobalConfig.fader })),
 });
 
 export const parseGlobalConfigFromFile = (
@@ -307,6 +413,8 @@ export const parseGlobalConfigFromFile = (
         validated.midi.outs[1],
         validated.midi.outs[2],
       ] as GlobalConfig["midi"]["outs"],
+      mtc_fps: validated.midi.mtc_fps as GlobalConfig["midi"]["mtc_fps"],
+      mtc_chase: validated.midi.mtc_chase as GlobalConfig["midi"]["mtc_chase"],
     },
     quantizer: validated.quantizer as GlobalConfig["quantizer"],
     takeover_mode: validated.takeover_mode as GlobalConfig["takeover_mode"],
@@ -316,6 +424,9 @@ export const parseGlobalConfigFromFile = (
       validated.custom_voct_curves[2],
       validated.custom_voct_curves[3],
     ] as GlobalConfig["custom_voct_curves"],
+    i2c_map: validated.i2c_map as GlobalConfig["i2c_map"],
+    custom_scales: validated.custom_scales as GlobalConfig["custom_scales"],
+    fader: validated.fader,
   };
 
   return config;
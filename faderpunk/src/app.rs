@@ -2,44 +2,55 @@ use core::cell::RefCell;
 
 use embassy_futures::select::{select, Either};
 use embassy_rp::clocks::RoscRng;
-use embassy_sync::{blocking_mutex::raw::NoopRawMutex, signal::Signal};
+use embassy_sync::{blocking_mutex::raw::NoopRawMutex, channel::TrySendError, signal::Signal};
 use embassy_time::Timer;
 use max11300::config::{
     ConfigMode0, ConfigMode3, ConfigMode5, ConfigMode7, Mode, Port, ADCRANGE, AVR, DACRANGE,
     NSAMPLES,
 };
-use midly::{live::LiveEvent, num::u4, MidiMessage, PitchBend};
+use midly::{
+    live::LiveEvent,
+    num::{u4, u7},
+    MidiMessage, PitchBend,
+};
 use portable_atomic::Ordering;
 
 use libfp::{
-    latch::AnalogLatch,
-    quantizer::{Pitch, QuantizerState},
+    i2c_leader::I2cParam,
+    latch::{AnalogLatch, LatchLayer},
+    quantizer::{Chord, Pitch, Quantizer as ScaleQuantizer, QuantizerState},
     utils::{scale_bits_12_7, scale_bits_14_12},
-    Brightness, ClockDivision, Color, Key, MidiCc, MidiChannel, MidiIn, MidiNote, MidiOut, Note,
-    Range, TakeoverMode, VoltPerOct,
+    AppScale, Brightness, ClockDivision, Color, Key, MidiCc, MidiChannel, MidiIn, MidiNote,
+    MidiOut, Note, Range, TakeoverMode, VoltPerOct,
 };
 
 use crate::{
     events::{EventPubSubChannel, InputEvent},
+    storage::app_takeover_mode,
     tasks::{
         buttons::{is_channel_button_pressed, is_shift_button_pressed},
         clock::{ClockSubscriber, CLOCK_PUBSUB},
         global_config::get_global_config,
         i2c::{I2cLeaderMessage, I2cLeaderSender},
-        leds::{set_led_mode, LedMsg},
-        max::{MaxCmd, MaxSender, MAX_CHANNEL, MAX_VALUES_ADC, MAX_VALUES_DAC, MAX_VALUES_FADER},
+        leds::{set_led_mode, set_pickup_guidance, LedMsg},
+        max::{
+            MaxCmd, MaxSender, MAX_CHANNEL, MAX_VALUES_ADC, MAX_VALUES_DAC, MAX_VALUES_FADER,
+            VOCT_TRIMS,
+        },
         midi::{
             AppMidiSender, MidiEvent, MidiEventSource, MidiMsg, MidiPubSubChannel,
             MidiPubSubSubscriber,
         },
+        midi_sysex::{claim_out, SysExIn},
     },
-    QUANTIZER,
+    APP_QUANTIZER, QUANTIZER,
 };
 
 pub use crate::{
     storage::{AppParams, AppStorage, Arr, ManagedStorage, ParamStore},
     tasks::{
         clock::ClockEvent,
+        global_config::set_global_key,
         leds::{Led, LedMode},
     },
 };
@@ -67,6 +78,15 @@ impl<const N: usize> Leds<N> {
         set_led_mode(channel, position, LedMsg::Set(mode));
     }
 
+    /// Shows `value` (0-4095) as a meter filling the button, bottom and top LED
+    #[allow(dead_code)]
+    pub fn set_meter(&self, chan: usize, color: Color, value: u16) {
+        let channel = self.start_channel + chan.clamp(0, N - 1);
+        for position in [Led::Button, Led::Bottom, Led::Top] {
+            set_led_mode(channel, position, LedMsg::Set(LedMode::Meter(color, value)));
+        }
+    }
+
     pub fn unset(&self, chan: usize, position: Led) {
         let channel = self.start_channel + chan.clamp(0, N - 1);
         set_led_mode(channel, position, LedMsg::Reset);
@@ -110,21 +130,39 @@ impl InJack {
 
 pub struct GateJack {
     channel: usize,
+    i2c_sender: Option<I2cLeaderSender>,
 }
 
 impl GateJack {
     fn new(channel: usize) -> Self {
-        Self { channel }
+        Self {
+            channel,
+            i2c_sender: None,
+        }
+    }
+
+    /// Also sends the gate to the I2C leader, on the jack's channel, if `enabled`
+    pub fn mirror_i2c<const N: usize>(mut self, i2c: &I2cOutput<N>, enabled: bool) -> Self {
+        self.i2c_sender = enabled.then_some(i2c.i2c_sender);
+        self
+    }
+
+    fn send_i2c(&self, high: bool) {
+        if let Some(sender) = &self.i2c_sender {
+            let _ = sender.try_send(I2cLeaderMessage::Gate(self.channel, high));
+        }
     }
 
     pub async fn set_high(&self) {
         let port = Port::try_from(self.channel).unwrap();
         MAX_CHANNEL.sender().send(MaxCmd::GpoSetHigh { port }).await;
+        self.send_i2c(true);
     }
 
     pub async fn set_low(&self) {
         let port = Port::try_from(self.channel).unwrap();
         MAX_CHANNEL.sender().send(MaxCmd::GpoSetLow { port }).await;
+        self.send_i2c(false);
     }
 }
 
@@ -145,6 +183,18 @@ impl OutJack {
         };
         MAX_VALUES_DAC[self.channel].store(val, Ordering::Relaxed);
     }
+
+    /// Set a V/Oct pitch, e.g. from `pitch_as_counts`, with the jack's V/Oct
+    /// trim applied. Use `set_value` for anything that isn't a pitch.
+    pub fn set_pitch_value(&self, value: u16) {
+        let (val, dac_range) = match self.range {
+            Range::_0_5V => (value / 2, Range::_0_10V),
+            Range::_Neg5_5V => (value, Range::_Neg5_5V),
+            _ => (value, Range::_0_10V),
+        };
+        let trim = VOCT_TRIMS.lock(|trims| trims.borrow()[self.channel]);
+        MAX_VALUES_DAC[self.channel].store(trim.apply(val, dac_range), Ordering::Relaxed);
+    }
 }
 
 #[derive(Clone, Copy)]
@@ -304,6 +354,72 @@ impl Faders<1> {
     }
 }
 
+/// An [`AnalogLatch`] bound to a fader, showing the way to the stored value on the
+/// fader's LEDs while it waits for a pickup.
+pub struct AppLatch {
+    latch: AnalogLatch,
+    channel: usize,
+    prev_value: u16,
+    is_guiding: bool,
+}
+
+impl AppLatch {
+    fn new(channel: usize, latch: AnalogLatch) -> Self {
+        Self {
+            latch,
+            channel,
+            prev_value: MAX_VALUES_FADER[channel].load(Ordering::Relaxed),
+            is_guiding: false,
+        }
+    }
+
+    /// See [`AnalogLatch::update`]
+    pub fn update(
+        &mut self,
+        value: u16,
+        new_active_layer: LatchLayer,
+        active_layer_target_value: u16,
+    ) -> Option<u16> {
+        let new_value = self
+            .latch
+            .update(value, new_active_layer, active_layer_target_value);
+        let has_moved = value != self.prev_value;
+        self.prev_value = value;
+
+        match self.latch.pickup_distance() {
+            Some(distance) if has_moved => {
+                set_pickup_guidance(self.channel, Some(distance));
+                self.is_guiding = true;
+            }
+            None if self.is_guiding => {
+                set_pickup_guidance(self.channel, None);
+                self.is_guiding = false;
+            }
+            _ => {}
+        }
+
+        new_value
+    }
+
+    #[allow(dead_code)]
+    pub fn active_layer(&self) -> LatchLayer {
+        self.latch.active_layer()
+    }
+
+    #[allow(dead_code)]
+    pub fn is_latched(&self) -> bool {
+        self.latch.is_latched()
+    }
+}
+
+impl Drop for AppLatch {
+    fn drop(&mut self) {
+        if self.is_guiding {
+            set_pickup_guidance(self.channel, None);
+        }
+    }
+}
+
 pub struct Clock {
     subscriber: ClockSubscriber,
 }
@@ -359,6 +475,36 @@ impl<const N: usize> I2cOutput<N> {
         // Dropping occasional updates is fine — the next update will send the current value.
         let _ = self.i2c_sender.try_send(msg);
     }
+
+    /// Plays a note on Just Friends, W/ synth and Disting EX. Velocity is
+    /// 12-bit, 0 ends the note.
+    pub fn send_note(&self, chan: usize, note: MidiNote, velocity: u16) {
+        let chan = chan.clamp(0, N - 1);
+        let msg = I2cLeaderMessage::Note(self.start_channel + chan, note, velocity);
+        // The channel is only drained in leader mode, so never wait on it
+        let _ = self.i2c_sender.try_send(msg);
+    }
+
+    /// Sets a gate on TXo, ER-301, Ansible, Just Friends and crow
+    pub fn send_gate(&self, chan: usize, high: bool) {
+        let chan = chan.clamp(0, N - 1);
+        let msg = I2cLeaderMessage::Gate(self.start_channel + chan, high);
+        let _ = self.i2c_sender.try_send(msg);
+    }
+
+    /// Fires a trigger wherever `send_gate` sets a gate
+    pub fn send_trigger(&self, chan: usize) {
+        let chan = chan.clamp(0, N - 1);
+        let msg = I2cLeaderMessage::Trigger(self.start_channel + chan);
+        let _ = self.i2c_sender.try_send(msg);
+    }
+
+    /// Sets a Disting EX parameter or controls W/ tape
+    pub fn set_param(&self, chan: usize, param: I2cParam, value: i16) {
+        let chan = chan.clamp(0, N - 1);
+        let msg = I2cLeaderMessage::Param(self.start_channel + chan, param, value);
+        let _ = self.i2c_sender.try_send(msg);
+    }
 }
 
 #[derive(Clone, Copy)]
@@ -368,6 +514,7 @@ pub struct MidiOutput {
     midi_out: MidiOut,
     midi_sender: AppMidiSender,
     nrpn_mode: bool,
+    cc14_mode: bool,
 }
 
 impl MidiOutput {
@@ -384,9 +531,23 @@ impl MidiOutput {
             midi_out,
             midi_sender,
             nrpn_mode,
+            cc14_mode: false,
         }
     }
 
+    /// Send CCs 0-31 as 14-bit MSB/LSB pairs (CC n + CC n+32). Higher CCs have
+    /// no LSB partner and stay 7-bit. NRPN mode takes precedence.
+    pub fn with_cc14(mut self, cc14_mode: bool) -> Self {
+        self.cc14_mode = cc14_mode;
+        self
+    }
+
+    /// Whether `send_cc` sends `cc` at full 12-bit resolution rather than 7-bit.
+    /// Gate repeated values with `midi_gate(value, midi.is_high_res(cc))`.
+    pub fn is_high_res(&self, cc: MidiCc) -> bool {
+        self.nrpn_mode || (self.cc14_mode && cc.as_u16() < 32)
+    }
+
     async fn send_midi_msg(&self, msg: MidiMessage) {
         let event = LiveEvent::Midi {
             channel: self.midi_channel,
@@ -396,7 +557,8 @@ impl MidiOutput {
         self.midi_sender.send((self.start_channel, msg)).await;
     }
 
-    /// Sends a MIDI CC message. In NRPN mode, sends as 14-bit NRPN instead.
+    /// Sends a MIDI CC message. In NRPN mode, sends as 14-bit NRPN instead; in
+    /// 14-bit CC mode, sends an MSB/LSB pair for CCs 0-31.
     /// value is normalized to a range of 0-4095
     ///
     /// Non-blocking: callers use this for continuous "latest value wins"
@@ -408,6 +570,9 @@ impl MidiOutput {
         if self.nrpn_mode {
             let msg = MidiMsg::nrpn(self.midi_channel, cc.as_u16(), value, self.midi_out);
             let _ = self.midi_sender.try_send((self.start_channel, msg));
+        } else if self.is_high_res(cc) {
+            let msg = MidiMsg::cc14(self.midi_channel, cc.into(), value, self.midi_out);
+            let _ = self.midi_sender.try_send((self.start_channel, msg));
         } else {
             let event = LiveEvent::Midi {
                 channel: self.midi_channel,
@@ -431,6 +596,17 @@ impl MidiOutput {
         self.send_midi_msg(msg).await;
     }
 
+    /// Sends a MIDI NoteOn message for a quantized pitch.
+    /// With a microtonal tuning active the pitch's offset from the nearest
+    /// semitone is sent as pitch bend (+/-2 semitones) before the note.
+    pub async fn send_pitch_note_on(&self, note_number: MidiNote, pitch: &Pitch, velocity: u16) {
+        let tuned = QUANTIZER.get().lock().await.get_tuning().is_active();
+        if tuned {
+            self.send_pitch_bend(pitch.pitch_bend(2)).await;
+        }
+        self.send_note_on(note_number, velocity).await;
+    }
+
     /// Sends a MIDI NoteOff message.
     pub async fn send_note_off(&self, note_number: MidiNote) {
         let msg = MidiMessage::NoteOff {
@@ -468,11 +644,45 @@ impl MidiOutput {
         };
         self.send_midi_msg(msg).await;
     }
+
+    /// Sends a SysEx message. `body` excludes the F0/F7 delimiters, must be
+    /// 7-bit and at most
+    /// [`SYSEX_MAX_LEN`](crate::tasks::midi_sysex::SYSEX_MAX_LEN) bytes long.
+    ///
+    /// Non-blocking: the message is dropped if it is invalid or the SysEx or
+    /// app MIDI queues are full.
+    #[allow(dead_code)]
+    pub fn send_sysex(&self, body: &[u8]) {
+        let Some(slot) = claim_out(body) else {
+            return;
+        };
+        let msg = MidiMsg::SysEx {
+            slot,
+            target: self.midi_out,
+            source: MidiEventSource::Local,
+        };
+        if let Err(TrySendError::Full((_, msg))) =
+            self.midi_sender.try_send((self.start_channel, msg))
+        {
+            msg.discard();
+        }
+    }
 }
 
 pub enum AppMidiEvent {
     Message(MidiMessage),
-    Nrpn { param: u16, value: u16 },
+    Nrpn {
+        param: u16,
+        value: u16,
+    },
+    Cc14 {
+        controller: u7,
+        value: u16,
+    },
+    /// SysEx without F0/F7, read the body with [`SysExIn::read`]. Not
+    /// filtered by channel.
+    #[allow(dead_code)]
+    SysEx(SysExIn),
 }
 
 pub struct MidiInput {
@@ -535,7 +745,8 @@ impl MidiInput {
         }
     }
 
-    /// Wait for any MIDI event (standard message or NRPN) on this channel.
+    /// Wait for any MIDI event (standard message, NRPN or 14-bit CC) on this
+    /// channel, or any SysEx message.
     pub async fn wait_for_event(&mut self) -> AppMidiEvent {
         loop {
             match self.next_event().await {
@@ -554,6 +765,17 @@ impl MidiInput {
                         value: scale_bits_14_12(value),
                     };
                 }
+                MidiEvent::Cc14 {
+                    channel,
+                    controller,
+                    value,
+                } if channel == self.midi_channel => {
+                    return AppMidiEvent::Cc14 {
+                        controller,
+                        value: scale_bits_14_12(value),
+                    };
+                }
+                MidiEvent::SysEx(sysex) => return AppMidiEvent::SysEx(sysex),
                 _ => {}
             }
         }
@@ -628,36 +850,80 @@ pub struct Quantizer {
     range: Range,
     vpo: VoltPerOct,
     bypass: bool,
+    scale: AppScale,
     state: RefCell<QuantizerState>,
 }
 
 impl Quantizer {
-    pub fn new(range: Range, vpo: VoltPerOct, bypass: bool) -> Self {
+    pub fn new(range: Range, vpo: VoltPerOct, bypass: bool, scale: AppScale) -> Self {
         Self {
             range,
             vpo,
             bypass,
+            scale,
             state: RefCell::new(QuantizerState::default()),
         }
     }
     /// Quantize a note.
     ///
-    /// Returns a `Pitch` with `raw` set when either the global scale is `Key::Off`
+    /// Returns a `Pitch` with `raw` set when either the app's scale is `Key::Off`
     /// or the per-app `bypass` flag is true — `as_counts()` will then return the
     /// original ADC value instead of the quantized voltage.
+    ///
+    /// With an `AppScale::Fixed` scale the note is quantized by the shared app
+    /// quantizer, which follows the global tuning and custom scales.
     pub async fn get_quantized_note(&self, value: u16) -> Pitch {
         let value = value.clamp(0, 4095);
-        let quantizer = QUANTIZER.get().lock().await;
-        let mut state = self.state.borrow_mut();
-        let mut pitch = quantizer.get_quantized_note(&mut state, value, self.range, self.vpo);
-        if self.bypass || quantizer.get_key() == Key::Off {
+        let (mut pitch, key) = self
+            .with_scale(|quantizer| {
+                let mut state = self.state.borrow_mut();
+                let pitch = quantizer.get_quantized_note(&mut state, value, self.range, self.vpo);
+                (pitch, quantizer.get_key())
+            })
+            .await;
+        if self.bypass || key == Key::Off {
             pitch.raw = Some(value);
         }
         pitch
     }
-    /// Get Quantizer scale
+    /// Voice `chord` on `root` in the app's scale, lowest voice first.
+    /// See `libfp::quantizer::Quantizer::harmonize`.
+    pub async fn harmonize(
+        &self,
+        root: &Pitch,
+        chord: Chord,
+        inversion: u8,
+        spread: u8,
+        voices: &mut [Pitch],
+    ) {
+        self.with_scale(|quantizer| quantizer.harmonize(root, chord, inversion, spread, voices))
+            .await;
+    }
+    /// Move `voices` to the nearest tones of `chord` on `root`.
+    /// See `libfp::quantizer::Quantizer::voice_lead`.
+    pub async fn voice_lead(&self, root: &Pitch, chord: Chord, voices: &mut [Pitch]) {
+        self.with_scale(|quantizer| quantizer.voice_lead(root, chord, voices))
+            .await;
+    }
+    /// Run `f` with the global quantizer, or the shared app quantizer set to
+    /// the app's scale when it overrides the global one
+    async fn with_scale<R>(&self, f: impl FnOnce(&ScaleQuantizer) -> R) -> R {
+        let quantizer = QUANTIZER.get().lock().await;
+        match self.scale {
+            AppScale::Global => f(&quantizer),
+            AppScale::Fixed { key, tonic } => {
+                let mut own = APP_QUANTIZER.get().lock().await;
+                own.follow(&quantizer, key, tonic);
+                f(&own)
+            }
+        }
+    }
+    /// Get Quantizer scale, the app's own one if it overrides the global scale
     #[allow(dead_code)]
     pub async fn get_scale(&self) -> (Key, Note) {
+        if let AppScale::Fixed { key, tonic } = self.scale {
+            return (key, tonic);
+        }
         let quantizer = QUANTIZER.get().lock().await;
         (quantizer.get_key(), quantizer.get_tonic())
     }
@@ -723,14 +989,15 @@ impl<const N: usize> App<N> {
         Global::new(initial)
     }
 
-    pub fn make_latch(&self, initial: u16) -> AnalogLatch {
-        let mode = get_global_config().takeover_mode;
-        AnalogLatch::new(initial, mode)
+    pub fn make_latch(&self, chan: usize, initial: u16) -> AppLatch {
+        let mode =
+            app_takeover_mode(self.layout_id).unwrap_or_else(|| get_global_config().takeover_mode);
+        self.make_latch_with_mode(chan, initial, mode)
     }
 
-    #[allow(dead_code)]
-    pub fn make_latch_with_mode(&self, initial: u16, mode: TakeoverMode) -> AnalogLatch {
-        AnalogLatch::new(initial, mode)
+    pub fn make_latch_with_mode(&self, chan: usize, initial: u16, mode: TakeoverMode) -> AppLatch {
+        let chan = chan.clamp(0, N - 1);
+        AppLatch::new(self.start_channel + chan, AnalogLatch::new(initial, mode))
     }
 
     pub async fn make_in_jack(&self, chan: usize, range: Range) -> InJack {
@@ -798,8 +1065,14 @@ impl<const N: usize> App<N> {
         Clock::new()
     }
 
-    pub fn use_quantizer(&self, range: Range, vpo: VoltPerOct, bypass: bool) -> Quantizer {
-        Quantizer::new(range, vpo, bypass)
+    pub fn use_quantizer(
+        &self,
+        range: Range,
+        vpo: VoltPerOct,
+        bypass: bool,
+        scale: AppScale,
+    ) -> Quantizer {
+        Quantizer::new(range, vpo, bypass, scale)
     }
 
     pub fn use_midi_input(&self, midi_in: MidiIn, midi_channel: MidiChannel) -> MidiInput {
@@ -126,6 +126,15 @@ pub async fn wrapper(app: App<CHANNELS>, exit_signal: &'static Signal<NoopRawMut
     select(app_loop, app.exit_handler(exit_signal)).await;
 }
 
+/// Button color of the curves the buttons cycle through
+fn curve_color(curve: Curve) -> Color {
+    match curve {
+        Curve::Logarithmic => Color::Cyan,
+        Curve::Exponential => Color::Pink,
+        _ => Color::Yellow,
+    }
+}
+
 pub async fn run(
     app: &App<CHANNELS>,
     params: &ParamStore<Params>,
@@ -165,13 +174,13 @@ pub async fn run(
         leds.set(
             0,
             Led::Button,
-            color[curve_setting[0] as usize],
+            curve_color(curve_setting[0]),
             Brightness::Mid,
         );
         leds.set(
             1,
             Led::Button,
-            color[curve_setting[1] as usize],
+            curve_color(curve_setting[1]),
             Brightness::Mid,
         );
     }
@@ -311,14 +320,9 @@ pub async fn run(
                     leds.unset(0, Led::Top);
                 }
             } else {
-                for n in 0..2 {
+                for (n, &curve) in curve_setting.iter().enumerate() {
                     if !glob_muted.get() {
-                        leds.set(
-                            n,
-                            Led::Button,
-                            color[curve_setting[n] as usize],
-                            Brightness::Mid,
-                        );
+                        leds.set(n, Led::Button, curve_color(curve), Brightness::Mid);
                     } else {
                         leds.unset(n, Led::Button);
                     }
@@ -343,8 +347,8 @@ pub async fn run(
 
     let fader_handler = async {
         let mut latch = [
-            app.make_latch(faders.get_value_at(0)),
-            app.make_latch(faders.get_value_at(1)),
+            app.make_latch(0, faders.get_value_at(0)),
+            app.make_latch(1, faders.get_value_at(1)),
         ];
 
         loop {
@@ -482,13 +486,13 @@ pub async fn run(
                         leds.set(
                             0,
                             Led::Button,
-                            color[curve_setting[0] as usize],
+                            curve_color(curve_setting[0]),
                             Brightness::Mid,
                         );
                         leds.set(
                             1,
                             Led::Button,
-                            color[curve_setting[1] as usize],
+                            curve_color(curve_setting[1]),
                             Brightness::Mid,
                         );
                     }
@@ -518,8 +522,8 @@ pub async fn run(
                     leds.unset(1, Led::Button);
                 } else {
                     let cs = storage.query(|s| s.curve_saved);
-                    leds.set(0, Led::Button, color[cs[0] as usize], Brightness::Mid);
-                    leds.set(1, Led::Button, color[cs[1] as usize], Brightness::Mid);
+                    leds.set(0, Led::Button, curve_color(cs[0]), Brightness::Mid);
+                    leds.set(1, Led::Button, curve_color(cs[1]), Brightness::Mid);
                 }
             }
         }
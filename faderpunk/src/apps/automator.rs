@@ -25,7 +25,7 @@ use crate::{
 };
 
 pub const CHANNELS: usize = 1;
-pub const PARAMS: usize = 7;
+pub const PARAMS: usize = 8;
 
 const MAX_BUFFER_SAMPLES: usize = 192;
 const CLOCK_TIMEOUT_MS: u64 = 500;
@@ -62,7 +62,8 @@ pub static CONFIG: Config<PARAMS> = Config::new(
 .add_param(Param::Enum {
     name: "Resolution",
     variants: &["24 ppqn / 2 bars", "12 ppqn / 4 bars", "6 ppqn / 8 bars"],
-});
+})
+.add_param(Param::MidiCc14);
 
 pub struct Params {
     midi_channel: MidiChannel,
@@ -70,6 +71,7 @@ pub struct Params {
     range: Range,
     color: Color,
     nrpn: bool,
+    cc14: bool,
     midi_out: MidiOut,
     resolution: usize,
 }
@@ -87,6 +89,7 @@ impl AppParams for Params {
             nrpn: bool::from_value(values[4]),
             midi_out: MidiOut::from_value(values[5]),
             resolution: usize::from_value(values[6]),
+            cc14: bool::from_value(values[7]),
         })
     }
 
@@ -99,6 +102,7 @@ impl AppParams for Params {
         vec.push(self.nrpn.into()).unwrap();
         vec.push(self.midi_out.into()).unwrap();
         vec.push(self.resolution.into()).unwrap();
+        vec.push(Value::MidiCc14(self.cc14)).unwrap();
         vec
     }
 }
@@ -170,6 +174,7 @@ pub async fn wrapper(app: App<CHANNELS>, exit_signal: &'static Signal<NoopRawMut
             range: Range::_0_10V,
             color: Color::Cyan,
             nrpn: false,
+            cc14: false,
             midi_out: MidiOut::default(),
             resolution: 1,
         },
@@ -198,17 +203,19 @@ pub async fn run(
     params: &ParamStore<Params>,
     storage: &ManagedStorage<Storage>,
 ) {
-    let (midi_out_cfg, midi_chan, midi_cc, range, nrpn, led_color, resolution) = params.query(|p| {
-        (
-            p.midi_out,
-            p.midi_channel,
-            p.midi_cc,
-            p.range,
-            p.nrpn,
-            p.color,
-            p.resolution,
-        )
-    });
+    let (midi_out_cfg, midi_chan, midi_cc, range, nrpn, cc14, led_color, resolution) =
+        params.query(|p| {
+            (
+                p.midi_out,
+                p.midi_channel,
+                p.midi_cc,
+                p.range,
+                p.nrpn,
+                p.cc14,
+                p.color,
+                p.resolution,
+            )
+        });
     let clock_div = match resolution {
         0 => ClockDivision::_1,
         2 => ClockDivision::_4,
@@ -219,7 +226,7 @@ pub async fn run(
     let fader = app.use_faders();
     let buttons = app.use_buttons();
     let leds = app.use_leds();
-    let midi = app.use_midi_output(midi_out_cfg, midi_chan, nrpn);
+    let midi = app.use_midi_output(midi_out_cfg, midi_chan, nrpn).with_cc14(cc14);
     let output = app.make_out_jack(0, range).await;
     let bipolar = range.is_bipolar();
 
@@ -500,7 +507,7 @@ pub async fn run(
     //     level in red (mirrors control.rs behaviour).
     //   • Button LED updates when clock state changes in Passthrough.
     let main_loop = async {
-        let mut latch = app.make_latch(fader.get_value());
+        let mut latch = app.make_latch(0, fader.get_value());
         let mut last_midi_scaled: u32 = u32::MAX;
         let mut prev_state = state_glob.get();
         let mut prev_clock_running = clock_running_glob.get();
@@ -560,7 +567,7 @@ pub async fn run(
                 // to the first loop sample is smooth instead of jumping from the stale
                 // seed (0 or old loop start) that clock_handler left in loop_prev_glob.
                 loop_prev_glob.set(prev_slew.value());
-                latch = app.make_latch(fader.get_value());
+                latch = app.make_latch(0, fader.get_value());
             }
 
             // ── CV + MIDI output ─────────────────────────────────────────────
@@ -571,7 +578,7 @@ pub async fn run(
                 | LooperState::PendingRecording
                 | LooperState::Recording => {
                     let fader_val = fader.get_value();
-                    let midi_val = midi_gate(fader_val, nrpn) as u32;
+                    let midi_val = midi_gate(fader_val, midi.is_high_res(midi_cc)) as u32;
                     if last_midi_scaled != midi_val {
                         midi.send_cc(midi_cc, fader_val).await;
                         last_midi_scaled = midi_val;
@@ -599,7 +606,7 @@ pub async fn run(
                     } else {
                         loop_target_glob.get()
                     };
-                    let midi_val = midi_gate(interp, nrpn) as u32;
+                    let midi_val = midi_gate(interp, midi.is_high_res(midi_cc)) as u32;
                     if last_midi_scaled != midi_val {
                         midi.send_cc(midi_cc, interp).await;
                         last_midi_scaled = midi_val;
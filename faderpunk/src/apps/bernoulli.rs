@@ -16,7 +16,7 @@ use crate::app::{
 };
 
 pub const CHANNELS: usize = 2;
-pub const PARAMS: usize = 6;
+pub const PARAMS: usize = 7;
 
 const LED_BRIGHTNESS: Brightness = Brightness::Mid;
 
@@ -49,7 +49,8 @@ pub static CONFIG: Config<PARAMS> = Config::new(
         Color::Yellow,
     ],
 })
-.add_param(Param::MidiOut);
+.add_param(Param::MidiOut)
+.add_param(Param::bool { name: "I2C gates" });
 
 pub struct Params {
     midi_channel: MidiChannel,
@@ -58,6 +59,7 @@ pub struct Params {
     midi_out: MidiOut,
     gatel: i32,
     color: Color,
+    i2c_gates: bool,
 }
 
 impl AppParams for Params {
@@ -72,6 +74,7 @@ impl AppParams for Params {
             gatel: i32::from_value(values[3]),
             color: Color::from_value(values[4]),
             midi_out: MidiOut::from_value(values[5]),
+            i2c_gates: bool::from_value(values[6]),
         })
     }
 
@@ -83,6 +86,7 @@ impl AppParams for Params {
         vec.push(self.gatel.into()).unwrap();
         vec.push(self.color.into()).unwrap();
         vec.push(self.midi_out.into()).unwrap();
+        vec.push(self.i2c_gates.into()).unwrap();
         vec
     }
 }
@@ -119,6 +123,7 @@ pub async fn wrapper(app: App<CHANNELS>, exit_signal: &'static Signal<NoopRawMut
             midi_out: MidiOut([false, false, false]),
             gatel: 50,
             color: Color::Cyan,
+            i2c_gates: false,
         },
     );
 
@@ -146,7 +151,7 @@ pub async fn run(
     params: &ParamStore<Params>,
     storage: &ManagedStorage<Storage>,
 ) {
-    let (midi_out, midi_chan, note_a, note_b, gatel, led_color) = params.query(|p| {
+    let (midi_out, midi_chan, note_a, note_b, gatel, led_color, i2c_gates) = params.query(|p| {
         (
             p.midi_out,
             p.midi_channel,
@@ -154,6 +159,7 @@ pub async fn run(
             p.note_b,
             p.gatel as u32,
             p.color,
+            p.i2c_gates,
         )
     });
 
@@ -172,8 +178,9 @@ pub async fn run(
     let div_glob = app.make_global(6);
     let prob_glob = app.make_global(2048_u16);
 
-    let jack_a = app.make_gate_jack(0, 4095).await;
-    let jack_b = app.make_gate_jack(1, 4095).await;
+    let i2c = app.use_i2c_output();
+    let jack_a = app.make_gate_jack(0, 4095).await.mirror_i2c(&i2c, i2c_gates);
+    let jack_b = app.make_gate_jack(1, 4095).await.mirror_i2c(&i2c, i2c_gates);
 
     // make_gate_jack drives the port high on configure; force a known-off
     // state (also clears any note left sounding by a prior run() that was
@@ -341,8 +348,8 @@ pub async fn run(
 
     let fut_faders = async {
         let mut latch = [
-            app.make_latch(faders.get_value_at(0)),
-            app.make_latch(faders.get_value_at(1)),
+            app.make_latch(0, faders.get_value_at(0)),
+            app.make_latch(1, faders.get_value_at(1)),
         ];
 
         loop {
@@ -0,0 +1,328 @@
+use embassy_futures::{
+    join::join4,
+    select::{select, select3},
+};
+use embassy_sync::{blocking_mutex::raw::NoopRawMutex, signal::Signal};
+use heapless::Vec;
+use serde::{Deserialize, Serialize};
+
+use libfp::{
+    ext::FromValue,
+    latch::LatchLayer,
+    quantizer::{Chord, Pitch, MAX_CHORD_VOICES},
+    utils::split_unsigned_value,
+    AppIcon, AppScale, Brightness, Color, Config, MidiChannel, MidiNote, MidiOut, Param, Range,
+    Value, VoltPerOct, APP_MAX_PARAMS,
+};
+
+use crate::app::{
+    pitch_as_counts, vpo_counts_per_oct, App, AppParams, AppStorage, Led, ManagedStorage,
+    ParamStore, SceneEvent,
+};
+
+pub const CHANNELS: usize = 5;
+pub const PARAMS: usize = 6;
+
+const VOICES: usize = MAX_CHORD_VOICES;
+const LED_BRIGHTNESS: Brightness = Brightness::Mid;
+
+pub static CONFIG: Config<PARAMS> = Config::new(
+    "Chord",
+    "Harmonize one CV into four diatonic voices",
+    Color::Cyan,
+    AppIcon::NoteGrid,
+)
+.add_param(Param::MidiChannel {
+    name: "MIDI Channel",
+})
+.add_param(Param::MidiOut)
+.add_param(Param::Color {
+    name: "Color",
+    variants: &[
+        Color::Blue,
+        Color::Green,
+        Color::Rose,
+        Color::Orange,
+        Color::Cyan,
+        Color::Pink,
+        Color::Violet,
+        Color::Yellow,
+    ],
+})
+.add_param(Param::Range {
+    name: "Range",
+    variants: &[Range::_0_10V, Range::_Neg5_5V],
+})
+.add_param(Param::VoltPerOct)
+.add_param(Param::Scale);
+
+pub struct Params {
+    midi_channel: MidiChannel,
+    midi_out: MidiOut,
+    color: Color,
+    range: Range,
+    vpo: VoltPerOct,
+    scale: AppScale,
+}
+
+impl AppParams for Params {
+    fn from_values(values: &[Value]) -> Option<Self> {
+        if values.len() < PARAMS {
+            return None;
+        }
+        Some(Self {
+            midi_channel: MidiChannel::from_value(values[0]),
+            midi_out: MidiOut::from_value(values[1]),
+            color: Color::from_value(values[2]),
+            range: Range::from_value(values[3]),
+            vpo: VoltPerOct::from_value(values[4]),
+            scale: AppScale::from_value(values[5]),
+        })
+    }
+
+    fn to_values(&self) -> Vec<Value, APP_MAX_PARAMS> {
+        let mut vec = Vec::new();
+        vec.push(self.midi_channel.into()).unwrap();
+        vec.push(self.midi_out.into()).unwrap();
+        vec.push(self.color.into()).unwrap();
+        vec.push(self.range.into()).unwrap();
+        vec.push(self.vpo.into()).unwrap();
+        vec.push(self.scale.into()).unwrap();
+        vec
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Storage {
+    /// Chord, inversion, spread, strum and octave
+    faders: [u16; CHANNELS],
+    voice_leading: bool,
+    muted: [bool; VOICES],
+}
+
+impl Default for Storage {
+    fn default() -> Self {
+        Self {
+            faders: [0, 0, 0, 0, 2047],
+            voice_leading: false,
+            muted: [false; VOICES],
+        }
+    }
+}
+
+impl AppStorage for Storage {}
+
+#[embassy_executor::task(pool_size = 16/CHANNELS)]
+pub async fn wrapper(app: App<CHANNELS>, exit_signal: &'static Signal<NoopRawMutex, bool>) {
+    let param_store = ParamStore::<Params>::new(
+        app.app_id,
+        app.layout_id,
+        Params {
+            midi_channel: MidiChannel::default(),
+            midi_out: MidiOut::default(),
+            color: Color::Cyan,
+            range: Range::_0_10V,
+            vpo: VoltPerOct::Standard,
+            scale: AppScale::Global,
+        },
+    );
+    let storage = ManagedStorage::<Storage>::new(app.app_id, app.layout_id);
+
+    param_store.load().await;
+    storage.load().await;
+
+    let app_loop = async {
+        loop {
+            select3(
+                run(&app, &param_store, &storage),
+                param_store.param_handler(),
+                storage.saver_task(),
+            )
+            .await;
+        }
+    };
+
+    select(app_loop, app.exit_handler(exit_signal)).await;
+}
+
+pub async fn run(
+    app: &App<CHANNELS>,
+    params: &ParamStore<Params>,
+    storage: &ManagedStorage<Storage>,
+) {
+    let (midi_chan, midi_out, led_color, range, vpo, scale) =
+        params.query(|p| (p.midi_channel, p.midi_out, p.color, p.range, p.vpo, p.scale));
+    let buttons = app.use_buttons();
+    let faders = app.use_faders();
+    let leds = app.use_leds();
+    let midi = app.use_midi_output(midi_out, midi_chan, false);
+
+    let quantizer = app.use_quantizer(range, vpo, false, scale);
+    let counts_per_oct = vpo_counts_per_oct(vpo) as i32;
+    let input = app.make_in_jack(0, range).await;
+    let outputs = [
+        app.make_out_jack(1, range).await,
+        app.make_out_jack(2, range).await,
+        app.make_out_jack(3, range).await,
+        app.make_out_jack(4, range).await,
+    ];
+
+    // Set when the voices have to be written out even if the chord is unchanged
+    let glob_refresh = app.make_global(true);
+
+    let set_button_leds = || {
+        let (voice_leading, muted) = storage.query(|s| (s.voice_leading, s.muted));
+        if voice_leading {
+            leds.set(0, Led::Button, led_color, LED_BRIGHTNESS);
+        } else {
+            leds.unset(0, Led::Button);
+        }
+        for (voice, muted) in muted.iter().enumerate() {
+            if *muted {
+                leds.unset(voice + 1, Led::Button);
+            } else {
+                leds.set(voice + 1, Led::Button, led_color, LED_BRIGHTNESS);
+            }
+        }
+    };
+
+    set_button_leds();
+
+    let main_loop = async {
+        let mut voices = [Pitch::default(); VOICES];
+        let mut last = None;
+        let mut notes: [Option<MidiNote>; VOICES] = [None; VOICES];
+
+        loop {
+            app.delay_millis(1).await;
+
+            let [chord_val, inversion_val, spread_val, strum_val, oct_val] =
+                storage.query(|s| s.faders);
+            let oct = (oct_val as i32 * 5 / 4096 - 2) * counts_per_oct;
+            let inval = (input.get_value() as i32 + oct).clamp(0, 4095) as u16;
+            // The chord is built from scale degrees, so the input is always quantized
+            let root = Pitch {
+                raw: None,
+                ..quantizer.get_quantized_note(inval).await
+            };
+            let chord = Chord::ALL[chord_val as usize * Chord::ALL.len() / 4096];
+            let inversion = (inversion_val as usize * VOICES / 4096) as u8;
+            let spread = (spread_val as usize * 4 / 4096) as u8;
+
+            let current = Some((root, chord, inversion, spread));
+            let refresh = glob_refresh.get();
+            if current == last && !refresh {
+                continue;
+            }
+            let voice_lead = storage.query(|s| s.voice_leading) && last.is_some();
+            last = current;
+            glob_refresh.set(false);
+
+            if voice_lead {
+                quantizer.voice_lead(&root, chord, &mut voices).await;
+            } else {
+                quantizer
+                    .harmonize(&root, chord, inversion, spread, &mut voices)
+                    .await;
+            }
+
+            let root_led = split_unsigned_value(pitch_as_counts(root, range, vpo));
+            leds.set(0, Led::Top, led_color, Brightness::Custom(root_led[0]));
+            leds.set(0, Led::Bottom, led_color, Brightness::Custom(root_led[1]));
+
+            let strum = strum_val as u64 * 100 / 4095;
+            let muted = storage.query(|s| s.muted);
+            for (i, voice) in voices.iter().enumerate() {
+                if muted[i] {
+                    if let Some(note) = notes[i].take() {
+                        midi.send_note_off(note).await;
+                    }
+                    leds.unset(i + 1, Led::Top);
+                    leds.unset(i + 1, Led::Bottom);
+                    continue;
+                }
+                if i > 0 && strum > 0 {
+                    app.delay_millis(strum).await;
+                }
+                let counts = pitch_as_counts(*voice, range, vpo);
+                outputs[i].set_pitch_value(counts);
+                let led = split_unsigned_value(counts);
+                leds.set(i + 1, Led::Top, led_color, Brightness::Custom(led[0]));
+                leds.set(i + 1, Led::Bottom, led_color, Brightness::Custom(led[1]));
+
+                // Held notes keep sounding, only the voices that moved retrigger
+                let note = voice.as_midi();
+                if notes[i] != Some(note) {
+                    if let Some(old) = notes[i] {
+                        midi.send_note_off(old).await;
+                    }
+                    midi.send_pitch_note_on(note, voice, 4095).await;
+                    notes[i] = Some(note);
+                }
+            }
+        }
+    };
+
+    let button_handler = async {
+        loop {
+            let (chan, is_shift_pressed) = buttons.wait_for_any_down().await;
+            if is_shift_pressed {
+                continue;
+            }
+            storage.modify_and_save(|s| {
+                if chan == 0 {
+                    s.voice_leading = !s.voice_leading;
+                } else {
+                    s.muted[chan - 1] = !s.muted[chan - 1];
+                }
+            });
+            set_button_leds();
+            glob_refresh.set(true);
+        }
+    };
+
+    let fader_event_handler = async {
+        let mut latch = [
+            app.make_latch(0, faders.get_value_at(0)),
+            app.make_latch(1, faders.get_value_at(1)),
+            app.make_latch(2, faders.get_value_at(2)),
+            app.make_latch(3, faders.get_value_at(3)),
+            app.make_latch(4, faders.get_value_at(4)),
+        ];
+
+        loop {
+            let chan = faders.wait_for_any_change().await;
+            let latch_layer = LatchLayer::Main;
+            let target_value = storage.query(|s| s.faders[chan]);
+
+            if let Some(new_value) =
+                latch[chan].update(faders.get_value_at(chan), latch_layer, target_value)
+            {
+                storage.modify_and_save(|s| {
+                    s.faders[chan] = new_value;
+                });
+            }
+        }
+    };
+
+    let scene_handler = async {
+        loop {
+            match app.wait_for_scene_event().await {
+                SceneEvent::LoadScene(scene) => {
+                    storage.load_from_scene(scene).await;
+                    set_button_leds();
+                    glob_refresh.set(true);
+                }
+                SceneEvent::SaveScene(scene) => storage.save_to_scene(scene).await,
+            }
+        }
+    };
+
+    join4(
+        main_loop,
+        button_handler,
+        fader_event_handler,
+        scene_handler,
+    )
+    .await;
+}
@@ -19,7 +19,7 @@ use crate::app::{
 };
 
 pub const CHANNELS: usize = 1;
-pub const PARAMS: usize = 6;
+pub const PARAMS: usize = 7;
 
 const LED_BRIGHTNESS: Brightness = Brightness::Mid;
 
@@ -55,7 +55,8 @@ pub static CONFIG: Config<PARAMS> = Config::new(
         Color::Yellow,
     ],
 })
-.add_param(Param::MidiOut);
+.add_param(Param::MidiOut)
+.add_param(Param::bool { name: "I2C gates" });
 
 pub struct Params {
     midi_channel: MidiChannel,
@@ -64,6 +65,7 @@ pub struct Params {
     gatel: i32,
     division_mode: usize,
     color: Color,
+    i2c_gates: bool,
 }
 
 impl AppParams for Params {
@@ -79,6 +81,7 @@ impl AppParams for Params {
             division_mode: usize::from_value(values[3]),
             color: Color::from_value(values[4]),
             midi_out: MidiOut::from_value(values[5]),
+            i2c_gates: bool::from_value(values[6]),
         })
     }
 
@@ -90,6 +93,7 @@ impl AppParams for Params {
         vec.push(self.division_mode.into()).unwrap();
         vec.push(self.color.into()).unwrap();
         vec.push(self.midi_out.into()).unwrap();
+        vec.push(self.i2c_gates.into()).unwrap();
         vec
     }
 }
@@ -126,6 +130,7 @@ pub async fn wrapper(app: App<CHANNELS>, exit_signal: &'static Signal<NoopRawMut
             gatel: 50,
             division_mode: 2,
             color: Color::Cyan,
+            i2c_gates: false,
         },
     );
     let storage = ManagedStorage::<Storage>::new(app.app_id, app.layout_id);
@@ -152,7 +157,7 @@ pub async fn run(
     params: &ParamStore<Params>,
     storage: &ManagedStorage<Storage>,
 ) {
-    let (midi_out, midi_chan, note, gatel, division_mode, led_color) = params.query(|p| {
+    let (midi_out, midi_chan, note, gatel, division_mode, led_color, i2c_gates) = params.query(|p| {
         (
             p.midi_out,
             p.midi_channel,
@@ -160,6 +165,7 @@ pub async fn run(
             p.gatel as u32,
             p.division_mode,
             p.color,
+            p.i2c_gates,
         )
     });
 
@@ -177,7 +183,8 @@ pub async fn run(
     let min_glob = app.make_global(6_u32);
     let glob_latch_layer = app.make_global(LatchLayer::Main);
 
-    let jack = app.make_gate_jack(0, 4095).await;
+    let i2c = app.use_i2c_output();
+    let jack = app.make_gate_jack(0, 4095).await.mirror_i2c(&i2c, i2c_gates);
 
     let resolution = resolution_for_mode(division_mode);
 
@@ -305,7 +312,7 @@ pub async fn run(
     };
 
     let fut3 = async {
-        let mut latch = app.make_latch(fader.get_value());
+        let mut latch = app.make_latch(0, fader.get_value());
         loop {
             fader.wait_for_change_at(0).await;
 
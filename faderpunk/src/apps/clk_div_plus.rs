@@ -339,8 +339,8 @@ pub async fn run(
 
     let fader_handler = async {
         let mut latch = [
-            app.make_latch(fader.get_value_at(0)),
-            app.make_latch(fader.get_value_at(1)),
+            app.make_latch(0, fader.get_value_at(0)),
+            app.make_latch(1, fader.get_value_at(1)),
         ];
 
         loop {
@@ -13,14 +13,14 @@ use libfp::{
     ext::FromValue,
     latch::LatchLayer,
     utils::{rotate_select_bit, scale_to_12bit},
-    AppIcon, Brightness, Color, Config, Curve, MidiCc, MidiChannel, MidiMode, MidiNote, MidiOut,
-    Param, Range, Value, VoltPerOct, APP_MAX_PARAMS,
+    AppIcon, AppScale, Brightness, Color, Config, Curve, MidiCc, MidiChannel, MidiMode, MidiNote,
+    MidiOut, Param, Range, Value, VoltPerOct, APP_MAX_PARAMS,
 };
 
 use crate::app::{pitch_as_counts, App, AppParams, AppStorage, Led, ManagedStorage, ParamStore, SceneEvent};
 
 pub const CHANNELS: usize = 2;
-pub const PARAMS: usize = 10;
+pub const PARAMS: usize = 11;
 
 pub static CONFIG: Config<PARAMS> = Config::new(
     "Turing+",
@@ -54,7 +54,8 @@ pub static CONFIG: Config<PARAMS> = Config::new(
 .add_param(Param::MidiNrpn)
 .add_param(Param::MidiOut)
 .add_param(Param::VoltPerOct)
-.add_param(Param::bool { name: "Bypass quantizer" });
+.add_param(Param::bool { name: "Bypass quantizer" })
+.add_param(Param::Scale);
 
 pub struct Params {
     midi_channel: MidiChannel,
@@ -67,6 +68,7 @@ pub struct Params {
     nrpn: bool,
     vpo: VoltPerOct,
     bypass: bool,
+    scale: AppScale,
 }
 
 impl AppParams for Params {
@@ -85,6 +87,7 @@ impl AppParams for Params {
             midi_out: MidiOut::from_value(values[7]),
             vpo: VoltPerOct::from_value(values[8]),
             bypass: bool::from_value(values[9]),
+            scale: AppScale::from_value(values[10]),
         })
     }
 
@@ -100,6 +103,7 @@ impl AppParams for Params {
         vec.push(self.midi_out.into()).unwrap();
         vec.push(self.vpo.into()).unwrap();
         vec.push(self.bypass.into()).unwrap();
+        vec.push(self.scale.into()).unwrap();
         vec
     }
 }
@@ -137,6 +141,7 @@ pub async fn wrapper(app: App<CHANNELS>, exit_signal: &'static Signal<NoopRawMut
         nrpn: false,
         vpo: VoltPerOct::Standard,
         bypass: false,
+        scale: AppScale::Global,
     });
     let storage = ManagedStorage::<Storage>::new(app.app_id, app.layout_id);
 
@@ -195,7 +200,9 @@ pub async fn run(
     let recall_flag = app.make_global(false);
     let midi_note = app.make_global(MidiNote::from(0));
 
-    let quantizer = app.use_quantizer(range, vpo, bypass);
+    let scale = params.query(|p| p.scale);
+
+    let quantizer = app.use_quantizer(range, vpo, bypass, scale);
 
     let glob_muted = app.make_global(storage.query(|s| s.muted));
     let long_press_fired = app.make_global(false);
@@ -247,7 +254,7 @@ pub async fn run(
 
                 let muted = glob_muted.get();
                 if !muted {
-                    output.set_value(pitch_as_counts(out, range, vpo));
+                    output.set_pitch_value(pitch_as_counts(out, range, vpo));
                     leds.set(
                         0,
                         Led::Top,
@@ -263,7 +270,7 @@ pub async fn run(
 
                     if let MidiMode::Note = midi_mode {
                         let note = base_note + out.as_midi();
-                        midi.send_note_on(note, 4095).await;
+                        midi.send_pitch_note_on(note, &out, 4095).await;
                         midi_note.set(note);
                     }
 
@@ -291,8 +298,8 @@ pub async fn run(
 
     let fut2 = async {
         let mut latch = [
-            app.make_latch(faders.get_value_at(0)),
-            app.make_latch(faders.get_value_at(1)),
+            app.make_latch(0, faders.get_value_at(0)),
+            app.make_latch(1, faders.get_value_at(1)),
         ];
         // faders handling
         loop {
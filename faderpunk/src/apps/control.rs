@@ -20,7 +20,7 @@ use libfp::{Config, Curve, Param, Range, Value};
 use crate::app::{App, AppParams, AppStorage, Led, ManagedStorage, ParamStore, SceneEvent};
 
 pub const CHANNELS: usize = 1;
-pub const PARAMS: usize = 13;
+pub const PARAMS: usize = 14;
 
 pub static CONFIG: Config<PARAMS> = Config::new(
     "Control",
@@ -69,7 +69,8 @@ pub static CONFIG: Config<PARAMS> = Config::new(
 })
 .add_param(Param::MidiCc { name: "Button CC / PC" })
 .add_param(Param::MidiNrpn)
-.add_param(Param::MidiOut);
+.add_param(Param::MidiOut)
+.add_param(Param::MidiCc14);
 
 pub struct Params {
     curve: Curve,
@@ -85,6 +86,7 @@ pub struct Params {
     button_ch: MidiChannel,
     button_cc: MidiCc,
     nrpn: bool,
+    cc14: bool,
 }
 
 impl AppParams for Params {
@@ -106,6 +108,7 @@ impl AppParams for Params {
             button_cc: MidiCc::from_value(values[10]),
             nrpn: bool::from_value(values[11]),
             midi_out: MidiOut::from_value(values[12]),
+            cc14: bool::from_value(values[13]),
         })
     }
 
@@ -124,6 +127,7 @@ impl AppParams for Params {
         vec.push(self.button_cc.into()).unwrap();
         vec.push(Value::MidiNrpn(self.nrpn)).unwrap();
         vec.push(self.midi_out.into()).unwrap();
+        vec.push(Value::MidiCc14(self.cc14)).unwrap();
         vec
     }
 }
@@ -167,6 +171,7 @@ pub async fn wrapper(app: App<CHANNELS>, exit_signal: &'static Signal<NoopRawMut
             button_ch: MidiChannel::default(),
             button_cc: MidiCc::from(48u8.saturating_add(ch)),
             nrpn: false,
+            cc14: false,
         },
     );
     let storage = ManagedStorage::<Storage>::new(app.app_id, app.layout_id);
@@ -207,6 +212,7 @@ pub async fn run(
         button_ch,
         button_cc,
         nrpn,
+        cc14,
     ) = params.query(|p| {
         (
             p.curve,
@@ -222,14 +228,15 @@ pub async fn run(
             p.button_ch,
             p.button_cc,
             p.nrpn,
+            p.cc14,
         )
     });
 
     let buttons = app.use_buttons();
     let fader = app.use_faders();
     let leds = app.use_leds();
-    let midi = app.use_midi_output(midi_out, midi_chan, nrpn);
-    // Button output never uses NRPN — hardcoded false regardless of the nrpn param
+    let midi = app.use_midi_output(midi_out, midi_chan, nrpn).with_cc14(cc14);
+    // Button output never uses NRPN or 14-bit CC — hardcoded regardless of the params
     let midi_button = app.use_midi_output(midi_out, button_ch, false);
     let i2c = app.use_i2c_output();
 
@@ -254,7 +261,7 @@ pub async fn run(
     };
 
     let main_loop = async {
-        let mut latch = app.make_latch(fader.get_value());
+        let mut latch = app.make_latch(0, fader.get_value());
         let mut main_layer_value = fader.get_value();
         let mut fad_val = 0;
         let mut out = SlewState::new();
@@ -342,7 +349,7 @@ pub async fn run(
             } else {
                 attenuate_bipolar(main_layer_value, att_layer_value)
             };
-            let midi_val = midi_gate(midi_out, nrpn) as u32;
+            let midi_val = midi_gate(midi_out, midi.is_high_res(midi_cc)) as u32;
             if last_midi != midi_val {
                 midi.send_cc(midi_cc, midi_out).await;
                 last_midi = midi_val;
@@ -248,7 +248,7 @@ pub async fn run(
         }
     };
     let fut3 = async {
-        let mut latch = app.make_latch(fader.get_value());
+        let mut latch = app.make_latch(0, fader.get_value());
         loop {
             fader.wait_for_change().await;
 
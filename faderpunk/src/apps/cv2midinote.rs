@@ -5,8 +5,8 @@ use embassy_futures::{
 use embassy_sync::{blocking_mutex::raw::NoopRawMutex, signal::Signal};
 use heapless::Vec;
 use libfp::{
-    latch::LatchLayer, utils::split_unsigned_value, AppIcon, Brightness, Color, MidiChannel,
-    MidiNote, MidiOut, APP_MAX_PARAMS,
+    latch::LatchLayer, utils::split_unsigned_value, AppIcon, AppScale, Brightness, Color,
+    MidiChannel, MidiNote, MidiOut, APP_MAX_PARAMS,
 };
 use serde::{Deserialize, Serialize};
 use libm;
@@ -16,7 +16,7 @@ use libfp::{ext::FromValue, Config, Param, Range, Value, VoltPerOct};
 use crate::app::{App, AppParams, AppStorage, Led, ManagedStorage, ParamStore, SceneEvent};
 
 pub const CHANNELS: usize = 2;
-pub const PARAMS: usize = 7;
+pub const PARAMS: usize = 8;
 
 const BUTTON_BRIGHTNESS: Brightness = Brightness::Mid;
 
@@ -53,7 +53,8 @@ pub static CONFIG: Config<PARAMS> = Config::new(
 })
 .add_param(Param::MidiOut)
 .add_param(Param::VoltPerOct)
-.add_param(Param::bool { name: "Bypass quantizer" });
+.add_param(Param::bool { name: "Bypass quantizer" })
+.add_param(Param::Scale);
 
 pub struct Params {
     range: Range,
@@ -63,6 +64,7 @@ pub struct Params {
     color: Color,
     vpo: VoltPerOct,
     bypass: bool,
+    scale: AppScale,
 }
 
 impl AppParams for Params {
@@ -78,6 +80,7 @@ impl AppParams for Params {
             midi_out: MidiOut::from_value(values[4]),
             vpo: VoltPerOct::from_value(values[5]),
             bypass: bool::from_value(values[6]),
+            scale: AppScale::from_value(values[7]),
         })
     }
 
@@ -90,6 +93,7 @@ impl AppParams for Params {
         vec.push(self.midi_out.into()).unwrap();
         vec.push(self.vpo.into()).unwrap();
         vec.push(self.bypass.into()).unwrap();
+        vec.push(self.scale.into()).unwrap();
         vec
     }
 }
@@ -123,6 +127,7 @@ pub async fn wrapper(app: App<CHANNELS>, exit_signal: &'static Signal<NoopRawMut
         color: Color::Orange,
         vpo: VoltPerOct::Standard,
         bypass: false,
+        scale: AppScale::Global,
     });
     let storage = ManagedStorage::<Storage>::new(app.app_id, app.layout_id);
 
@@ -170,7 +175,8 @@ pub async fn run(
         leds.set(0, Led::Button, led_color, BUTTON_BRIGHTNESS);
     }
     let input = app.make_in_jack(0, range).await;
-    let quantizer = app.use_quantizer(range, vpo, false);
+    let scale = params.query(|p| p.scale);
+    let quantizer = app.use_quantizer(range, vpo, false, scale);
     let counts_per_oct = vpo.counts_per_oct() as i32;
 
     let gate_in = app.make_in_jack(1, Range::_0_10V).await;
@@ -207,6 +213,7 @@ pub async fn run(
                     };
                     note = (note + oct + st).clamp(0, 4095);
 
+                    let mut pitch = None;
                     midi_out = if bypass {
                         // Chromatic bypass: quantize to nearest semitone regardless of global scale.
                         // Mirrors the quantizer's internal voltage→semitone conversion.
@@ -219,10 +226,15 @@ pub async fn run(
                             libm::roundf(voltage * vpo.semitones_per_volt()) as i32;
                         MidiNote::from(semitone.clamp(0, 127))
                     } else {
-                        quantizer.get_quantized_note(note as u16).await.as_midi()
+                        let out = quantizer.get_quantized_note(note as u16).await;
+                        pitch = Some(out);
+                        out.as_midi()
                     };
 
-                    midi.send_note_on(midi_out, 4095).await;
+                    match pitch {
+                        Some(pitch) => midi.send_pitch_note_on(midi_out, &pitch, 4095).await,
+                        None => midi.send_note_on(midi_out, 4095).await,
+                    }
                     note_on = true;
                     leds.set(1, Led::Button, led_color, Brightness::High);
                 }
@@ -285,8 +297,8 @@ pub async fn run(
     };
     let fut3 = async {
         let mut latch = [
-            app.make_latch(faders.get_value_at(0)),
-            app.make_latch(faders.get_value_at(1)),
+            app.make_latch(0, faders.get_value_at(0)),
+            app.make_latch(1, faders.get_value_at(1)),
         ];
 
         loop {
@@ -19,7 +19,7 @@ use crate::app::{
 };
 
 pub const CHANNELS: usize = 2;
-pub const PARAMS: usize = 6;
+pub const PARAMS: usize = 7;
 
 const LED_BRIGHTNESS: Brightness = Brightness::Mid;
 
@@ -56,7 +56,8 @@ pub static CONFIG: Config<PARAMS> = Config::new(
         Color::Yellow,
     ],
 })
-.add_param(Param::MidiOut);
+.add_param(Param::MidiOut)
+.add_param(Param::bool { name: "I2C gates" });
 
 pub struct Params {
     midi_channel: MidiChannel,
@@ -65,6 +66,7 @@ pub struct Params {
     note2: MidiNote,
     gatel: i32,
     color: Color,
+    i2c_gates: bool,
 }
 
 impl AppParams for Params {
@@ -79,6 +81,7 @@ impl AppParams for Params {
             gatel: i32::from_value(values[3]),
             color: Color::from_value(values[4]),
             midi_out: MidiOut::from_value(values[5]),
+            i2c_gates: bool::from_value(values[6]),
         })
     }
 
@@ -90,6 +93,7 @@ impl AppParams for Params {
         vec.push(self.gatel.into()).unwrap();
         vec.push(self.color.into()).unwrap();
         vec.push(self.midi_out.into()).unwrap();
+        vec.push(self.i2c_gates.into()).unwrap();
         vec
     }
 }
@@ -128,6 +132,7 @@ pub async fn wrapper(app: App<CHANNELS>, exit_signal: &'static Signal<NoopRawMut
             note2: MidiNote::from(33),
             gatel: 50,
             color: Color::Orange,
+            i2c_gates: false,
         },
     );
     let storage = ManagedStorage::<Storage>::new(app.app_id, app.layout_id);
@@ -160,7 +165,7 @@ pub async fn run(
     let buttons = app.use_buttons();
     let leds = app.use_leds();
 
-    let (midi_out, midi_chan, note, note2, gatel, led_color) = params.query(|p| {
+    let (midi_out, midi_chan, note, note2, gatel, led_color, i2c_gates) = params.query(|p| {
         (
             p.midi_out,
             p.midi_channel,
@@ -168,6 +173,7 @@ pub async fn run(
             p.note2,
             p.gatel,
             p.color,
+            p.i2c_gates,
         )
     });
 
@@ -181,9 +187,10 @@ pub async fn run(
 
     let glob_latch_layer = app.make_global(LatchLayer::Main);
 
+    let i2c = app.use_i2c_output();
     let jack = [
-        app.make_gate_jack(0, 4095).await,
-        app.make_gate_jack(1, 4095).await,
+        app.make_gate_jack(0, 4095).await.mirror_i2c(&i2c, i2c_gates),
+        app.make_gate_jack(1, 4095).await.mirror_i2c(&i2c, i2c_gates),
     ];
 
     let resolution = [384, 192, 96, 48, 24, 16, 12, 8, 6, 4, 3, 2];
@@ -360,8 +367,8 @@ pub async fn run(
 
     let fut3 = async {
         let mut latch = [
-            app.make_latch(faders.get_value_at(0)),
-            app.make_latch(faders.get_value_at(1)),
+            app.make_latch(0, faders.get_value_at(0)),
+            app.make_latch(1, faders.get_value_at(1)),
         ];
         loop {
             let chan = faders.wait_for_any_change().await;
@@ -680,10 +680,10 @@ pub async fn run(
 
     let fader_fut = async {
         let mut latch = [
-            app.make_latch(faders.get_value_at(0)),
-            app.make_latch(faders.get_value_at(1)),
-            app.make_latch(faders.get_value_at(2)),
-            app.make_latch(faders.get_value_at(3)),
+            app.make_latch(0, faders.get_value_at(0)),
+            app.make_latch(1, faders.get_value_at(1)),
+            app.make_latch(2, faders.get_value_at(2)),
+            app.make_latch(3, faders.get_value_at(3)),
         ];
         loop {
             let chan = faders.wait_for_any_change().await;
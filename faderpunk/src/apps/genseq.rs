@@ -11,8 +11,8 @@ use libfp::{
     latch::LatchLayer,
     quantizer::Pitch,
     utils::{attenuate, euclidean_at, rotate_select_bit, scale_to_12bit},
-    AppIcon, Brightness, ClockDivision, Color, Config, Curve, MidiChannel, MidiNote, MidiOut,
-    Param, Range, Value, VoltPerOct, APP_MAX_PARAMS,
+    AppIcon, AppScale, Brightness, ClockDivision, Color, Config, Curve, MidiChannel, MidiNote,
+    MidiOut, Param, Range, Value, VoltPerOct, APP_MAX_PARAMS,
 };
 use midly::num::u7;
 
@@ -22,7 +22,7 @@ use crate::app::{
 };
 
 pub const CHANNELS: usize = 3;
-pub const PARAMS: usize = 6;
+pub const PARAMS: usize = 7;
 
 /// LED colors for the 5 octave shift steps -2..+2 (F1 Third layer)
 const OCT_COLORS: [Color; 5] = [
@@ -60,7 +60,8 @@ pub static CONFIG: Config<PARAMS> = Config::new(
 .add_param(Param::VoltPerOct)
 .add_param(Param::bool {
     name: "Bypass quantizer",
-});
+})
+.add_param(Param::Scale);
 
 pub struct Params {
     midi_channel: MidiChannel,
@@ -69,6 +70,7 @@ pub struct Params {
     midi_out: MidiOut,
     vpo: VoltPerOct,
     bypass: bool,
+    scale: AppScale,
 }
 
 impl Default for Params {
@@ -80,6 +82,7 @@ impl Default for Params {
             midi_out: MidiOut::default(),
             vpo: VoltPerOct::Standard,
             bypass: false,
+            scale: AppScale::Global,
         }
     }
 }
@@ -96,6 +99,7 @@ impl AppParams for Params {
             midi_out: MidiOut::from_value(values[3]),
             vpo: VoltPerOct::from_value(values[4]),
             bypass: bool::from_value(values[5]),
+            scale: AppScale::from_value(values[6]),
         })
     }
 
@@ -107,6 +111,7 @@ impl AppParams for Params {
         vec.push(self.midi_out.into()).unwrap();
         vec.push(self.vpo.into()).unwrap();
         vec.push(self.bypass.into()).unwrap();
+        vec.push(self.scale.into()).unwrap();
         vec
     }
 }
@@ -204,7 +209,8 @@ pub async fn run(
     let leds = app.use_leds();
     let mut clock = app.use_clock();
     let die = app.use_die();
-    let quantizer = app.use_quantizer(Range::_0_10V, vpo, bypass);
+    let scale = params.query(|p| p.scale);
+    let quantizer = app.use_quantizer(Range::_0_10V, vpo, bypass, scale);
     let midi = app.use_midi_output(midi_out, midi_chan, false);
 
     let prob_pitch_glob = app.make_global(0u16);
@@ -212,6 +218,7 @@ pub async fn run(
     let recall_flag = app.make_global(false);
     let div_glob = app.make_global(4u32);
     let midi_note = app.make_global(MidiNote::from(0));
+    let midi_pitch = app.make_global(Pitch::default());
     let last_note_on = app.make_global(MidiNote::from(0));
     let glob_latch_layer = app.make_global(LatchLayer::Main);
     let glob_muted = app.make_global(storage.query(|s| s.muted));
@@ -359,8 +366,8 @@ pub async fn run(
                             // rounding error from the integer counts_per_oct approximation.
                             let shifted = Pitch {
                                 octave: (out.octave as i32 + octave_offset) as i8,
-                                note: out.note,
                                 raw: None,
+                                ..out
                             };
 
                             let base_note_i = u7::from(base_note).as_int() as i32;
@@ -368,9 +375,10 @@ pub async fn run(
                             let note =
                                 MidiNote::from((base_note_i + shifted_midi_val - 12).clamp(0, 127));
                             midi_note.set(note);
+                            midi_pitch.set(shifted);
 
                             if !glob_muted.get() {
-                                cv_out.set_value(pitch_as_counts(shifted, Range::_0_10V, vpo));
+                                cv_out.set_pitch_value(pitch_as_counts(shifted, Range::_0_10V, vpo));
                             }
                             leds.set(
                                 0,
@@ -442,7 +450,8 @@ pub async fn run(
                                 gate_on = true;
                                 pending_note_off = false;
                                 aux_out.set_value(if is_accented { 4095 } else { 0 });
-                                midi.send_note_on(note, if is_accented { 4095 } else { 2048 })
+                                let velocity = if is_accented { 4095 } else { 2048 };
+                                midi.send_pitch_note_on(note, &midi_pitch.get(), velocity)
                                     .await;
                                 leds.set(1, Led::Top, led_color, Brightness::Low);
                             }
@@ -500,9 +509,9 @@ pub async fn run(
     // Fader handler
     let fut2 = async {
         let mut latch = [
-            app.make_latch(fader.get_value_at(0)),
-            app.make_latch(fader.get_value_at(1)),
-            app.make_latch(fader.get_value_at(2)),
+            app.make_latch(0, fader.get_value_at(0)),
+            app.make_latch(1, fader.get_value_at(1)),
+            app.make_latch(2, fader.get_value_at(2)),
         ];
 
         loop {
@@ -0,0 +1,355 @@
+use embassy_futures::{
+    join::{join, join5},
+    select::{select, select3},
+};
+use embassy_sync::{blocking_mutex::raw::NoopRawMutex, signal::Signal};
+use heapless::Vec;
+use midly::MidiMessage;
+use serde::{Deserialize, Serialize};
+
+use libfp::{
+    ext::FromValue,
+    key_follow::{note_name, HeldNotes},
+    latch::LatchLayer,
+    AppIcon, Brightness, ClockDivision, Color, Config, Key, MidiChannel, MidiIn, Note, Param,
+    Range, Value, VoltPerOct, APP_MAX_PARAMS,
+};
+
+use crate::app::{
+    set_global_key, vpo_counts_per_oct, App, AppMidiEvent, AppParams, AppStorage, ClockEvent, Led,
+    ManagedStorage, ParamStore, SceneEvent,
+};
+
+pub const CHANNELS: usize = 1;
+pub const PARAMS: usize = 8;
+
+const LED_BRIGHTNESS: Brightness = Brightness::Mid;
+
+pub static CONFIG: Config<PARAMS> = Config::new(
+    "Key Follow",
+    "Set the global key from MIDI or CV",
+    Color::Yellow,
+    AppIcon::NoteBox,
+)
+.add_param(Param::Enum {
+    name: "Source",
+    variants: &["MIDI lowest", "MIDI last", "CV"],
+})
+.add_param(Param::MidiIn)
+.add_param(Param::MidiChannel {
+    name: "MIDI Channel",
+})
+.add_param(Param::bool {
+    name: "Follow major/minor",
+})
+.add_param(Param::Enum {
+    name: "Quantize",
+    variants: &["Off", "Beat", "Bar"],
+})
+.add_param(Param::Range {
+    name: "Range",
+    variants: &[Range::_0_10V, Range::_Neg5_5V],
+})
+.add_param(Param::VoltPerOct)
+.add_param(Param::Color {
+    name: "Color",
+    variants: &[
+        Color::Blue,
+        Color::Green,
+        Color::Rose,
+        Color::Orange,
+        Color::Cyan,
+        Color::Pink,
+        Color::Violet,
+        Color::Yellow,
+    ],
+});
+
+pub struct Params {
+    source: usize,
+    midi_in: MidiIn,
+    midi_channel: MidiChannel,
+    follow_mode: bool,
+    quantize: usize,
+    range: Range,
+    vpo: VoltPerOct,
+    color: Color,
+}
+
+impl AppParams for Params {
+    fn from_values(values: &[Value]) -> Option<Self> {
+        if values.len() < PARAMS {
+            return None;
+        }
+        Some(Self {
+            source: usize::from_value(values[0]),
+            midi_in: MidiIn::from_value(values[1]),
+            midi_channel: MidiChannel::from_value(values[2]),
+            follow_mode: bool::from_value(values[3]),
+            quantize: usize::from_value(values[4]),
+            range: Range::from_value(values[5]),
+            vpo: VoltPerOct::from_value(values[6]),
+            color: Color::from_value(values[7]),
+        })
+    }
+
+    fn to_values(&self) -> Vec<Value, APP_MAX_PARAMS> {
+        let mut vec = Vec::new();
+        vec.push(self.source.into()).unwrap();
+        vec.push(self.midi_in.into()).unwrap();
+        vec.push(self.midi_channel.into()).unwrap();
+        vec.push(self.follow_mode.into()).unwrap();
+        vec.push(self.quantize.into()).unwrap();
+        vec.push(self.range.into()).unwrap();
+        vec.push(self.vpo.into()).unwrap();
+        vec.push(self.color.into()).unwrap();
+        vec
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Storage {
+    transpose: u16,
+    active: bool,
+}
+
+impl Default for Storage {
+    fn default() -> Self {
+        Self {
+            transpose: 0,
+            active: true,
+        }
+    }
+}
+
+impl AppStorage for Storage {}
+
+#[embassy_executor::task(pool_size = 16/CHANNELS)]
+pub async fn wrapper(app: App<CHANNELS>, exit_signal: &'static Signal<NoopRawMutex, bool>) {
+    let param_store = ParamStore::<Params>::new(
+        app.app_id,
+        app.layout_id,
+        Params {
+            source: 0,
+            midi_in: MidiIn::default(),
+            midi_channel: MidiChannel::default(),
+            follow_mode: false,
+            quantize: 0,
+            range: Range::_0_10V,
+            vpo: VoltPerOct::Standard,
+            color: Color::Yellow,
+        },
+    );
+    let storage = ManagedStorage::<Storage>::new(app.app_id, app.layout_id);
+
+    param_store.load().await;
+    storage.load().await;
+
+    let app_loop = async {
+        loop {
+            select3(
+                run(&app, &param_store, &storage),
+                param_store.param_handler(),
+                storage.saver_task(),
+            )
+            .await;
+        }
+    };
+
+    select(app_loop, app.exit_handler(exit_signal)).await;
+}
+
+/// Semitones set by the transpose fader
+fn transpose_semitones(value: u16) -> u8 {
+    (value as u32 * 12 / 4096) as u8
+}
+
+pub async fn run(
+    app: &App<CHANNELS>,
+    params: &ParamStore<Params>,
+    storage: &ManagedStorage<Storage>,
+) {
+    let (source, midi_in, midi_chan, follow_mode, quantize, range, vpo, led_color) =
+        params.query(|p| {
+            (
+                p.source,
+                p.midi_in,
+                p.midi_channel,
+                p.follow_mode,
+                p.quantize,
+                p.range,
+                p.vpo,
+                p.color,
+            )
+        });
+    let buttons = app.use_buttons();
+    let faders = app.use_faders();
+    let leds = app.use_leds();
+    let mut midi = app.use_midi_input(midi_in, midi_chan);
+    let mut clock = app.use_clock();
+    let counts_per_oct = vpo_counts_per_oct(vpo) as i32;
+    let input = app.make_in_jack(0, range).await;
+
+    // Followed tonic and mode, waiting to be applied
+    let glob_pending = app.make_global(None::<(Note, Option<Key>)>);
+    // Last applied, to re-apply when the transpose fader moves
+    let glob_followed = app.make_global(None::<(Note, Option<Key>)>);
+
+    let set_button_led = || {
+        if storage.query(|s| s.active) {
+            leds.set(0, Led::Button, led_color, LED_BRIGHTNESS);
+        } else {
+            leds.unset(0, Led::Button);
+        }
+    };
+
+    set_button_led();
+
+    let midi_handler = async {
+        if source == 2 {
+            return;
+        }
+        let mut held = HeldNotes::new();
+        loop {
+            let AppMidiEvent::Message(msg) = midi.wait_for_event().await else {
+                continue;
+            };
+            match msg {
+                MidiMessage::NoteOn { key, vel } if vel > 0 => held.note_on(key.as_int()),
+                // Sometimes note-off will be a NoteOn with velocity 0
+                MidiMessage::NoteOn { key, .. } | MidiMessage::NoteOff { key, .. } => {
+                    held.note_off(key.as_int())
+                }
+                _ => continue,
+            }
+            let note = if source == 0 {
+                held.lowest()
+            } else {
+                held.last()
+            };
+            // Releasing every key keeps the last key
+            if let Some(note) = note {
+                let key = if follow_mode { held.mode() } else { None };
+                glob_pending.set(Some((note_name(note), key)));
+            }
+        }
+    };
+
+    let cv_handler = async {
+        if source != 2 {
+            return;
+        }
+        let mut last = None;
+        loop {
+            app.delay_millis(1).await;
+            let value = match range {
+                Range::_Neg5_5V => input.get_value() as i32 - 2048,
+                _ => input.get_value() as i32,
+            };
+            // Rounded to the nearest semitone
+            let semitones = (value * 24 + counts_per_oct).div_euclid(counts_per_oct * 2);
+            let note = Note::from(semitones.rem_euclid(12) as u8);
+            if last != Some(note) {
+                last = Some(note);
+                glob_pending.set(Some((note, None)));
+            }
+        }
+    };
+
+    let apply_loop = async {
+        loop {
+            match quantize {
+                1 | 2 => {
+                    let division = if quantize == 1 {
+                        ClockDivision::_24
+                    } else {
+                        ClockDivision::_96
+                    };
+                    if matches!(clock.wait_for_event(division).await, ClockEvent::Stop) {
+                        continue;
+                    }
+                }
+                _ => app.delay_millis(1).await,
+            }
+            let Some(followed) = glob_pending.get() else {
+                continue;
+            };
+            glob_pending.set(None);
+            glob_followed.set(Some(followed));
+            if !storage.query(|s| s.active) {
+                continue;
+            }
+            let (note, key) = followed;
+            let transpose = transpose_semitones(storage.query(|s| s.transpose));
+            let tonic = Note::from((note as u8 + transpose) % 12);
+            set_global_key(key, tonic);
+            leds.set(
+                0,
+                Led::Bottom,
+                led_color,
+                Brightness::Custom((tonic as u16 * 255 / 11) as u8),
+            );
+        }
+    };
+
+    let button_handler = async {
+        loop {
+            let (_, is_shift_pressed) = buttons.wait_for_any_down().await;
+            if is_shift_pressed {
+                continue;
+            }
+            storage.modify_and_save(|s| s.active = !s.active);
+            set_button_led();
+            if storage.query(|s| s.active) {
+                glob_pending.set(glob_followed.get());
+            }
+        }
+    };
+
+    let fader_event_handler = async {
+        let mut latch = app.make_latch(0, faders.get_value());
+
+        loop {
+            faders.wait_for_change().await;
+            let latch_layer = LatchLayer::Main;
+            let target_value = storage.query(|s| s.transpose);
+
+            if let Some(new_value) = latch.update(faders.get_value(), latch_layer, target_value) {
+                let old = transpose_semitones(storage.query(|s| s.transpose));
+                storage.modify_and_save(|s| s.transpose = new_value);
+                let transpose = transpose_semitones(new_value);
+                leds.set(
+                    0,
+                    Led::Top,
+                    led_color,
+                    Brightness::Custom((transpose as u16 * 255 / 11) as u8),
+                );
+                if transpose != old {
+                    glob_pending.set(glob_followed.get());
+                }
+            }
+        }
+    };
+
+    let scene_handler = async {
+        loop {
+            match app.wait_for_scene_event().await {
+                SceneEvent::LoadScene(scene) => {
+                    storage.load_from_scene(scene).await;
+                    set_button_led();
+                    glob_pending.set(glob_followed.get());
+                }
+                SceneEvent::SaveScene(scene) => storage.save_to_scene(scene).await,
+            }
+        }
+    };
+
+    join5(
+        join(midi_handler, cv_handler),
+        apply_loop,
+        button_handler,
+        fader_event_handler,
+        scene_handler,
+    )
+    .await;
+}
@@ -19,7 +19,7 @@ use crate::app::{
 };
 
 pub const CHANNELS: usize = 1;
-pub const PARAMS: usize = 7;
+pub const PARAMS: usize = 8;
 
 pub static CONFIG: Config<PARAMS> =
     Config::new("LFO", "Multi shape LFO", Color::Yellow, AppIcon::Sine)
@@ -37,7 +37,8 @@ pub static CONFIG: Config<PARAMS> =
         .add_param(Param::MidiCc { name: "MIDI CC" })
         .add_param(Param::MidiNrpn)
         .add_param(Param::MidiOut)
-        .add_param(Param::bool { name: "Grid Lock" });
+        .add_param(Param::bool { name: "Grid Lock" })
+        .add_param(Param::MidiCc14);
 
 pub struct Params {
     speed_mult: usize,
@@ -46,6 +47,7 @@ pub struct Params {
     midi_channel: MidiChannel,
     midi_cc: MidiCc,
     nrpn: bool,
+    cc14: bool,
     phase_lock: bool,
 }
 
@@ -62,6 +64,7 @@ impl AppParams for Params {
             nrpn: bool::from_value(values[4]),
             midi_out: MidiOut::from_value(values[5]),
             phase_lock: bool::from_value(values[6]),
+            cc14: bool::from_value(values[7]),
         })
     }
 
@@ -74,6 +77,7 @@ impl AppParams for Params {
         vec.push(Value::MidiNrpn(self.nrpn)).unwrap();
         vec.push(self.midi_out.into()).unwrap();
         vec.push(self.phase_lock.into()).unwrap();
+        vec.push(Value::MidiCc14(self.cc14)).unwrap();
         vec
     }
 }
@@ -113,6 +117,7 @@ pub async fn wrapper(app: App<CHANNELS>, exit_signal: &'static Signal<NoopRawMut
             midi_channel: MidiChannel::default(),
             midi_cc: MidiCc::from(32u8.saturating_add(app.start_channel as u8)),
             nrpn: false,
+            cc14: false,
             phase_lock: true,
         },
     );
@@ -140,8 +145,16 @@ pub async fn run(
     params: &ParamStore<Params>,
     storage: &ManagedStorage<Storage>,
 ) {
-    let (range, midi_out, midi_chan, midi_cc, nrpn) =
-        params.query(|p| (p.range, p.midi_out, p.midi_channel, p.midi_cc, p.nrpn));
+    let (range, midi_out, midi_chan, midi_cc, nrpn, cc14) = params.query(|p| {
+        (
+            p.range,
+            p.midi_out,
+            p.midi_channel,
+            p.midi_cc,
+            p.nrpn,
+            p.cc14,
+        )
+    });
 
     let speed_mult = 2u32.pow(params.query(|p| p.speed_mult).min(31) as u32);
     let phase_lock = params.query(|p| p.phase_lock);
@@ -151,7 +164,9 @@ pub async fn run(
     let leds = app.use_leds();
     let mut clk = app.use_clock();
 
-    let midi = app.use_midi_output(midi_out, midi_chan, nrpn);
+    let midi = app
+        .use_midi_output(midi_out, midi_chan, nrpn)
+        .with_cc14(cc14);
 
     let glob_lfo_speed = app.make_global(0.0682);
     let glob_lfo_pos = app.make_global(0.0);
@@ -246,7 +261,7 @@ pub async fn run(
             };
             output.set_value(effective_val);
             if midi_out.is_some() {
-                let gate_val = midi_gate(effective_val, nrpn);
+                let gate_val = midi_gate(effective_val, midi.is_high_res(midi_cc));
                 if gate_val != last_val {
                     midi.send_cc(midi_cc, effective_val).await;
                     last_val = gate_val;
@@ -291,7 +306,7 @@ pub async fn run(
     };
 
     let fut2 = async {
-        let mut latch = app.make_latch(fader.get_value());
+        let mut latch = app.make_latch(0, fader.get_value());
 
         loop {
             fader.wait_for_change().await;
@@ -10,6 +10,7 @@ use serde::{Deserialize, Serialize};
 
 use libfp::{
     ext::FromValue,
+    i2c_leader::I2cParam,
     latch::LatchLayer,
     utils::{
         apply_slide, bits_7_16, clickless, fader_to_slide_coeff, scale_bits_14_12, scale_bits_7_12,
@@ -21,7 +22,7 @@ use libfp::{
 use crate::app::{App, AppMidiEvent, AppParams, AppStorage, Led, ManagedStorage, ParamStore, SceneEvent};
 
 pub const CHANNELS: usize = 1;
-pub const PARAMS: usize = 9;
+pub const PARAMS: usize = 10;
 
 const LED_BRIGHTNESS: Brightness = Brightness::Mid;
 
@@ -65,7 +66,8 @@ pub static CONFIG: Config<PARAMS> = Config::new(
 .add_param(Param::MidiIn)
 .add_param(Param::bool {
     name: "Velocity on Gate",
-});
+})
+.add_param(Param::bool { name: "I2C out" });
 
 pub struct Params {
     mode: usize,
@@ -77,6 +79,7 @@ pub struct Params {
     bend_range: i32,
     color: Color,
     gate_vel: bool,
+    i2c_out: bool,
 }
 
 impl AppParams for Params {
@@ -94,6 +97,7 @@ impl AppParams for Params {
             color: Color::from_value(values[6]),
             midi_in: MidiIn::from_value(values[7]),
             gate_vel: bool::from_value(values[8]),
+            i2c_out: bool::from_value(values[9]),
         })
     }
 
@@ -108,6 +112,7 @@ impl AppParams for Params {
         vec.push(self.color.into()).unwrap();
         vec.push(self.midi_in.into()).unwrap();
         vec.push(self.gate_vel.into()).unwrap();
+        vec.push(self.i2c_out.into()).unwrap();
         vec
     }
 }
@@ -143,6 +148,7 @@ pub async fn wrapper(app: App<CHANNELS>, exit_signal: &'static Signal<NoopRawMut
         bend_range: 12,
         color: Color::Cyan,
         gate_vel: false,
+        i2c_out: false,
     });
     let storage = ManagedStorage::<Storage>::new(app.app_id, app.layout_id);
 
@@ -168,8 +174,8 @@ pub async fn run(
     params: &ParamStore<Params>,
     storage: &ManagedStorage<Storage>,
 ) {
-    let (midi_in, midi_chan, midi_cc, curve, bend_range, led_color, note, mode, gate_vel) = params
-        .query(|p| {
+    let (midi_in, midi_chan, midi_cc, curve, bend_range, led_color, note, mode, gate_vel, i2c_out) =
+        params.query(|p| {
             (
                 p.midi_in,
                 p.midi_channel,
@@ -180,6 +186,7 @@ pub async fn run(
                 p.midi_note,
                 p.mode,
                 p.gate_vel,
+                p.i2c_out,
             )
         });
 
@@ -193,6 +200,7 @@ pub async fn run(
     let buttons = app.use_buttons();
     let fader = app.use_faders();
     let leds = app.use_leds();
+    let i2c = app.use_i2c_output();
 
     let glob_latch_layer = app.make_global(LatchLayer::Main);
 
@@ -225,11 +233,17 @@ pub async fn run(
             if *note_num == 0 {
                 glide_active_glob.set(false);
             }
+            if i2c_out {
+                i2c.send_note(0, MidiNote::from(key.as_int()), 0);
+            }
         } else if mode == 2 || (mode == 6 && key == u7::from(note)) {
             *note_num = (*note_num - 1).max(0);
             if *note_num == 0 {
                 jack.set_value(0);
                 leds.unset(0, Led::Top);
+                if mode == 2 && i2c_out {
+                    i2c.send_gate(0, false);
+                }
             }
         }
     };
@@ -360,7 +374,7 @@ pub async fn run(
     };
 
     let fader_handler = async {
-        let mut latch = app.make_latch(fader.get_value());
+        let mut latch = app.make_latch(0, fader.get_value());
 
         loop {
             fader.wait_for_change().await;
@@ -402,12 +416,23 @@ pub async fn run(
                         offset_glob.set(val);
                     }
                 }
+                AppMidiEvent::Cc14 { controller, value }
+                    if mode == 0 && controller == u7::from(midi_cc) =>
+                {
+                    offset_glob.set(value);
+                }
+                AppMidiEvent::Cc14 { .. } | AppMidiEvent::SysEx(_) => {}
                 AppMidiEvent::Message(msg) => match msg {
                 MidiMessage::Controller { controller, value }
                     if mode == 0 && controller == u7::from(midi_cc) =>
                 {
                     let val = scale_bits_7_12(value);
                     offset_glob.set(val);
+                    if i2c_out {
+                        // The Disting EX parameter with the CC's number follows it
+                        let param = I2cParam::DistingEx(controller.as_int());
+                        i2c.set_param(0, param, value.as_int() as i16);
+                    }
                 }
                 MidiMessage::NoteOn { key, vel } => {
                     // Sometimes note-off will be a NoteOn with velocity 0
@@ -428,6 +453,10 @@ pub async fn run(
                                 let note_out =
                                     (note_in as i32 + oct * 410).clamp(0, 4095) as u16;
                                 pitch_glob.set(note_out);
+                                if i2c_out {
+                                    let note = MidiNote::from(key.as_int());
+                                    i2c.send_note(0, note, scale_bits_7_12(vel));
+                                }
                                 leds.set(
                                     0,
                                     Led::Top,
@@ -445,6 +474,9 @@ pub async fn run(
                                     jack.set_value(vel_out);
                                     note_num += 1;
                                     leds.set(0, Led::Top, led_color, LED_BRIGHTNESS);
+                                    if i2c_out {
+                                        i2c.send_gate(0, true);
+                                    }
                                 } else {
                                     note_num = 0;
                                 }
@@ -474,6 +506,10 @@ pub async fn run(
                                     jack.set_value(vel_out);
                                     note_num += 1;
                                     leds.set(0, Led::Top, led_color, LED_BRIGHTNESS);
+                                    if i2c_out {
+                                        // Drum hits, Ansible pulses by itself
+                                        i2c.send_trigger(0);
+                                    }
                                 } else {
                                     note_num = 0;
                                 }
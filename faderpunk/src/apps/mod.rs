@@ -26,4 +26,6 @@ register_apps!(
     25 => automator,
     26 => genseq,
     27 => bernoulli,
+    28 => chord,
+    29 => key_follow,
 );
@@ -8,7 +8,7 @@ use heapless::Vec;
 use serde::{Deserialize, Serialize};
 
 use libfp::{
-    ext::FromValue, latch::LatchLayer, AppIcon, Brightness, ClockDivision, Color, Config,
+    ext::FromValue, latch::LatchLayer, AppIcon, AppScale, Brightness, ClockDivision, Color, Config,
     MidiChannel, MidiNote, MidiOut, Param, Range, Value, VoltPerOct, APP_MAX_PARAMS,
 };
 
@@ -18,7 +18,7 @@ use crate::app::{
 };
 
 pub const CHANNELS: usize = 1;
-pub const PARAMS: usize = 9;
+pub const PARAMS: usize = 10;
 
 const LED_BRIGHTNESS: Brightness = Brightness::Mid;
 
@@ -61,7 +61,8 @@ pub static CONFIG: Config<PARAMS> = Config::new(
 })
 .add_param(Param::MidiOut)
 .add_param(Param::VoltPerOct)
-.add_param(Param::bool { name: "Bypass quantizer" });
+.add_param(Param::bool { name: "Bypass quantizer" })
+.add_param(Param::Scale);
 
 pub struct Params {
     midi_channel: MidiChannel,
@@ -73,6 +74,7 @@ pub struct Params {
     color: Color,
     vpo: VoltPerOct,
     bypass: bool,
+    scale: AppScale,
 }
 
 impl AppParams for Params {
@@ -90,6 +92,7 @@ impl AppParams for Params {
             midi_out: MidiOut::from_value(values[6]),
             vpo: VoltPerOct::from_value(values[7]),
             bypass: bool::from_value(values[8]),
+            scale: AppScale::from_value(values[9]),
         })
     }
 
@@ -104,6 +107,7 @@ impl AppParams for Params {
         vec.push(self.midi_out.into()).unwrap();
         vec.push(self.vpo.into()).unwrap();
         vec.push(self.bypass.into()).unwrap();
+        vec.push(self.scale.into()).unwrap();
         vec
     }
 }
@@ -140,6 +144,7 @@ pub async fn wrapper(app: App<CHANNELS>, exit_signal: &'static Signal<NoopRawMut
         color: Color::Rose,
         vpo: VoltPerOct::Standard,
         bypass: false,
+        scale: AppScale::Global,
     });
     let storage = ManagedStorage::<Storage>::new(app.app_id, app.layout_id);
 
@@ -182,7 +187,8 @@ pub async fn run(
         });
 
     let mut clock = app.use_clock();
-    let quantizer = app.use_quantizer(range, vpo, bypass);
+    let scale = params.query(|p| p.scale);
+    let quantizer = app.use_quantizer(range, vpo, bypass, scale);
 
     let fader = app.use_faders();
     let buttons = app.use_buttons();
@@ -218,13 +224,13 @@ pub async fn run(
 
         let out = quantizer.get_quantized_note(fadval).await;
         if outmode == 0 {
-            jack.set_value(pitch_as_counts(out, range, vpo));
+            jack.set_pitch_value(pitch_as_counts(out, range, vpo));
         } else {
             jack.set_value(4095)
         }
 
         let note = base_note + out.as_midi();
-        midi.send_note_on(note, 4095).await;
+        midi.send_pitch_note_on(note, &out, 4095).await;
         leds.set(0, Led::Button, led_color, LED_BRIGHTNESS);
         note
     };
@@ -331,7 +337,7 @@ pub async fn run(
     };
 
     let fut3 = async {
-        let mut latch = app.make_latch(fader.get_value());
+        let mut latch = app.make_latch(0, fader.get_value());
 
         loop {
             fader.wait_for_change().await;
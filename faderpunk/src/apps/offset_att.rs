@@ -180,8 +180,8 @@ pub async fn run(
 
     let fut2 = async {
         let mut latch = [
-            app.make_latch(faders.get_value_at(0)),
-            app.make_latch(faders.get_value_at(1)),
+            app.make_latch(0, faders.get_value_at(0)),
+            app.make_latch(1, faders.get_value_at(1)),
         ];
         loop {
             let chan = faders.wait_for_any_change().await;
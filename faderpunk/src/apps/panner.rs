@@ -18,7 +18,7 @@ use libfp::{Config, Curve, Param, Range, Value};
 use crate::app::{App, AppParams, AppStorage, Led, ManagedStorage, ParamStore, SceneEvent};
 
 pub const CHANNELS: usize = 2;
-pub const PARAMS: usize = 10;
+pub const PARAMS: usize = 11;
 
 pub static CONFIG: Config<PARAMS> = Config::new(
     "Panner",
@@ -59,7 +59,8 @@ pub static CONFIG: Config<PARAMS> = Config::new(
     name: "Store state",
 })
 .add_param(Param::MidiNrpn)
-.add_param(Param::MidiOut);
+.add_param(Param::MidiOut)
+.add_param(Param::MidiCc14);
 
 pub struct Params {
     curve: Curve,
@@ -72,6 +73,7 @@ pub struct Params {
     color: Color,
     save_state: bool,
     nrpn: bool,
+    cc14: bool,
 }
 
 impl AppParams for Params {
@@ -90,6 +92,7 @@ impl AppParams for Params {
             save_state: bool::from_value(values[7]),
             nrpn: bool::from_value(values[8]),
             midi_out: MidiOut::from_value(values[9]),
+            cc14: bool::from_value(values[10]),
         })
     }
 
@@ -105,6 +108,7 @@ impl AppParams for Params {
         vec.push(self.save_state.into()).unwrap();
         vec.push(Value::MidiNrpn(self.nrpn)).unwrap();
         vec.push(self.midi_out.into()).unwrap();
+        vec.push(Value::MidiCc14(self.cc14)).unwrap();
         vec
     }
 }
@@ -153,6 +157,7 @@ pub async fn wrapper(app: App<CHANNELS>, exit_signal: &'static Signal<NoopRawMut
             color: Color::Blue,
             save_state: true,
             nrpn: false,
+            cc14: false,
         },
     );
     let storage = ManagedStorage::<Storage>::new(app.app_id, app.layout_id);
@@ -190,6 +195,7 @@ pub async fn run(
         led_color,
         save_state,
         nrpn,
+        cc14,
     ) = params.query(|p| {
         (
             p.curve,
@@ -202,13 +208,16 @@ pub async fn run(
             p.color,
             p.save_state,
             p.nrpn,
+            p.cc14,
         )
     });
 
     let buttons = app.use_buttons();
     let faders = app.use_faders();
     let leds = app.use_leds();
-    let midi = app.use_midi_output(midi_out, midi_chan, nrpn);
+    let midi = app
+        .use_midi_output(midi_out, midi_chan, nrpn)
+        .with_cc14(cc14);
     let i2c = app.use_i2c_output();
 
     let muted_glob = app.make_global(storage.query(|s| s.muted));
@@ -239,8 +248,8 @@ pub async fn run(
 
     let main_loop = async {
         let mut latch = [
-            app.make_latch(faders.get_value_at(0)),
-            app.make_latch(faders.get_value_at(1)),
+            app.make_latch(0, faders.get_value_at(0)),
+            app.make_latch(1, faders.get_value_at(1)),
         ];
 
         let mut lfo_pos: f32 = 0.;
@@ -375,12 +384,12 @@ pub async fn run(
             jacks[1].set_value(out_r_val);
 
             if midi_out.is_some() {
-                let gate_l = midi_gate(out_l_val, nrpn);
+                let gate_l = midi_gate(out_l_val, midi.is_high_res(midi_cc_l));
                 if last_out[0] != gate_l {
                     midi.send_cc(midi_cc_l, out_l_val).await;
                     last_out[0] = gate_l;
                 }
-                let gate_r = midi_gate(out_r_val, nrpn);
+                let gate_r = midi_gate(out_r_val, midi.is_high_res(midi_cc_r));
                 if last_out[1] != gate_r {
                     midi.send_cc(midi_cc_r, out_r_val).await;
                     last_out[1] = gate_r;
@@ -516,7 +525,7 @@ pub async fn run(
     };
 
     let fader_event_handler = async {
-        let mut latch = app.make_latch(faders.get_value_at(1));
+        let mut latch = app.make_latch(1, faders.get_value_at(1));
         loop {
             let chan = faders.wait_for_any_change().await;
             if chan == 0 {
@@ -16,7 +16,7 @@ use crate::app::{
 };
 
 pub const CHANNELS: usize = 1;
-pub const PARAMS: usize = 5;
+pub const PARAMS: usize = 6;
 
 const LED_BRIGHTNESS: Brightness = Brightness::Mid;
 
@@ -48,7 +48,8 @@ pub static CONFIG: Config<PARAMS> = Config::new(
         Color::Yellow,
     ],
 })
-.add_param(Param::MidiOut);
+.add_param(Param::MidiOut)
+.add_param(Param::bool { name: "I2C gates" });
 
 pub struct Params {
     midi_channel: MidiChannel,
@@ -56,6 +57,7 @@ pub struct Params {
     midi_out: MidiOut,
     gatel: i32,
     color: Color,
+    i2c_gates: bool,
 }
 
 impl AppParams for Params {
@@ -69,6 +71,7 @@ impl AppParams for Params {
             gatel: i32::from_value(values[2]),
             color: Color::from_value(values[3]),
             midi_out: MidiOut::from_value(values[4]),
+            i2c_gates: bool::from_value(values[5]),
         })
     }
 
@@ -79,6 +82,7 @@ impl AppParams for Params {
         vec.push(self.gatel.into()).unwrap();
         vec.push(self.color.into()).unwrap();
         vec.push(self.midi_out.into()).unwrap();
+        vec.push(self.i2c_gates.into()).unwrap();
         vec
     }
 }
@@ -109,6 +113,7 @@ pub async fn wrapper(app: App<CHANNELS>, exit_signal: &'static Signal<NoopRawMut
         midi_out: MidiOut([false, false, false]),
         gatel: 50,
         color: Color::Cyan,
+        i2c_gates: false,
     });
     let storage = ManagedStorage::<Storage>::new(app.app_id, app.layout_id);
 
@@ -134,13 +139,14 @@ pub async fn run(
     params: &ParamStore<Params>,
     storage: &ManagedStorage<Storage>,
 ) {
-    let (midi_out, midi_chan, note, gatel, led_color) = params.query(|p| {
+    let (midi_out, midi_chan, note, gatel, led_color, i2c_gates) = params.query(|p| {
         (
             p.midi_out,
             p.midi_channel,
             p.midi_note,
             p.gatel as u32,
             p.color,
+            p.i2c_gates,
         )
     });
     let curve = Curve::Exponential;
@@ -158,7 +164,8 @@ pub async fn run(
     let prob_glob = app.make_global(4095_u16);
     let glob_latch_layer = app.make_global(LatchLayer::Main);
 
-    let jack = app.make_gate_jack(0, 4095).await;
+    let i2c = app.use_i2c_output();
+    let jack = app.make_gate_jack(0, 4095).await.mirror_i2c(&i2c, i2c_gates);
 
     let resolution = [384, 192, 96, 48, 24, 16, 12, 8, 6, 4, 3, 2];
 
@@ -260,7 +267,7 @@ pub async fn run(
     };
 
     let fut3 = async {
-        let mut latch = app.make_latch(fader.get_value());
+        let mut latch = app.make_latch(0, fader.get_value());
         loop {
             fader.wait_for_change_at(0).await;
 
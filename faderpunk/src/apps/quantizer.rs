@@ -5,8 +5,8 @@ use embassy_futures::{
 use embassy_sync::{blocking_mutex::raw::NoopRawMutex, signal::Signal};
 use heapless::Vec;
 use libfp::{
-    ext::FromValue, latch::LatchLayer, utils::split_unsigned_value, AppIcon, Brightness, Color,
-    VoltPerOct, APP_MAX_PARAMS,
+    ext::FromValue, latch::LatchLayer, utils::split_unsigned_value, AppIcon, AppScale, Brightness,
+    Color, VoltPerOct, APP_MAX_PARAMS,
 };
 use serde::{Deserialize, Serialize};
 
@@ -17,7 +17,7 @@ use crate::app::{
 };
 
 pub const CHANNELS: usize = 2;
-pub const PARAMS: usize = 4;
+pub const PARAMS: usize = 5;
 
 pub static CONFIG: Config<PARAMS> = Config::new(
     "Quantizer",
@@ -45,13 +45,15 @@ pub static CONFIG: Config<PARAMS> = Config::new(
 .add_param(Param::VoltPerOct)
 .add_param(Param::bool {
     name: "Bypass quantizer",
-});
+})
+.add_param(Param::Scale);
 
 pub struct Params {
     color: Color,
     range: Range,
     vpo: VoltPerOct,
     bypass: bool,
+    scale: AppScale,
 }
 
 impl AppParams for Params {
@@ -64,6 +66,7 @@ impl AppParams for Params {
             range: Range::from_value(values[1]),
             vpo: VoltPerOct::from_value(values[2]),
             bypass: bool::from_value(values[3]),
+            scale: AppScale::from_value(values[4]),
         })
     }
 
@@ -73,6 +76,7 @@ impl AppParams for Params {
         vec.push(self.range.into()).unwrap();
         vec.push(self.vpo.into()).unwrap();
         vec.push(self.bypass.into()).unwrap();
+        vec.push(self.scale.into()).unwrap();
         vec
     }
 }
@@ -106,6 +110,7 @@ pub async fn wrapper(app: App<CHANNELS>, exit_signal: &'static Signal<NoopRawMut
             range: Range::_Neg5_5V,
             vpo: VoltPerOct::Standard,
             bypass: false,
+            scale: AppScale::Global,
         },
     );
     let storage = ManagedStorage::<Storage>::new(app.app_id, app.layout_id);
@@ -139,7 +144,9 @@ pub async fn run(
     leds.set(0, Led::Button, led_color, Brightness::Mid);
     leds.set(1, Led::Button, led_color, Brightness::Mid);
 
-    let quantizer = app.use_quantizer(range, vpo, bypass);
+    let scale = params.query(|p| p.scale);
+
+    let quantizer = app.use_quantizer(range, vpo, bypass, scale);
     let counts_per_oct = vpo.counts_per_oct();
     let _input = app.make_in_jack(0, range).await;
     let output = app.make_out_jack(1, range).await;
@@ -175,7 +182,7 @@ pub async fn run(
                 .get_quantized_note((inval + oct + st).clamp(0, 4095) as u16)
                 .await;
 
-            output.set_value(pitch_as_counts(outval, range, vpo));
+            output.set_pitch_value(pitch_as_counts(outval, range, vpo));
             let oct_led = split_unsigned_value(pitch_as_counts(outval, range, vpo));
             leds.set(1, Led::Top, led_color, Brightness::Custom(oct_led[0]));
             leds.set(1, Led::Bottom, led_color, Brightness::Custom(oct_led[1]));
@@ -207,8 +214,8 @@ pub async fn run(
 
     let fader_event_handler = async {
         let mut latch = [
-            app.make_latch(faders.get_value_at(0)),
-            app.make_latch(faders.get_value_at(1)),
+            app.make_latch(0, faders.get_value_at(0)),
+            app.make_latch(1, faders.get_value_at(1)),
         ];
 
         loop {
@@ -318,8 +318,8 @@ pub async fn run(
 
     let fader_handler = async {
         let mut latch = [
-            app.make_latch(fader.get_value_at(0)),
-            app.make_latch(fader.get_value_at(1)),
+            app.make_latch(0, fader.get_value_at(0)),
+            app.make_latch(1, fader.get_value_at(1)),
         ];
         loop {
             let chan = fader.wait_for_any_change().await;
@@ -8,18 +8,18 @@ use heapless::Vec;
 use serde::{Deserialize, Serialize};
 
 use libfp::{
-    ext::FromValue, latch::LatchLayer, utils::fader_to_slide_coeff, AppIcon, Brightness,
+    ext::FromValue, latch::LatchLayer, utils::fader_to_slide_coeff, AppIcon, AppScale, Brightness,
     ClockDivision, Color, Config, MidiChannel, MidiIn, MidiNote, MidiOut, Param, Range, Value,
     VoltPerOct, APP_MAX_PARAMS,
 };
 
 use crate::app::{
-    pitch_as_counts, vpo_counts_per_oct, App, AppParams, AppStorage, Arr, ClockEvent, Global,
-    Led, ManagedStorage, ParamStore, SceneEvent,
+    pitch_as_counts, vpo_counts_per_oct, App, AppLatch, AppParams, AppStorage, Arr, ClockEvent,
+    Global, Led, ManagedStorage, ParamStore, SceneEvent,
 };
 
 pub const CHANNELS: usize = 8;
-pub const PARAMS: usize = 14;
+pub const PARAMS: usize = 15;
 
 pub static CONFIG: Config<PARAMS> = Config::new(
     "Sequencer",
@@ -48,7 +48,8 @@ pub static CONFIG: Config<PARAMS> = Config::new(
 .add_param(Param::MidiChannel { name: "Track 3: transpose CH" })
 .add_param(Param::MidiChannel { name: "Track 4: transpose CH" })
 .add_param(Param::VoltPerOct)
-.add_param(Param::bool { name: "Bypass quantizer" });
+.add_param(Param::bool { name: "Bypass quantizer" })
+.add_param(Param::Scale);
 
 pub struct Params {
     midi_channel1: MidiChannel,
@@ -65,6 +66,7 @@ pub struct Params {
     transpose_ch4: MidiChannel,
     vpo: VoltPerOct,
     bypass: bool,
+    scale: AppScale,
 }
 
 impl AppParams for Params {
@@ -87,6 +89,7 @@ impl AppParams for Params {
             transpose_ch4: MidiChannel::from_value(values[11]),
             vpo: VoltPerOct::from_value(values[12]),
             bypass: bool::from_value(values[13]),
+            scale: AppScale::from_value(values[14]),
         })
     }
 
@@ -106,6 +109,7 @@ impl AppParams for Params {
         vec.push(self.transpose_ch4.into()).unwrap();
         vec.push(self.vpo.into()).unwrap();
         vec.push(self.bypass.into()).unwrap();
+        vec.push(self.scale.into()).unwrap();
         vec
     }
 }
@@ -262,6 +266,7 @@ pub async fn wrapper(app: App<CHANNELS>, exit_signal: &'static Signal<NoopRawMut
             transpose_ch4: MidiChannel::from(4),
             vpo: VoltPerOct::Standard,
             bypass: false,
+            scale: AppScale::Global,
         },
     );
     let storage = ManagedStorage::<Storage>::new(app.app_id, app.layout_id);
@@ -350,7 +355,9 @@ pub async fn run(
         app.make_gate_jack(7, 4095).await,
     ];
 
-    let quantizer = app.use_quantizer(range, vpo, bypass);
+    let scale = params.query(|p| p.scale);
+
+    let quantizer = app.use_quantizer(range, vpo, bypass, scale);
     // Nominal scale for pre-quantize offsets (matches the quantizer's internal decode).
     let counts_per_oct = vpo.counts_per_oct() as u32;
     // Calibrated scale for the post-quantize transpose add (real DAC-counts domain).
@@ -396,8 +403,8 @@ pub async fn run(
     let mut vel_source = [4095u16; 4];
 
     // Initialize latches for all 8 faders
-    let mut latches: [libfp::latch::AnalogLatch; 8] =
-        core::array::from_fn(|i| app.make_latch(faders.get_value_at(i)));
+    let mut latches: [AppLatch; 8] =
+        core::array::from_fn(|i| app.make_latch(i, faders.get_value_at(i)));
 
     let (
         seq_saved,
@@ -766,7 +773,9 @@ pub async fn run(
                                     } else {
                                         4095
                                     };
-                                    midi[n].send_note_on(lastnote[n], velocity).await;
+                                    midi[n]
+                                        .send_pitch_note_on(lastnote[n], &out, velocity)
+                                        .await;
                                     gatelength1 = gatelength_glob.get();
                                     // Hand the target CV to slide_handler; slide only if
                                     // the previously played step had legato enabled.
@@ -873,7 +882,11 @@ pub async fn run(
                 } else {
                     current[n] = target_f;
                 }
-                cv_out[n].set_value(current[n] as u16);
+                if vel_lane[n] {
+                    cv_out[n].set_value(current[n] as u16);
+                } else {
+                    cv_out[n].set_pitch_value(current[n] as u16);
+                }
             }
         }
     };
@@ -11,18 +11,18 @@ use heapless::Vec;
 use serde::{Deserialize, Serialize};
 
 use libfp::{
-    ext::FromValue, latch::LatchLayer, utils::apply_slide, AppIcon, Brightness, ClockDivision,
-    Color, Config, Curve, MidiChannel, MidiNote, MidiOut, Param, Range, Value, VoltPerOct,
-    APP_MAX_PARAMS,
+    ext::FromValue, latch::LatchLayer, utils::apply_slide, AppIcon, AppScale, Brightness,
+    ClockDivision, Color, Config, Curve, MidiChannel, MidiNote, MidiOut, Param, Range, Value,
+    VoltPerOct, APP_MAX_PARAMS,
 };
 
 use crate::app::{
-    pitch_as_counts, App, AppParams, AppStorage, ClockEvent, Global, Led, LedMode, ManagedStorage,
-    ParamStore, SceneEvent,
+    pitch_as_counts, App, AppLatch, AppParams, AppStorage, ClockEvent, Global, Led, LedMode,
+    ManagedStorage, ParamStore, SceneEvent,
 };
 
 pub const CHANNELS: usize = 3;
-pub const PARAMS: usize = 4;
+pub const PARAMS: usize = 5;
 
 const MAX_STEPS: usize = 32;
 
@@ -49,13 +49,15 @@ pub static CONFIG: Config<PARAMS> = Config::new(
         Color::Violet,
     ],
 })
-.add_param(Param::VoltPerOct);
+.add_param(Param::VoltPerOct)
+.add_param(Param::Scale);
 
 pub struct Params {
     midi_channel: MidiChannel,
     midi_out: MidiOut,
     color: Color,
     vpo: VoltPerOct,
+    scale: AppScale,
 }
 
 impl Default for Params {
@@ -65,6 +67,7 @@ impl Default for Params {
             midi_out: MidiOut::default(),
             color: Color::Orange,
             vpo: VoltPerOct::Standard,
+            scale: AppScale::Global,
         }
     }
 }
@@ -79,6 +82,7 @@ impl AppParams for Params {
             midi_out: MidiOut::from_value(values[1]),
             color: Color::from_value(values[2]),
             vpo: VoltPerOct::from_value(values[3]),
+            scale: AppScale::from_value(values[4]),
         })
     }
 
@@ -88,6 +92,7 @@ impl AppParams for Params {
         vec.push(self.midi_out.into()).unwrap();
         vec.push(self.color.into()).unwrap();
         vec.push(self.vpo.into()).unwrap();
+        vec.push(self.scale.into()).unwrap();
         vec
     }
 }
@@ -342,7 +347,8 @@ pub async fn run(
     let faders = app.use_faders();
     let leds = app.use_leds();
     let mut clock = app.use_clock();
-    let quantizer = app.use_quantizer(pitch_range, vpo, false);
+    let scale = params.query(|p| p.scale);
+    let quantizer = app.use_quantizer(pitch_range, vpo, false, scale);
     let midi = app.use_midi_output(midi_out, midi_chan, false);
 
     let pitch_out = app.make_out_jack(0, pitch_range).await;
@@ -384,8 +390,8 @@ pub async fn run(
     leds.set(0, Led::Button, led_color, Brightness::Mid);
 
     // Fader latches for smooth takeover
-    let mut latches: [libfp::latch::AnalogLatch; CHANNELS] =
-        core::array::from_fn(|i| app.make_latch(faders.get_value_at(i)));
+    let mut latches: [AppLatch; CHANNELS] =
+        core::array::from_fn(|i| app.make_latch(i, faders.get_value_at(i)));
 
     // --- Clock task: step advance, quantise pitch, fire gate ---
     let clock_task = async {
@@ -501,7 +507,7 @@ pub async fn run(
 
                             midi.send_note_off(last_midi_note_glob.get()).await;
                             let velocity = if is_accent { 4095 } else { 2048 };
-                            midi.send_note_on(note, velocity).await;
+                            midi.send_pitch_note_on(note, &out, velocity).await;
                             last_midi_note_glob.set(note);
 
                             gate_out.set_high().await;
@@ -564,7 +570,7 @@ pub async fn run(
                 } else {
                     glide_current = target;
                 }
-                pitch_out.set_value(glide_current as u16);
+                pitch_out.set_pitch_value(glide_current as u16);
             }
         }
     };
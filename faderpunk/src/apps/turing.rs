@@ -14,8 +14,8 @@ use libfp::{
     ext::FromValue,
     latch::LatchLayer,
     utils::{rotate_select_bit, scale_to_12bit},
-    AppIcon, Brightness, ClockDivision, Color, Config, Curve, MidiCc, MidiChannel, MidiMode,
-    MidiNote, MidiOut, Param, Range, Value, VoltPerOct, APP_MAX_PARAMS,
+    AppIcon, AppScale, Brightness, ClockDivision, Color, Config, Curve, MidiCc, MidiChannel,
+    MidiMode, MidiNote, MidiOut, Param, Range, Value, VoltPerOct, APP_MAX_PARAMS,
 };
 
 use crate::app::{
@@ -24,7 +24,7 @@ use crate::app::{
 };
 
 pub const CHANNELS: usize = 1;
-pub const PARAMS: usize = 12;
+pub const PARAMS: usize = 13;
 
 pub static CONFIG: Config<PARAMS> = Config::new(
     "Turing",
@@ -64,7 +64,8 @@ pub static CONFIG: Config<PARAMS> = Config::new(
 .add_param(Param::MidiOut)
 .add_param(Param::bool { name: "Gate Out" })
 .add_param(Param::VoltPerOct)
-.add_param(Param::bool { name: "Bypass quantizer" });
+.add_param(Param::bool { name: "Bypass quantizer" })
+.add_param(Param::Scale);
 
 pub struct Params {
     midi_mode: MidiMode,
@@ -79,6 +80,7 @@ pub struct Params {
     gate_out: bool,
     vpo: VoltPerOct,
     bypass: bool,
+    scale: AppScale,
 }
 
 impl AppParams for Params {
@@ -99,6 +101,7 @@ impl AppParams for Params {
             gate_out: bool::from_value(values[9]),
             vpo: VoltPerOct::from_value(values[10]),
             bypass: bool::from_value(values[11]),
+            scale: AppScale::from_value(values[12]),
         })
     }
 
@@ -116,6 +119,7 @@ impl AppParams for Params {
         vec.push(self.gate_out.into()).unwrap();
         vec.push(self.vpo.into()).unwrap();
         vec.push(self.bypass.into()).unwrap();
+        vec.push(self.scale.into()).unwrap();
         vec
     }
 }
@@ -162,6 +166,7 @@ pub async fn wrapper(app: App<CHANNELS>, exit_signal: &'static Signal<NoopRawMut
             gate_out: false,
             vpo: VoltPerOct::Standard,
             bypass: false,
+            scale: AppScale::Global,
         },
     );
     let storage = ManagedStorage::<Storage>::new(app.app_id, app.layout_id);
@@ -223,7 +228,8 @@ pub async fn run(
     let leds = app.use_leds();
     let mut clock = app.use_clock();
     let die = app.use_die();
-    let quantizer = app.use_quantizer(range, vpo, bypass);
+    let scale = params.query(|p| p.scale);
+    let quantizer = app.use_quantizer(range, vpo, bypass, scale);
 
     let midi = app.use_midi_output(midi_out, midi_chan, nrpn);
 
@@ -342,7 +348,7 @@ pub async fn run(
                             let out = quantizer.get_quantized_note(att_reg).await;
                             let muted = glob_muted.get();
                             if !muted {
-                                cv_jack.as_ref().unwrap().set_value(pitch_as_counts(out, range, vpo));
+                                cv_jack.as_ref().unwrap().set_pitch_value(pitch_as_counts(out, range, vpo));
                                 leds.set(
                                     0,
                                     Led::Top,
@@ -352,7 +358,7 @@ pub async fn run(
                                 match midi_mode {
                                     MidiMode::Note => {
                                         let note = midi_note.set(out.as_midi() + base_note);
-                                        midi.send_note_on(note, 4095).await;
+                                        midi.send_pitch_note_on(note, &out, 4095).await;
                                     }
                                     MidiMode::Cc => {
                                         midi.send_cc(midi_cc, att_reg).await;
@@ -395,7 +401,7 @@ pub async fn run(
     };
 
     let fut2 = async {
-        let mut latch = app.make_latch(fader.get_value());
+        let mut latch = app.make_latch(0, fader.get_value());
         loop {
             fader.wait_for_change().await;
 
@@ -12,6 +12,9 @@ pub enum InputEvent {
     LoadSceneFromButton(u8),
     LoadSceneFromMidi(u8),
     SaveScene(u8),
+    /// Scene button held with Shift: flip a note of the custom scale on the
+    /// scale keyboard
+    ToggleScaleNote(u8),
     SceneButtonDown,
     SceneButtonUp,
     ShiftButtonDown,
@@ -47,7 +47,9 @@ use layout::{
     EvictionCmd, LayoutManager, FORCE_RESPAWN_SIGNAL, LAYOUT_EVICTION_REQ, LAYOUT_EVICTION_RES,
     LAYOUT_MANAGER, LAYOUT_WATCH,
 };
-use storage::{load_calibration_data, load_global_config, load_layout, migrate_fram};
+use storage::{
+    load_calibration_data, load_fader_calibration, load_global_config, load_layout, migrate_fram,
+};
 use tasks::{
     buttons::{is_channel_button_pressed, is_scene_button_pressed},
     fram::MAX_DATA_LEN,
@@ -92,6 +94,11 @@ static BUF_FRAM_WRITE: StaticCell<[u8; MAX_DATA_LEN]> = StaticCell::new();
 pub static QUANTIZER: LazyLock<Mutex<CriticalSectionRawMutex, Quantizer>> =
     LazyLock::new(|| Mutex::new(Quantizer::default()));
 
+/// Shared by apps with their own scale, rebuilt whenever a different app
+/// scale is needed
+pub static APP_QUANTIZER: LazyLock<Mutex<CriticalSectionRawMutex, Quantizer>> =
+    LazyLock::new(|| Mutex::new(Quantizer::default()));
+
 #[embassy_executor::task]
 async fn main_core1(spawner: Spawner) {
     use embassy_futures::select::{select3, Either3};
@@ -223,6 +230,7 @@ async fn main(spawner: Spawner) {
     migrate_fram().await;
 
     let calibration_data = load_calibration_data().await;
+    let fader_calibration = load_fader_calibration().await;
     let mut global_config = load_global_config().await;
 
     if is_channel_button_pressed(0) && is_channel_button_pressed(1) {
@@ -245,7 +253,16 @@ async fn main(spawner: Spawner) {
 
     tasks::input_handlers::start_input_handlers(&spawner).await;
 
-    tasks::max::start_max(&spawner, spi0, p.PIO0, mux_pins, p.PIN_17, calibration_data).await;
+    tasks::max::start_max(
+        &spawner,
+        spi0,
+        p.PIO0,
+        mux_pins,
+        p.PIN_17,
+        calibration_data,
+        fader_calibration,
+    )
+    .await;
 
     tasks::i2c::start_i2c(&spawner, p.I2C0, p.PIN_21, p.PIN_20).await;
 
@@ -1,17 +1,26 @@
-use core::{cell::RefCell, ops::Range};
+use core::{
+    cell::{Cell, RefCell},
+    ops::Range,
+};
 
 use embassy_futures::select::{select, Either};
 use embassy_sync::{blocking_mutex::raw::NoopRawMutex, signal::Signal};
 use embassy_time::Timer;
 use heapless::Vec;
 use minicbor::{Decode, Encode};
-use postcard::{from_bytes, to_slice};
+use portable_atomic::{AtomicU8, Ordering};
+use postcard::{from_bytes, take_from_bytes, to_slice};
 use serde::{de::Error as DeError, Deserialize, Deserializer, Serialize, Serializer};
 
 use libfp::{
-    types::{CalibFile, MaxCalibration, MaxCalibrationV1},
-    AuxJackMode, ClockConfig, ClockSrc, GlobalConfig, I2cMode, Layout, MidiConfig, QuantizerConfig,
-    ResetSrc, TakeoverMode, Value, APP_MAX_PARAMS, CALIB_FILE_MAGIC,
+    custom_curve::CUSTOM_CURVES,
+    types::{
+        CalibFile, CalibFileV2, FaderCalibration, MaxCalibration, MaxCalibrationV1,
+        MaxCalibrationV2, PiecewiseCalibration, PiecewiseFile,
+    },
+    AuxJackMode, ClockConfig, ClockSrc, CustomCurve, GlobalConfig, I2cMode, Layout, LedConfig,
+    MidiConfig, MidiOutConfig, MidiOutMode, QuantizerConfig, ResetSrc, TakeoverMode, Tuning, Value,
+    VoOctTrim, APP_MAX_PARAMS, CALIB_FILE_MAGIC, CALIB_POINTS_FILE_MAGIC, GLOBAL_CHANNELS,
 };
 
 use crate::{
@@ -27,8 +36,19 @@ const GLOBAL_CONFIG_RANGE: Range<u32> = 0..320;
 const RUNTIME_STATE_RANGE: Range<u32> = GLOBAL_CONFIG_RANGE.end..384;
 const LAYOUT_RANGE: Range<u32> = RUNTIME_STATE_RANGE.end..512;
 const CALIBRATION_RANGE: Range<u32> = LAYOUT_RANGE.end..1024;
-const APP_STORAGE_RANGE: Range<u32> = CALIBRATION_RANGE.end..122_880;
-const APP_PARAM_RANGE: Range<u32> = APP_STORAGE_RANGE.end..SCHEMA_HEADER_RANGE.start;
+const APP_STORAGE_RANGE: Range<u32> = CALIBRATION_RANGE.end..120_512;
+// The ranges below are taken from the end of the app storage, which 16 layout
+// ids never reach: 16 × 17 × 400 + 1024 = 109_824 < 120_512
+const LED_CONFIG_RANGE: Range<u32> = APP_STORAGE_RANGE.end..120_576;
+/// One slot of `CUSTOM_CURVE_MAX_BYTES` per curve
+const CUSTOM_CURVES_RANGE: Range<u32> = LED_CONFIG_RANGE.end..121_088;
+/// Not erased by a factory reset, like the calibration
+const FADER_CALIBRATION_RANGE: Range<u32> = CUSTOM_CURVES_RANGE.end..121_344;
+const VOCT_TRIMS_RANGE: Range<u32> = FADER_CALIBRATION_RANGE.end..121_856;
+/// Multi-point calibration, protected like `CALIBRATION_RANGE`
+const CALIBRATION_POINTS_RANGE: Range<u32> = VOCT_TRIMS_RANGE.end..122_368;
+const TUNING_RANGE: Range<u32> = CALIBRATION_POINTS_RANGE.end..122_880;
+const APP_PARAM_RANGE: Range<u32> = TUNING_RANGE.end..SCHEMA_HEADER_RANGE.start;
 /// Reserved region at the very end of FRAM holding `SchemaHeader`. Everything
 /// before it is data laid out by the firmware version that wrote it; this
 /// header is what tells us whether that layout matches the running firmware.
@@ -36,6 +56,7 @@ const SCHEMA_HEADER_RANGE: Range<u32> = 131_056..131_072;
 
 const APP_STORAGE_MAX_BYTES: u32 = 400;
 const APP_PARAMS_MAX_BYTES: u32 = 128;
+const CUSTOM_CURVE_MAX_BYTES: u32 = 128;
 const SCENES_PER_APP: u32 = 16;
 
 /// Magic bytes identifying a valid `SchemaHeader` (FaderPunk Schema Version).
@@ -70,6 +91,35 @@ struct ClockConfigV0 {
     internal_bpm: f32,
 }
 
+/// On-FRAM layout of `MidiOutConfig` before `send_mtc` was added.
+#[derive(Deserialize)]
+struct MidiOutConfigV0 {
+    send_clock: bool,
+    send_transport: bool,
+    mode: MidiOutMode,
+}
+
+/// On-FRAM layout of `MidiConfig` before the MTC and clock offset settings
+/// were added. Used by both legacy `GlobalConfig` layouts.
+#[derive(Deserialize)]
+struct MidiConfigV0 {
+    outs: [MidiOutConfigV0; 3],
+}
+
+impl From<MidiConfigV0> for MidiConfig {
+    fn from(old: MidiConfigV0) -> Self {
+        Self {
+            outs: old.outs.map(|o| MidiOutConfig {
+                send_clock: o.send_clock,
+                send_transport: o.send_transport,
+                mode: o.mode,
+                ..MidiOutConfig::new()
+            }),
+            ..MidiConfig::new()
+        }
+    }
+}
+
 /// `GlobalConfig` as written by v1.8.x: same as v1.7.0 plus `takeover_mode`,
 /// still missing `ClockConfig::swing_amount` (added in v1.9).
 #[derive(Deserialize)]
@@ -78,7 +128,7 @@ struct GlobalConfigV0 {
     clock: ClockConfigV0,
     i2c_mode: I2cMode,
     led_brightness: u8,
-    midi: MidiConfig,
+    midi: MidiConfigV0,
     quantizer: QuantizerConfig,
     takeover_mode: TakeoverMode,
 }
@@ -96,10 +146,13 @@ impl From<GlobalConfigV0> for GlobalConfig {
             },
             i2c_mode: old.i2c_mode,
             led_brightness: old.led_brightness,
-            midi: old.midi,
+            midi: old.midi.into(),
             quantizer: old.quantizer,
             takeover_mode: old.takeover_mode,
             custom_voct_curves: Default::default(),
+            i2c_map: Default::default(),
+            custom_scales: Default::default(),
+            fader: Default::default(),
         }
     }
 }
@@ -113,7 +166,7 @@ struct GlobalConfigV170 {
     clock: ClockConfigV0,
     i2c_mode: I2cMode,
     led_brightness: u8,
-    midi: MidiConfig,
+    midi: MidiConfigV0,
     quantizer: QuantizerConfig,
 }
 
@@ -130,10 +183,13 @@ impl From<GlobalConfigV170> for GlobalConfig {
             },
             i2c_mode: old.i2c_mode,
             led_brightness: old.led_brightness,
-            midi: old.midi,
+            midi: old.midi.into(),
             quantizer: old.quantizer,
             takeover_mode: TakeoverMode::Pickup,
             custom_voct_curves: Default::default(),
+            i2c_map: Default::default(),
+            custom_scales: Default::default(),
+            fader: Default::default(),
         }
     }
 }
@@ -195,6 +251,121 @@ pub async fn load_global_config() -> GlobalConfig {
     GlobalConfig::new()
 }
 
+pub async fn store_tuning(tuning: &Tuning) {
+    let res = write_with(TUNING_RANGE.start, |buf| cbor_encode(tuning, buf)).await;
+
+    if res.is_err() {
+        defmt::error!("Could not save Tuning");
+    }
+}
+
+pub async fn load_tuning() -> Tuning {
+    if let Ok(guard) = read_data(TUNING_RANGE.start).await {
+        let data = guard.data();
+        if !data.is_empty() {
+            if let Some(tuning) = cbor_decode::<Tuning>(data) {
+                if tuning.is_valid() {
+                    return tuning;
+                }
+            }
+        }
+    }
+    Tuning::new()
+}
+
+pub async fn store_led_config(config: &LedConfig) {
+    let res = write_with(LED_CONFIG_RANGE.start, |buf| cbor_encode(config, buf)).await;
+
+    if res.is_err() {
+        defmt::error!("Could not save LedConfig");
+    }
+}
+
+pub async fn load_led_config() -> LedConfig {
+    if let Ok(guard) = read_data(LED_CONFIG_RANGE.start).await {
+        let data = guard.data();
+        if !data.is_empty() {
+            if let Some(mut config) = cbor_decode::<LedConfig>(data) {
+                config.validate();
+                return config;
+            }
+        }
+    }
+    LedConfig::new()
+}
+
+pub async fn store_custom_curve(idx: usize, curve: &CustomCurve) {
+    let address = CUSTOM_CURVES_RANGE.start + idx as u32 * CUSTOM_CURVE_MAX_BYTES;
+    let res = write_with(address, |buf| cbor_encode(curve, buf)).await;
+
+    if res.is_err() {
+        defmt::error!("Could not save custom curve {}", idx);
+    }
+}
+
+pub async fn load_custom_curves() -> [CustomCurve; CUSTOM_CURVES] {
+    let mut curves = [CustomCurve::new(); CUSTOM_CURVES];
+    for (idx, curve) in curves.iter_mut().enumerate() {
+        let address = CUSTOM_CURVES_RANGE.start + idx as u32 * CUSTOM_CURVE_MAX_BYTES;
+        if let Ok(guard) = read_data(address).await {
+            let data = guard.data();
+            if !data.is_empty() {
+                if let Some(stored) = cbor_decode::<CustomCurve>(data) {
+                    if stored.is_valid() {
+                        *curve = stored;
+                    }
+                }
+            }
+        }
+    }
+    curves
+}
+
+pub async fn store_fader_calibration(calibration: &FaderCalibration) {
+    let res = write_with(FADER_CALIBRATION_RANGE.start, |buf| {
+        cbor_encode(calibration, buf)
+    })
+    .await;
+
+    if res.is_err() {
+        defmt::error!("Could not save fader calibration");
+    }
+}
+
+pub async fn load_fader_calibration() -> FaderCalibration {
+    if let Ok(guard) = read_data(FADER_CALIBRATION_RANGE.start).await {
+        let data = guard.data();
+        if !data.is_empty() {
+            if let Some(calibration) = cbor_decode::<FaderCalibration>(data) {
+                return calibration;
+            }
+        }
+    }
+    FaderCalibration::new()
+}
+
+pub async fn store_voct_trims(trims: &[VoOctTrim; GLOBAL_CHANNELS]) {
+    let res = write_with(VOCT_TRIMS_RANGE.start, |buf| cbor_encode(trims, buf)).await;
+
+    if res.is_err() {
+        defmt::error!("Could not save V/Oct trims");
+    }
+}
+
+pub async fn load_voct_trims() -> [VoOctTrim; GLOBAL_CHANNELS] {
+    if let Ok(guard) = read_data(VOCT_TRIMS_RANGE.start).await {
+        let data = guard.data();
+        if !data.is_empty() {
+            if let Some(trims) = cbor_decode::<[VoOctTrim; GLOBAL_CHANNELS]>(data) {
+                if trims.iter().all(VoOctTrim::is_valid) {
+                    return trims;
+                }
+            }
+        }
+    }
+    [VoOctTrim::default(); GLOBAL_CHANNELS]
+}
+
 pub async fn store_runtime_state(state: &RuntimeState) {
     let res = write_with(RUNTIME_STATE_RANGE.start, |buf| cbor_encode(state, buf)).await;
 
@@ -255,47 +426,104 @@ pub async fn store_calibration_data(data: &MaxCalibration) {
     if res.is_err() {
         defmt::error!("Could not save MaxCalibration");
     }
+
+    let points_to_save = PiecewiseFile::new(data.piecewise);
+
+    let res = write_with(CALIBRATION_POINTS_RANGE.start, |buf| {
+        Ok(to_slice(&points_to_save, &mut *buf)?.len())
+    })
+    .await;
+
+    if res.is_err() {
+        defmt::error!("Could not save PiecewiseCalibration");
+    }
 }
 
-pub async fn load_calibration_data() -> Option<MaxCalibration> {
-    if let Ok(guard) = read_data(CALIBRATION_RANGE.start).await {
+/// Stores multi-point corrections for the stored linear fits. False on an
+/// uncalibrated device, which has no fits to correct.
+pub async fn store_piecewise_calibration(data: PiecewiseCalibration) -> bool {
+    let calibrated = match read_data(CALIBRATION_RANGE.start).await {
+        Ok(guard) => guard.data().starts_with(&CALIB_FILE_MAGIC),
+        Err(_) => false,
+    };
+    calibrated
+        && write_with(CALIBRATION_POINTS_RANGE.start, |buf| {
+            Ok(to_slice(&PiecewiseFile::new(data), &mut *buf)?.len())
+        })
+        .await
+        .is_ok()
+}
+
+async fn load_piecewise_calibration() -> PiecewiseCalibration {
+    if let Ok(guard) = read_data(CALIBRATION_POINTS_RANGE.start).await {
         let data = guard.data();
-        if data.len() < 4 {
-            // Not enough data to be anything
-            return None;
+        if data.len() >= 4 && data[0..4] == CALIB_POINTS_FILE_MAGIC {
+            if let Ok(file) = from_bytes::<PiecewiseFile>(data) {
+                return file.data;
+            }
         }
+    }
+    PiecewiseCalibration::default()
+}
+
+pub async fn load_calibration_data() -> Option<MaxCalibration> {
+    let guard = read_data(CALIBRATION_RANGE.start).await.ok()?;
+    let data = guard.data();
+    if data.len() < 5 {
+        // Not enough data to be anything
+        return None;
+    }
 
-        if data[0..4] == CALIB_FILE_MAGIC {
-            if let Ok(file) = from_bytes::<CalibFile>(data) {
-                if file.version == 2 {
-                    return Some(file.data);
-                } else {
-                    defmt::warn!("Unsupported calibration file version: {}", file.version);
-                    return None;
+    if data[0..4] == CALIB_FILE_MAGIC {
+        // The version follows the magic
+        match data[4] {
+            2 => {
+                if let Ok(file) = from_bytes::<CalibFileV2>(data) {
+                    defmt::info!("V2 calibration data found, converting to new format.");
+                    let new_data = MaxCalibration::from(file.data);
+                    drop(guard);
+                    // Re-save the data in the new V3 format for next time
+                    store_calibration_data(&new_data).await;
+                    return Some(new_data);
+                }
+            }
+            3 => {
+                if let Ok(file) = from_bytes::<CalibFile>(data) {
+                    let mut new_data = file.data;
+                    drop(guard);
+                    new_data.piecewise = load_piecewise_calibration().await;
+                    return Some(new_data);
                 }
             }
-        } else if let Ok(old_data) = from_bytes::<MaxCalibrationV1>(data) {
-            defmt::info!("Old V1 calibration data found, converting to new format.");
-            let new_data = MaxCalibration::from(old_data);
-            // Re-save the data in the new V2 format for next time
-            store_calibration_data(&new_data).await;
-            return Some(new_data);
+            version => {
+                defmt::warn!("Unsupported calibration file version: {}", version);
+                return None;
+            }
         }
-
-        defmt::warn!("Failed to deserialize calibration data as any known format.");
+    } else if let Ok(old_data) = from_bytes::<MaxCalibrationV1>(data) {
+        defmt::info!("Old V1 calibration data found, converting to new format.");
+        let new_data = MaxCalibration::from(MaxCalibrationV2::from(old_data));
+        drop(guard);
+        // Re-save the data in the new V3 format for next time
+        store_calibration_data(&new_data).await;
+        return Some(new_data);
     }
+
+    defmt::warn!("Failed to deserialize calibration data as any known format.");
     None
 }
 
 async fn erase_range(range: Range<u32>) {
-    // Prevent erasing the calibration range
-    if range.start < CALIBRATION_RANGE.end && range.end > CALIBRATION_RANGE.start {
-        defmt::error!(
-            "CRITICAL: Attempted to erase Protected Calibration Range ({:?}) with request ({:?})",
-            CALIBRATION_RANGE,
-            range
-        );
-        return;
+    // Prevent erasing the calibration ranges
+    for protected in [CALIBRATION_RANGE, CALIBRATION_POINTS_RANGE] {
+        if range.start < protected.end && range.end > protected.start {
+            defmt::error!(
+                "CRITICAL: Attempted to erase Protected Calibration Range ({:?}) with request ({:?})",
+                protected,
+                range
+            );
+            return;
+        }
     }
 
     let mut addr = range.start;
@@ -471,6 +699,10 @@ pub async fn factory_reset() {
     erase_range(RUNTIME_STATE_RANGE).await;
     erase_range(LAYOUT_RANGE).await;
     erase_range(APP_STORAGE_RANGE).await;
+    erase_range(LED_CONFIG_RANGE).await;
+    erase_range(CUSTOM_CURVES_RANGE).await;
+    erase_range(VOCT_TRIMS_RANGE).await;
+    erase_range(TUNING_RANGE).await;
     erase_range(APP_PARAM_RANGE).await;
     erase_range(SCHEMA_HEADER_RANGE).await;
     write_schema_header(SCHEMA_VERSION).await;
@@ -630,6 +862,25 @@ impl AppParamsAddress {
     }
 }
 
+/// Value of `APP_TAKEOVER_PARAM` per layout id, set by the app's `ParamStore`
+static APP_TAKEOVER_PARAMS: [AtomicU8; GLOBAL_CHANNELS] =
+    [const { AtomicU8::new(0) }; GLOBAL_CHANNELS];
+
+/// The takeover mode picked in the params of the app at `layout_id`, `None` if it follows
+/// the global one
+pub fn app_takeover_mode(layout_id: u8) -> Option<TakeoverMode> {
+    TakeoverMode::from_app_param(
+        APP_TAKEOVER_PARAMS[layout_id as usize].load(Ordering::Relaxed) as usize,
+    )
+}
+
+fn takeover_param_index(value: Option<&Value>) -> usize {
+    match value {
+        Some(&Value::Enum(index)) if TakeoverMode::from_app_param(index).is_some() => index,
+        _ => 0,
+    }
+}
+
 pub trait AppParams: Sized + Send + Sync + 'static {
     fn from_values(values: &[Value]) -> Option<Self>;
     fn to_values(&self) -> Vec<Value, APP_MAX_PARAMS>;
@@ -639,46 +890,67 @@ pub struct ParamStore<P: AppParams> {
     app_id: u8,
     inner: RefCell<P>,
     layout_id: u8,
+    /// Number of the app's own values, `APP_TAKEOVER_PARAM` comes after them
+    /// in what is sent to the configurator
+    len: usize,
+    takeover: Cell<usize>,
 }
 
 impl<P: AppParams> ParamStore<P> {
     pub fn new(app_id: u8, layout_id: u8, initial: P) -> Self {
-        Self {
+        let len = initial.to_values().len();
+        let store = Self {
             app_id,
             inner: RefCell::new(initial),
             layout_id,
-        }
+            len,
+            takeover: Cell::new(0),
+        };
+        store.set_takeover(0);
+        store
     }
 
-    fn des(&self, data: &[u8]) -> Option<P> {
+    fn des(&self, data: &[u8]) -> Option<(P, usize)> {
         // First byte is app id
         if data[0] != self.app_id {
             return None;
         }
-        if let Ok(val) = from_bytes::<Vec<Value, APP_MAX_PARAMS>>(&data[1..]) {
-            return P::from_values(&val);
+        if let Ok((val, rest)) = take_from_bytes::<Vec<Value, APP_MAX_PARAMS>>(&data[1..]) {
+            // Params stored before the takeover param existed follow the global mode
+            let takeover = takeover_param_index(from_bytes::<Value>(rest).ok().as_ref());
+            return P::from_values(&val).map(|params| (params, takeover));
         }
         None
     }
 
+    fn set_takeover(&self, index: usize) {
+        self.takeover.set(index);
+        APP_TAKEOVER_PARAMS[self.layout_id as usize].store(index as u8, Ordering::Relaxed);
+    }
+
+    /// The app's values followed by the value of `APP_TAKEOVER_PARAM`
+    fn values(&self) -> Vec<Value, APP_MAX_PARAMS> {
+        let mut values = self.inner.borrow().to_values();
+        // Config::new leaves room for it
+        values.push(Value::Enum(self.takeover.get())).ok();
+        values
+    }
+
     async fn send_values(&self) {
-        let values = {
-            let guard = self.inner.borrow();
-            guard.to_values()
-        };
+        let values = self.values();
         APP_PARAM_CHANNEL.send((self.layout_id, values)).await;
     }
 
     async fn save(&self) {
         let address = AppParamsAddress::new(self.layout_id);
-        let values = {
-            let guard = self.inner.borrow_mut();
-            guard.to_values()
-        };
+        let values = self.inner.borrow().to_values();
+        let takeover = Value::Enum(self.takeover.get());
+        // The takeover param is stored after the app's values rather than among
+        // them, so a param appended to the app later never reads it
         let res = write_with(address.into(), |buf| {
             buf[0] = self.app_id;
-            let len = to_slice(&values, &mut buf[1..])?.len();
-            Ok(len + 1)
+            let len = to_slice(&values, &mut buf[1..])?.len() + 1;
+            Ok(len + to_slice(&takeover, &mut buf[len..])?.len())
         })
         .await;
 
@@ -692,10 +964,11 @@ impl<P: AppParams> ParamStore<P> {
         if let Ok(guard) = read_data(address.into()).await {
             let data = guard.data();
             if !data.is_empty() {
-                if let Some(val) = self.des(data) {
+                if let Some((val, takeover)) = self.des(data) {
                     drop(guard);
                     let mut inner = self.inner.borrow_mut();
                     *inner = val;
+                    self.set_takeover(takeover);
                 }
             }
         }
@@ -714,7 +987,7 @@ impl<P: AppParams> ParamStore<P> {
         loop {
             match APP_PARAM_SIGNALS[self.layout_id as usize].wait().await {
                 AppParamCmd::SetAppParams { values } => {
-                    let mut current_values = self.inner.borrow().to_values();
+                    let mut current_values = self.values();
                     let mut changed = false;
 
                     for (index, &value) in values.iter().enumerate() {
@@ -729,6 +1002,7 @@ impl<P: AppParams> ParamStore<P> {
                     if changed {
                         let updated = if let Some(new_params) = P::from_values(&current_values) {
                             *self.inner.borrow_mut() = new_params;
+                            self.set_takeover(takeover_param_index(current_values.get(self.len)));
                             true
                         } else {
                             false
@@ -12,6 +12,7 @@ use portable_atomic::{AtomicBool, Ordering};
 
 use crate::events::{EventPubSubPublisher, InputEvent, EVENT_PUBSUB};
 use crate::tasks::clock::{TransportCmd, TRANSPORT_CMD_CHANNEL};
+use crate::tasks::input_handlers::is_custom_scale_key;
 
 const LONG_PRESS_DURATION_MS: u64 = 500;
 
@@ -78,7 +79,17 @@ async fn process_button(i: usize, mut button: Input<'_>, event_publisher: &Event
             continue;
         }
 
-        if BUTTON_PRESSED[16].load(Ordering::Relaxed) {
+        if BUTTON_PRESSED[16].load(Ordering::Relaxed)
+            && BUTTON_PRESSED[17].load(Ordering::Relaxed)
+            && is_custom_scale_key(i)
+        {
+            // Shift held before the scene button - edit the custom scale
+            event_publisher
+                .publish(InputEvent::ToggleScaleNote(i as u8))
+                .await;
+
+            button.wait_for_rising_edge().await;
+        } else if BUTTON_PRESSED[16].load(Ordering::Relaxed) {
             // Special mode when button 16 is pressed - handle scene load/save
             match select(
                 button.wait_for_rising_edge(),
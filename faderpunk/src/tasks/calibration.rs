@@ -6,7 +6,11 @@ use max11300::config::{ConfigMode5, ConfigMode7, Mode, Port, ADCRANGE, AVR, DACR
 use portable_atomic::Ordering;
 
 use libfp::{
-    types::{MaxCalibration, RegressionValuesInput, RegressionValuesOutput},
+    types::{
+        apply_regression, calib_point_value, calib_points_from_residuals, CalibPoints,
+        MaxCalibration, PiecewiseCalibration, RegressionValuesInput, RegressionValuesOutput,
+        CALIB_POINTS, CALIB_POINTS_JACKS,
+    },
     Brightness, Color, CALIBRATION_SCALE_FACTOR,
 };
 
@@ -28,6 +32,20 @@ const VOLTAGES_IN_0_10V: [f32; 3] = [0.0, 2.0, 10.0];
 const VALUES_NEG5_5V: [u16; 3] = [819, 2048, 3276];
 const VOLTAGES_NEG5_5V: [f32; 3] = [-3.0, 0.0, 3.0];
 const LED_POS: [Led; 3] = [Led::Button, Led::Bottom, Led::Top];
+/// Multi-point calibration: 0V, then every volt up to 10V
+const VALUES_IN_POINTS: [u16; CALIB_POINTS + 1] = calib_point_values();
+const VOLTAGES_IN_POINTS: [f32; CALIB_POINTS + 1] =
+    [0.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 10.0];
+
+const fn calib_point_values() -> [u16; CALIB_POINTS + 1] {
+    let mut values = [0; CALIB_POINTS + 1];
+    let mut k = 0;
+    while k < CALIB_POINTS {
+        values[k + 1] = calib_point_value(k) as u16;
+        k += 1;
+    }
+    values
+}
 
 fn set_led_color(ch: usize, pos: Led, color: Color) {
     set_led_mode(
@@ -76,22 +94,28 @@ async fn configure_jack(ch: usize, mode: Mode) {
         .await;
 }
 
-async fn run_manual_input_calibration() -> RegressionValuesInput {
+/// Measures the input. With `multi_point` the 0-10V range is measured at every
+/// volt and also gets piecewise corrections.
+async fn run_manual_input_calibration(
+    multi_point: bool,
+) -> (RegressionValuesInput, Option<CalibPoints>) {
     let mut input_results = RegressionValuesInput::default();
+    let mut input_points = None;
     let adc_ranges = [ADCRANGE::Rg0_10v, ADCRANGE::RgNeg5_5v];
-    let voltages_arrays = [VOLTAGES_IN_0_10V, VOLTAGES_NEG5_5V];
-    let values_arrays = [VALUES_IN_0_10V, VALUES_NEG5_5V];
     set_led_color(0, Led::Button, Color::Cyan);
     info!("Plug a good voltage source into channel 0, then press button");
     wait_for_button_press(0).await;
     for (range_index, &adc_range) in adc_ranges.iter().enumerate() {
-        let mut measured_values: [u16; 3] = Default::default();
-        let target_values = values_arrays[range_index];
+        let (voltages, target_values): (&[f32], &[u16]) = match range_index {
+            0 if multi_point => (&VOLTAGES_IN_POINTS, &VALUES_IN_POINTS),
+            0 => (&VOLTAGES_IN_0_10V, &VALUES_IN_0_10V),
+            _ => (&VOLTAGES_NEG5_5V, &VALUES_NEG5_5V),
+        };
+        let count = target_values.len();
+        let mut measured_values = [0_f32; CALIB_POINTS + 1];
+        let mut targets = [0_f32; CALIB_POINTS + 1];
 
-        for (j, (&voltage, &target_value)) in voltages_arrays[range_index]
-            .iter()
-            .zip(target_values.iter())
-            .enumerate()
+        for (j, (&voltage, &target_value)) in voltages.iter().zip(target_values.iter()).enumerate()
         {
             // Configure first channel to be input
             configure_jack(
@@ -103,12 +127,14 @@ async fn run_manual_input_calibration() -> RegressionValuesInput {
                 )),
             )
             .await;
-            let pos = LED_POS[j];
+            // The three LEDs show the progress through the points
+            let pos = LED_POS[j * LED_POS.len() / count];
             flash_led(0, pos, Color::Cyan, None);
             info!("Set voltage source to {}V, then press button", voltage);
             wait_for_button_press(0).await;
             let value = MAX_VALUES_ADC[0].load(Ordering::Relaxed);
-            measured_values[j] = value;
+            measured_values[j] = value as f32;
+            targets[j] = target_value as f32;
             set_led_color(0, pos, Color::Cyan);
             let error = target_value as i16 - value as i16;
             info!("Target value: {}", target_value);
@@ -117,10 +143,9 @@ async fn run_manual_input_calibration() -> RegressionValuesInput {
             info!("------------------");
         }
 
-        if let Ok(results) = linear_regression::<f32, f32, f32>(
-            &measured_values.map(|v| v as f32),
-            &target_values.map(|v| v as f32),
-        ) {
+        if let Ok(results) =
+            linear_regression::<f32, f32, f32>(&measured_values[..count], &targets[..count])
+        {
             // Convert f32 results to i64 fixed-point format
             let slope = (results.0 * CALIBRATION_SCALE_FACTOR as f32) as i64;
             let intercept = (results.1 * CALIBRATION_SCALE_FACTOR as f32) as i64;
@@ -130,6 +155,17 @@ async fn run_manual_input_calibration() -> RegressionValuesInput {
                 range_index,
                 (slope, intercept)
             );
+            if range_index == 0 && multi_point {
+                // What the linear fit is off by at every volt, 0V being skipped
+                let residuals: [f32; CALIB_POINTS] = core::array::from_fn(|k| {
+                    let fitted =
+                        apply_regression((slope, intercept), measured_values[k + 1] as u16);
+                    targets[k + 1] - fitted as f32
+                });
+                let points = calib_points_from_residuals(&residuals);
+                info!("Multi-point corrections for the input: {}", points);
+                input_points = Some(points);
+            }
         } else {
             // Blink LED red if calibration didn't succeeed
             flash_led(0, Led::Button, Color::Red, None);
@@ -139,11 +175,19 @@ async fn run_manual_input_calibration() -> RegressionValuesInput {
         }
     }
 
-    input_results
+    (input_results, input_points)
 }
 
-async fn run_manual_output_calibration() -> RegressionValuesOutput {
+/// Measures every output. With `multi_point` the 0-10V range of the jacks is
+/// measured at every volt and also gets piecewise corrections.
+async fn run_manual_output_calibration(
+    multi_point: bool,
+) -> (
+    RegressionValuesOutput,
+    [Option<CalibPoints>; CALIB_POINTS_JACKS],
+) {
     let mut output_results = RegressionValuesOutput::default();
+    let mut output_points = [None; CALIB_POINTS_JACKS];
 
     for i in 0..CHANNELS {
         for &p in LED_POS.iter() {
@@ -155,8 +199,6 @@ async fn run_manual_output_calibration() -> RegressionValuesOutput {
     wait_for_button_press(0).await;
 
     let dac_ranges = [DACRANGE::Rg0_10v, DACRANGE::RgNeg5_5v];
-    let voltages_arrays = [VOLTAGES_OUT_0_10V, VOLTAGES_NEG5_5V];
-    let values_arrays = [VALUES_OUT_0_10V, VALUES_NEG5_5V];
 
     // Bottom LED = range 0 (0-10V) progress, Top LED = range 1 (±5V) progress
     const RANGE_LED: [Led; 2] = [Led::Bottom, Led::Top];
@@ -175,8 +217,16 @@ async fn run_manual_output_calibration() -> RegressionValuesOutput {
         reset_led(ui_no, Led::Top);
 
         for (range_idx, &dac_range) in dac_ranges.iter().enumerate() {
-            let mut set_values: [u16; 3] = Default::default();
-            let target_values = values_arrays[range_idx];
+            let (voltages, target_values): (&[f32], &[u16]) = match range_idx {
+                0 if multi_point && chan < CALIB_POINTS_JACKS => {
+                    (&VOLTAGES_IN_POINTS[1..], &VALUES_IN_POINTS[1..])
+                }
+                0 => (&VOLTAGES_OUT_0_10V, &VALUES_OUT_0_10V),
+                _ => (&VOLTAGES_NEG5_5V, &VALUES_NEG5_5V),
+            };
+            let count = target_values.len();
+            let mut set_values = [0_f32; CALIB_POINTS];
+            let mut targets = [0_f32; CALIB_POINTS];
 
             let port = Port::try_from(chan).unwrap();
             MAX_CHANNEL
@@ -189,10 +239,8 @@ async fn run_manual_output_calibration() -> RegressionValuesOutput {
 
             info!("Calibrating DAC range index: {}", range_idx);
 
-            for (j, (&voltage, &target_value)) in voltages_arrays[range_idx]
-                .iter()
-                .zip(target_values.iter())
-                .enumerate()
+            for (j, (&voltage, &target_value)) in
+                voltages.iter().zip(target_values.iter()).enumerate()
             {
                 let pos = RANGE_LED[range_idx];
                 flash_led(ui_no, pos, Color::Green, None);
@@ -250,7 +298,8 @@ async fn run_manual_output_calibration() -> RegressionValuesOutput {
                 }
 
                 set_led_color(ui_no, RANGE_LED[range_idx], Color::Green);
-                set_values[j] = value;
+                set_values[j] = value as f32;
+                targets[j] = target_value as f32;
                 let error = target_value as i16 - value as i16;
                 info!("Target value: {}", target_value);
                 info!("Read value: {}", value);
@@ -258,10 +307,9 @@ async fn run_manual_output_calibration() -> RegressionValuesOutput {
                 info!("------------------");
             }
 
-            if let Ok(results) = linear_regression::<f32, f32, f32>(
-                &set_values.map(|v| v as f32),
-                &target_values.map(|v| v as f32),
-            ) {
+            if let Ok(results) =
+                linear_regression::<f32, f32, f32>(&set_values[..count], &targets[..count])
+            {
                 // Convert f32 results to i64 fixed-point format
                 let slope = (results.0 * CALIBRATION_SCALE_FACTOR as f32) as i64;
                 let intercept = (results.1 * CALIBRATION_SCALE_FACTOR as f32) as i64;
@@ -270,6 +318,16 @@ async fn run_manual_output_calibration() -> RegressionValuesOutput {
                     "Linear regression results for outputs channel {} range {}: ({}, {})",
                     chan, range_idx, slope, intercept
                 );
+                if count == CALIB_POINTS {
+                    // What the linear fit is off by at every volt
+                    let residuals: [f32; CALIB_POINTS] = core::array::from_fn(|k| {
+                        let fitted = apply_regression((slope, intercept), target_values[k]);
+                        set_values[k] - fitted as f32
+                    });
+                    let points = calib_points_from_residuals(&residuals);
+                    info!("Multi-point corrections for channel {}: {}", chan, points);
+                    output_points[chan] = Some(points);
+                }
             } else {
                 // Blink LED red if calibration didn't succeeed
                 flash_led(ui_no, Led::Button, Color::Red, None);
@@ -295,12 +353,10 @@ async fn run_manual_output_calibration() -> RegressionValuesOutput {
         i += 1;
     }
 
-    output_results
+    (output_results, output_points)
 }
 
-async fn run_automatic_calibration(
-    receiver: &mut I2cFollowerReceiver,
-) -> (RegressionValuesInput, RegressionValuesOutput) {
+async fn run_automatic_calibration(receiver: &mut I2cFollowerReceiver) -> MaxCalibration {
     for i in 0..CHANNELS {
         for &p in LED_POS.iter() {
             reset_led(i, p);
@@ -310,6 +366,8 @@ async fn run_automatic_calibration(
     info!("Waiting for calibration data...");
 
     let mut current_ch: Option<usize> = None;
+    // Multi-point corrections are optional and come before the linear fits
+    let mut piecewise = PiecewiseCalibration::default();
     loop {
         match receiver.receive().await {
             I2cFollowerMessage::ChannelUpdate(ch) => {
@@ -326,7 +384,15 @@ async fn run_automatic_calibration(
                 if let Some(prev) = current_ch {
                     reset_led(prev, Led::Button);
                 }
-                return (input_values, output_values);
+                return MaxCalibration {
+                    inputs: input_values,
+                    outputs: output_values,
+                    piecewise,
+                };
+            }
+            I2cFollowerMessage::SetPiecewise(points) => {
+                info!("Received multi-point calibration data.");
+                piecewise = points;
             }
             I2cFollowerMessage::Start => {}
         }
@@ -334,10 +400,10 @@ async fn run_automatic_calibration(
 }
 
 // 0-10V steps: 0, 2, 4, 6, 8, 10 V (counts = V * 409.5)
-const TEST_STEPS_0_10V: [u16; 6] = [0, 819, 1638, 2457, 3276, 4095];
+pub const TEST_STEPS_0_10V: [u16; 6] = [0, 819, 1638, 2457, 3276, 4095];
 // ±5V steps: -4, -2, 0, +2, +4 V — capped at ±4V to stay within calibrated range
 // count = (V + 5) * 409.5
-const TEST_STEPS_NEG5_5V: [u16; 5] = [410, 1229, 2048, 2867, 3686];
+pub const TEST_STEPS_NEG5_5V: [u16; 5] = [410, 1229, 2048, 2867, 3686];
 
 async fn run_test_mode(calibration_data: &MaxCalibration) -> ! {
     // Initial state: both passthrough and channels start on 0-10V
@@ -376,12 +442,10 @@ async fn run_test_mode(calibration_data: &MaxCalibration) -> ! {
     // Set initial channel output (step 0, 0-10V)
     let ideal = TEST_STEPS_0_10V[0];
     for (ch, dac_val) in MAX_VALUES_DAC.iter().enumerate().take(CHANNELS).skip(2) {
-        let (slope, intercept) = calibration_data.outputs[ch][0];
         let hw = if ideal == 0 {
             0
         } else {
-            ((ideal as i64 * slope + intercept + CALIBRATION_SCALE_FACTOR / 2) >> 16).clamp(0, 4095)
-                as u16
+            calibration_data.output_value(ch, 0, ideal)
         };
         dac_val.store(hw, Ordering::Relaxed);
     }
@@ -395,16 +459,12 @@ async fn run_test_mode(calibration_data: &MaxCalibration) -> ! {
 
     loop {
         // --- Passthrough: same range as channel outputs ---
-        let (is, ii) = calibration_data.inputs[range];
-        let (os1, oi1) = calibration_data.outputs[1][range];
         let raw = MAX_VALUES_ADC[0].load(Ordering::Relaxed);
-        let ideal_adc =
-            ((raw as i64 * is + ii + CALIBRATION_SCALE_FACTOR / 2) >> 16).clamp(0, 4095) as u16;
+        let ideal_adc = calibration_data.input_value(range, raw);
         let hw_dac = if range == 0 && ideal_adc == 0 {
             0
         } else {
-            ((ideal_adc as i64 * os1 + oi1 + CALIBRATION_SCALE_FACTOR / 2) >> 16).clamp(0, 4095)
-                as u16
+            calibration_data.output_value(1, range, ideal_adc)
         };
         MAX_VALUES_DAC[1].store(hw_dac, Ordering::Relaxed);
 
@@ -478,12 +538,10 @@ async fn run_test_mode(calibration_data: &MaxCalibration) -> ! {
                 v
             };
             for (ch, dac_val) in MAX_VALUES_DAC.iter().enumerate().take(CHANNELS).skip(2) {
-                let (slope, intercept) = calibration_data.outputs[ch][range];
                 let hw = if range == 0 && ideal == 0 {
                     0
                 } else {
-                    ((ideal as i64 * slope + intercept + CALIBRATION_SCALE_FACTOR / 2) >> 16)
-                        .clamp(0, 4095) as u16
+                    calibration_data.output_value(ch, range, ideal)
                 };
                 dac_val.store(hw, Ordering::Relaxed);
             }
@@ -520,20 +578,28 @@ pub async fn run_calibration(mut msg_receiver: I2cFollowerReceiver) {
     )
     .await
     {
-        Either::First(_) => {
-            // Manual calibration
+        Either::First(is_shift_pressed) => {
+            // Manual calibration, multi-point when started with shift held
             info!("Starting manual calibration...");
-            let inputs = run_manual_input_calibration().await;
-            let outputs = run_manual_output_calibration().await;
-
-            MaxCalibration { inputs, outputs }
+            if is_shift_pressed {
+                info!("Measuring the 0-10V range at every volt");
+            }
+            let (inputs, input_points) = run_manual_input_calibration(is_shift_pressed).await;
+            let (outputs, output_points) = run_manual_output_calibration(is_shift_pressed).await;
+
+            MaxCalibration {
+                inputs,
+                outputs,
+                piecewise: PiecewiseCalibration {
+                    input: input_points,
+                    outputs: output_points,
+                },
+            }
         }
         Either::Second(_) => {
             // Automatic calibration
             info!("Starting automatic calibration...");
-            let (inputs, outputs) = run_automatic_calibration(&mut msg_receiver).await;
-
-            MaxCalibration { inputs, outputs }
+            run_automatic_calibration(&mut msg_receiver).await
         }
     };
 
@@ -18,7 +18,8 @@ use midly::live::SystemRealtime;
 use portable_atomic::{AtomicBool, Ordering};
 
 use libfp::{
-    utils::bpm_to_clock_duration, AuxJackMode, ClockSrc, GlobalConfig, MidiOut, MidiOutConfig,
+    mtc::QuarterFrameDecoder, utils::bpm_to_clock_duration, AuxJackMode, ClockSrc, GlobalConfig,
+    MidiOut, MidiOutConfig, MtcChaseSrc, MtcFps,
 };
 
 use max11300::config::Port;
@@ -28,6 +29,7 @@ use crate::{
     tasks::{
         max::{MaxCmd, MAX_CHANNEL},
         midi::{MidiRealtimeMsg, MIDI_CLOCK_CHANNEL, MIDI_TRANSPORT_CHANNEL},
+        mtc::{mtc_locate, run_mtc_generator},
     },
     Spawner, GLOBAL_CONFIG_WATCH,
 };
@@ -79,6 +81,9 @@ pub enum ClockInEvent {
     Stop(ClockSrc),
     Reset(ClockSrc),
     Continue(ClockSrc),
+    /// Move the transport to a 24ppqn tick position without affecting the run
+    /// state (Song Position Pointer, MTC chase)
+    Locate(ClockSrc, u64),
 }
 
 impl ClockInEvent {
@@ -89,7 +94,8 @@ impl ClockInEvent {
             | Self::Start(s)
             | Self::Stop(s)
             | Self::Reset(s)
-            | Self::Continue(s) => *s,
+            | Self::Continue(s)
+            | Self::Locate(s, _) => *s,
         }
     }
     pub fn is_clock(&self) -> bool {
@@ -131,8 +137,22 @@ pub enum SyncEngineEvent {
         source: ClockSrc,
         timestamp: Instant,
     },
-    /// A transport command from an external source (MIDI Start/Stop/Continue/Reset)
+    /// A transport command from an external source (MIDI Start/Stop/Continue/Reset/SPP)
     Transport(ClockInEvent),
+    /// An MTC quarter frame (`piece` 0-7 and its data nibble)
+    QuarterFrame {
+        source: ClockSrc,
+        piece: u8,
+        nibble: u8,
+        timestamp: Instant,
+    },
+    /// An MTC Full Frame: the sender located to `frames` while stopped or
+    /// shuttling
+    FullFrame {
+        source: ClockSrc,
+        frames: u64,
+        fps: MtcFps,
+    },
 }
 
 pub static SYNC_ENGINE_CHANNEL: Channel<ThreadModeRawMutex, SyncEngineEvent, 16> = Channel::new();
@@ -174,6 +194,15 @@ const PENDING_EMISSIONS_CAPACITY: usize = 64;
 /// silently skip ticks — short enough to be musically instantaneous.
 const CATCHUP_SPACING: Duration = Duration::from_micros(500);
 
+/// Drift between the chased MTC position and the internal clock that makes
+/// the chase re-locate, about one frame. Smaller drift is left alone so
+/// quarter-frame jitter doesn't jump the tick position around.
+const MTC_CHASE_TOLERANCE: Duration = Duration::from_millis(33);
+
+/// Gap in incoming quarter frames after which a chasing clock stops. Quarter
+/// frames arrive at least every ~10ms while the sender plays.
+const MTC_CHASE_TIMEOUT: Duration = Duration::from_millis(250);
+
 /// Swung absolute offset of tick `i` (in `[0, 2H]`) from the start of the swing
 /// window. Used by both the internal clock (to schedule the next tick directly)
 /// and the external clock (to schedule the whole window on its anchor pulse).
@@ -240,10 +269,38 @@ fn effective_ppqn(ext_ppqn: u8) -> u8 {
     }
 }
 
+/// True if the internal clock follows MTC arriving from `source`
+fn is_mtc_chase_src(config: &GlobalConfig, source: ClockSrc) -> bool {
+    config.clock.clock_src == ClockSrc::Internal
+        && config.midi.mtc_chase != MtcChaseSrc::None
+        && ClockSrc::from(config.midi.mtc_chase) == source
+}
+
+/// Where the internal clock picks up when located to `position` (time since
+/// tick zero) at `now`: the index of the next tick and its unswung time.
+fn locate_schedule(position: Duration, tick: Duration, now: Instant) -> (u64, Instant) {
+    let tick_ticks = tick.as_ticks().max(1);
+    let next = position.as_ticks().div_ceil(tick_ticks);
+    let at = now + Duration::from_ticks(next * tick_ticks - position.as_ticks());
+    (next, at)
+}
+
+/// How far ahead of the beat the internal clock sends MIDI ticks, so outputs
+/// with a negative clock offset can be early. External clocks can't be
+/// anticipated.
+pub fn midi_clock_lead(config: &GlobalConfig) -> Duration {
+    if config.clock.clock_src == ClockSrc::Internal {
+        Duration::from_millis(config.midi.clock_lead_ms() as u64)
+    } else {
+        Duration::from_ticks(0)
+    }
+}
+
 pub async fn start_clock(spawner: &Spawner, aux_inputs: AuxInputs) {
     spawner.spawn(run_clock_sources(aux_inputs)).unwrap();
     spawner.spawn(run_clock_gatekeeper()).unwrap();
     spawner.spawn(metronome()).unwrap();
+    spawner.spawn(run_mtc_generator()).unwrap();
 }
 
 pub(crate) async fn make_ext_clock_loop(pin: &mut Input<'_>, clock_src: ClockSrc) {
@@ -362,7 +419,8 @@ async fn run_clock_gatekeeper() {
                     | ClockInEvent::MidiTick(s)
                     | ClockInEvent::Start(s)
                     | ClockInEvent::Stop(s)
-                    | ClockInEvent::Continue(s) => (s == config.clock.clock_src, s),
+                    | ClockInEvent::Continue(s)
+                    | ClockInEvent::Locate(s, _) => (s == config.clock.clock_src, s),
                     ClockInEvent::Reset(s) => (s == config.clock.reset_src.into(), s),
                 };
 
@@ -427,6 +485,7 @@ async fn run_clock_gatekeeper() {
                         is_running = true;
                         clock_publisher.publish(ClockEvent::Start).await;
                         midi_rt_event = Some(SystemRealtime::Continue);
+                        mtc_locate(tick_counter.wrapping_add(1), true);
                     }
                     // (Re-)start the clock. Full phase reset
                     ClockInEvent::Start(_) => {
@@ -437,12 +496,14 @@ async fn run_clock_gatekeeper() {
                         analog_tick_counters = [0; 3];
                         send_analog_reset(&spawner, &config).await;
                         midi_rt_event = Some(SystemRealtime::Start);
+                        mtc_locate(0, true);
                     }
                     // Stop the clock. No phase reset
                     ClockInEvent::Stop(_) => {
                         is_running = false;
                         clock_publisher.publish(ClockEvent::Stop).await;
                         midi_rt_event = Some(SystemRealtime::Stop);
+                        mtc_locate(tick_counter.wrapping_add(1), false);
                     }
                     // Reset the phase without affecting the run state
                     ClockInEvent::Reset(_) => {
@@ -451,6 +512,12 @@ async fn run_clock_gatekeeper() {
                         analog_tick_counters = [0; 3];
                         send_analog_reset(&spawner, &config).await;
                         midi_rt_event = Some(SystemRealtime::Reset);
+                        mtc_locate(0, is_running);
+                    }
+                    // Jump the tick position, the next tick carries `tick`
+                    ClockInEvent::Locate(_, tick) => {
+                        tick_counter = tick.wrapping_sub(1);
+                        mtc_locate(tick, is_running);
                     }
                 }
 
@@ -534,6 +601,12 @@ async fn run_unified_clock_engine() {
     // to straight passthrough (not predicted — e.g. swing was 0 or there
     // was no measured period at the anchor). Cleared at window rollover.
     let mut window_predicted = false;
+    // Index of the next unswung internal tick, kept to compare against the
+    // chased MTC position
+    let mut midi_tick_pos: u64 = 0;
+    let mut mtc_decoder = QuarterFrameDecoder::default();
+    // Last complete MTC timecode while chasing, for the chase timeout
+    let mut mtc_last_at: Option<Instant> = None;
     let mut config = config;
 
     // If clock was already running at startup (persisted state) with internal source,
@@ -550,13 +623,17 @@ async fn run_unified_clock_engine() {
         // - External + running: earliest of watchdog (`next_tick_at`) and the
         //   front of the pending-emission queue
         // - Otherwise: pend forever
+        // Unswung MIDI ticks go out ahead of the beat by the clock lead
+        let next_midi_tick_due = next_midi_tick_at
+            .checked_sub(midi_clock_lead(&config))
+            .unwrap_or(next_midi_tick_at);
         let timer_fut = async {
             if !is_running {
                 core::future::pending::<()>().await;
                 return;
             }
             if config.clock.clock_src == ClockSrc::Internal {
-                Timer::at(next_tick_at.min(next_midi_tick_at)).await;
+                Timer::at(next_tick_at.min(next_midi_tick_due)).await;
             } else if last_pulse.is_some() && measured_ext_period.is_some() {
                 // Watchdog is armed; also consider any pending emission
                 // that may be due sooner.
@@ -637,6 +714,11 @@ async fn run_unified_clock_engine() {
                     ext_pulse_div_count = 0;
                 }
 
+                if config.midi.mtc_chase != new_config.midi.mtc_chase {
+                    mtc_decoder = QuarterFrameDecoder::default();
+                    mtc_last_at = None;
+                }
+
                 config = new_config;
             }
 
@@ -654,11 +736,15 @@ async fn run_unified_clock_engine() {
 
                 if is_running != next_is_running {
                     if next_is_running {
-                        window_start_at =
-                            Instant::now() + Duration::from_millis(TICK_RESET_DELAY as u64);
+                        // The beat starts late by the clock lead, so the first
+                        // early MIDI tick isn't in the past
+                        window_start_at = Instant::now()
+                            + Duration::from_millis(TICK_RESET_DELAY as u64)
+                            + midi_clock_lead(&config);
                         tick_in_window = 0;
                         next_tick_at = window_start_at;
                         next_midi_tick_at = window_start_at;
+                        midi_tick_pos = 0;
                         clock_in_sender
                             .send(ClockInEvent::Start(ClockSrc::Internal))
                             .await;
@@ -710,9 +796,89 @@ async fn run_unified_clock_engine() {
                             tick_in_window = 0;
                             ext_pulse_div_count = 0;
                         }
+                        ClockInEvent::Locate(_, tick) => {
+                            // Song positions land on 16th notes, which keeps
+                            // the swing window phase intact
+                            pending_emissions.clear();
+                            tick_in_window = (tick % (2 * SWING_HALF_INTERVAL) as u64) as u32;
+                            ext_pulse_div_count = 0;
+                        }
                         _ => {}
                     }
                 }
+                SyncEngineEvent::QuarterFrame { .. } | SyncEngineEvent::FullFrame { .. } => {
+                    let (position, at, start) = match sync_event {
+                        SyncEngineEvent::QuarterFrame {
+                            source,
+                            piece,
+                            nibble,
+                            timestamp,
+                        } => {
+                            if !is_mtc_chase_src(&config, source) {
+                                continue;
+                            }
+                            let Some((qf, fps)) = mtc_decoder.feed(piece, nibble) else {
+                                continue;
+                            };
+                            mtc_last_at = Some(timestamp);
+                            let position = Duration::from_micros(fps.quarter_frames_to_micros(qf));
+                            if is_running {
+                                // Internal position at `timestamp`, from the
+                                // unswung tick schedule
+                                let until_next = next_midi_tick_at.as_ticks() as i64
+                                    - timestamp.as_ticks() as i64;
+                                let clock_pos = (current_tick_duration.as_ticks() * midi_tick_pos)
+                                    as i64
+                                    - until_next;
+                                let drift = (clock_pos - position.as_ticks() as i64).unsigned_abs();
+                                if drift <= MTC_CHASE_TOLERANCE.as_ticks() {
+                                    continue;
+                                }
+                            }
+                            (position, timestamp, !is_running)
+                        }
+                        SyncEngineEvent::FullFrame {
+                            source,
+                            frames,
+                            fps,
+                        } => {
+                            if !is_mtc_chase_src(&config, source) {
+                                continue;
+                            }
+                            mtc_decoder = QuarterFrameDecoder::default();
+                            let position =
+                                Duration::from_micros(fps.quarter_frames_to_micros(frames * 4));
+                            (position, Instant::now(), false)
+                        }
+                        _ => continue,
+                    };
+
+                    // Re-anchor the internal schedule on the chased position
+                    let (next, next_at) = locate_schedule(position, current_tick_duration, at);
+                    midi_tick_pos = next;
+                    next_midi_tick_at = next_at;
+                    tick_in_window = (next % (2 * SWING_HALF_INTERVAL) as u64) as u32;
+                    window_start_at = next_at
+                        .checked_sub(current_tick_duration * tick_in_window)
+                        .unwrap_or(next_at);
+                    next_tick_at = window_start_at
+                        + swung_offset(
+                            tick_in_window,
+                            current_tick_duration,
+                            config.clock.swing_amount,
+                        );
+                    clock_in_sender
+                        .send(ClockInEvent::Locate(ClockSrc::Internal, next))
+                        .await;
+
+                    if start {
+                        clock_in_sender
+                            .send(ClockInEvent::Continue(ClockSrc::Internal))
+                            .await;
+                        is_running = true;
+                        spawner.spawn(store_clock_running(true)).ok();
+                    }
+                }
                 SyncEngineEvent::Pulse { source, timestamp } => {
                     // Check if this pulse is from the reset source
                     let reset_src: ClockSrc = config.clock.reset_src.into();
@@ -721,6 +887,7 @@ async fn run_unified_clock_engine() {
                         pending_emissions.clear();
                         tick_in_window = 0;
                         ext_pulse_div_count = 0;
+                        midi_tick_pos = 0;
                         continue;
                     }
 
@@ -934,12 +1101,28 @@ async fn run_unified_clock_engine() {
             Either4::Fourth(_) => {
                 if config.clock.clock_src == ClockSrc::Internal && is_running {
                     let now = Instant::now();
+                    // MTC chase: the sender stopped
+                    if let Some(last) = mtc_last_at {
+                        if config.midi.mtc_chase != MtcChaseSrc::None
+                            && now.saturating_duration_since(last) > MTC_CHASE_TIMEOUT
+                        {
+                            clock_in_sender
+                                .send(ClockInEvent::Stop(ClockSrc::Internal))
+                                .await;
+                            mtc_last_at = None;
+                            mtc_decoder = QuarterFrameDecoder::default();
+                            is_running = false;
+                            spawner.spawn(store_clock_running(false)).ok();
+                            continue;
+                        }
+                    }
                     // Unswung MIDI clock: fires at the nominal (straight) cadence
-                    if now >= next_midi_tick_at {
+                    if now >= next_midi_tick_due {
                         clock_in_sender
                             .send(ClockInEvent::MidiTick(ClockSrc::Internal))
                             .await;
                         next_midi_tick_at += current_tick_duration;
+                        midi_tick_pos += 1;
                     }
                     // Swung internal tick: fires at the swing-adjusted time
                     if now >= next_tick_at {
@@ -1,8 +1,10 @@
+use core::future::Future;
+
 use embassy_futures::select::{select, Either};
 use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
 use embassy_sync::channel::Channel;
 use embassy_sync::signal::Signal;
-use embassy_time::{with_timeout, Duration};
+use embassy_time::{with_timeout, Duration, Instant, Timer};
 use heapless::Vec;
 use portable_atomic::Ordering;
 use postcard::{from_bytes, to_slice};
@@ -11,18 +13,38 @@ use libfp::sysex::{
     pack_7bit, unpack_7bit, MAX_PLAIN_SIZE, MAX_SYSEX_FRAME, SYSEX_EOX, SYSEX_HEADER, SYSEX_START,
 };
 use libfp::{
-    AuxJackMode, ConfigMsgIn, ConfigMsgOut, Layout, Value, APP_MAX_PARAMS, GLOBAL_CHANNELS,
+    custom_curve::{custom_curve, set_custom_curve, CUSTOM_CURVES},
+    types::{CalibFile, FaderCalibration, PiecewiseFile},
+    AuxJackMode, CalibrationCheckPoint, ConfigMsgIn, ConfigMsgOut, Layout, Param, Range, Value,
+    VoOctTrim, APP_MAX_PARAMS, APP_TAKEOVER_PARAM, GLOBAL_CHANNELS,
+};
+use max11300::config::{
+    ConfigMode0, ConfigMode3, ConfigMode5, ConfigMode7, Mode, Port, ADCRANGE, AVR, DACRANGE,
+    NSAMPLES,
 };
-use max11300::config::{ConfigMode0, ConfigMode3, ConfigMode5, Mode, Port, DACRANGE};
 
 use crate::apps::{get_channels, get_config, REGISTERED_APP_IDS};
 use crate::layout::{EvictionCmd, LAYOUT_EVICTION_REQ, LAYOUT_EVICTION_RES, LAYOUT_WATCH};
-use crate::storage::factory_reset;
-use crate::tasks::global_config::{get_global_config, GLOBAL_CONFIG_WATCH};
-use crate::tasks::max::{MaxCmd, MAX_CHANNEL, MAX_VALUES_DAC};
+use crate::storage::{
+    factory_reset, load_calibration_data, store_calibration_data, store_fader_calibration,
+    store_piecewise_calibration,
+};
+use crate::tasks::calibration::{TEST_STEPS_0_10V, TEST_STEPS_NEG5_5V};
+use crate::tasks::global_config::{
+    get_global_config, CUSTOM_CURVES_SIGNAL, GLOBAL_CONFIG_WATCH, LED_CONFIG_SIGNAL, TUNING_SIGNAL,
+    VOCT_TRIMS_SIGNAL,
+};
+use crate::tasks::i2c::scan_i2c;
+use crate::tasks::leds::LED_CONFIG;
+use crate::tasks::max::{
+    MaxCmd, FADER_CALIBRATION, FADER_SWEEP, MAX_CHANNEL, MAX_VALUES_ADC, MAX_VALUES_DAC, VOCT_TRIMS,
+};
 use crate::tasks::midi::{SharedUsbSender, CONFIG_CABLE};
+use crate::tasks::midi_din::din_stats;
+use crate::tasks::midi_log::{midi_log_count, midi_log_entry, set_midi_log_filter};
 use crate::tasks::voct_freq::{VOCT_MEASURE_REQ, VOCT_MEASURE_RES};
 use crate::version::FIRMWARE_VERSION;
+use crate::QUANTIZER;
 
 use super::transport::USB_MAX_PACKET_SIZE;
 
@@ -96,14 +118,21 @@ pub async fn start_config_loop<'a>(usb_tx: &'a SharedUsbSender<'a>) {
                     .await
             }
             ConfigMsgIn::GetAllApps => {
-                let configs = REGISTERED_APP_IDS.map(get_config);
                 let mut res = proto
-                    .send_msg(ConfigMsgOut::BatchMsgStart(configs.len()))
+                    .send_msg(ConfigMsgOut::BatchMsgStart(REGISTERED_APP_IDS.len()))
                     .await;
-                for (app_id, channels, config_meta) in configs.into_iter().flatten() {
+                // Built one at a time so the configs of all apps are never held across an await
+                for (app_id, channels, (len, name, description, color, icon, params)) in
+                    REGISTERED_APP_IDS.into_iter().filter_map(get_config)
+                {
                     if res.is_err() {
                         break;
                     }
+                    // Every app gets the takeover param after its own ones, see ParamStore
+                    let mut all_params: Vec<Param, APP_MAX_PARAMS> = Vec::new();
+                    all_params.extend_from_slice(params).ok();
+                    all_params.push(APP_TAKEOVER_PARAM).ok();
+                    let config_meta = (len + 1, name, description, color, icon, &all_params[..]);
                     res = proto
                         .send_msg(ConfigMsgOut::AppConfig(app_id, channels, config_meta))
                         .await;
@@ -232,6 +261,149 @@ pub async fn start_config_loop<'a>(usb_tx: &'a SharedUsbSender<'a>) {
                 handle_release_voct_output(&mut proto, &mut pending_voct_eviction, output_jack)
                     .await
             }
+            ConfigMsgIn::GetMidiOutStats => {
+                proto
+                    .send_msg(ConfigMsgOut::MidiOutStats(din_stats()))
+                    .await
+            }
+            ConfigMsgIn::GetMidiLog { since } => {
+                let count = midi_log_count(since);
+                let mut res = proto.send_msg(ConfigMsgOut::BatchMsgStart(count)).await;
+                // Entries keep arriving while the batch goes out, so walk by
+                // sequence number rather than from a snapshot
+                let mut next = since;
+                for _ in 0..count {
+                    if res.is_err() {
+                        break;
+                    }
+                    let Some(entry) = midi_log_entry(next) else {
+                        break;
+                    };
+                    next = entry.seq.wrapping_add(1);
+                    res = proto.send_msg(ConfigMsgOut::MidiLogEntry(entry)).await;
+                }
+                if res.is_ok() {
+                    res = proto.send_msg(ConfigMsgOut::BatchMsgEnd).await;
+                }
+                res
+            }
+            ConfigMsgIn::SetMidiLogFilter(filter) => {
+                set_midi_log_filter(filter);
+                Ok(())
+            }
+            ConfigMsgIn::ScanI2c => {
+                let found = scan_i2c().await;
+                let mut res = proto
+                    .send_msg(ConfigMsgOut::BatchMsgStart(found.len()))
+                    .await;
+                for &(device, address) in found.iter() {
+                    if res.is_err() {
+                        break;
+                    }
+                    res = proto
+                        .send_msg(ConfigMsgOut::I2cDevice { device, address })
+                        .await;
+                }
+                if res.is_ok() {
+                    res = proto.send_msg(ConfigMsgOut::BatchMsgEnd).await;
+                }
+                res
+            }
+            ConfigMsgIn::GetTuning => {
+                let tuning = *QUANTIZER.get().lock().await.get_tuning();
+                proto.send_msg(ConfigMsgOut::Tuning(tuning)).await
+            }
+            ConfigMsgIn::SetTuning(tuning) => {
+                if tuning.is_valid() {
+                    TUNING_SIGNAL.signal(tuning);
+                }
+                Ok(())
+            }
+            ConfigMsgIn::GetVoOctTrims => {
+                let trims = VOCT_TRIMS.lock(|t| *t.borrow());
+                proto.send_msg(ConfigMsgOut::VoOctTrims(trims)).await
+            }
+            ConfigMsgIn::SetVoOctTrims(trims) => {
+                if trims.iter().all(VoOctTrim::is_valid) {
+                    VOCT_TRIMS_SIGNAL.signal(trims);
+                }
+                Ok(())
+            }
+            ConfigMsgIn::GetFaderCalibration => {
+                let calibration = FADER_CALIBRATION.lock(|c| *c.borrow());
+                proto
+                    .send_msg(ConfigMsgOut::FaderCalibration(calibration))
+                    .await
+            }
+            ConfigMsgIn::StartFaderCalibration => {
+                FADER_SWEEP
+                    .lock(|sweep| sweep.replace(Some((Instant::now(), [(u16::MAX, 0); 16]))));
+                Ok(())
+            }
+            ConfigMsgIn::FinishFaderCalibration => {
+                let calibration = finish_fader_calibration().await;
+                proto
+                    .send_msg(ConfigMsgOut::FaderCalibration(calibration))
+                    .await
+            }
+            ConfigMsgIn::GetCustomCurves => {
+                let curves = core::array::from_fn(custom_curve);
+                proto.send_msg(ConfigMsgOut::CustomCurves(curves)).await
+            }
+            ConfigMsgIn::SetCustomCurve(idx, curve) => {
+                if (idx as usize) < CUSTOM_CURVES && curve.is_valid() {
+                    set_custom_curve(idx as usize, &curve);
+                    CUSTOM_CURVES_SIGNAL.signal(());
+                }
+                Ok(())
+            }
+            ConfigMsgIn::GetLedConfig => {
+                let config = LED_CONFIG.lock(|c| c.get());
+                proto.send_msg(ConfigMsgOut::LedConfig(config)).await
+            }
+            ConfigMsgIn::SetLedConfig(mut config) => {
+                config.validate();
+                LED_CONFIG_SIGNAL.signal(config);
+                Ok(())
+            }
+            ConfigMsgIn::GetCalibFile => {
+                let file = load_calibration_data().await.map(CalibFile::new);
+                proto.send_msg(ConfigMsgOut::CalibFile(file)).await
+            }
+            ConfigMsgIn::GetPiecewiseFile => {
+                let file = load_calibration_data()
+                    .await
+                    .map(|data| PiecewiseFile::new(data.piecewise));
+                proto.send_msg(ConfigMsgOut::PiecewiseFile(file)).await
+            }
+            ConfigMsgIn::SetCalibFile(file) => {
+                let valid = file.is_valid();
+                if valid {
+                    // Also clears the multi-point corrections, which were
+                    // measured against the old fits
+                    store_calibration_data(&file.data).await;
+                }
+                proto.send_msg(ConfigMsgOut::CalibrationStored(valid)).await
+            }
+            ConfigMsgIn::SetPiecewiseFile(file) => {
+                let stored = file.is_valid() && store_piecewise_calibration(file.data).await;
+                proto
+                    .send_msg(ConfigMsgOut::CalibrationStored(stored))
+                    .await
+            }
+            ConfigMsgIn::RunCalibrationCheck {
+                output_jack,
+                input_jack,
+            } => {
+                handle_calibration_check(
+                    &mut proto,
+                    &layout,
+                    &mut pending_voct_eviction,
+                    output_jack,
+                    input_jack,
+                )
+                .await
+            }
         };
         if let Err(err) = res {
             defmt::warn!("Failed to send config response: {}", err);
@@ -493,6 +665,135 @@ async fn handle_release_voct_output(
     proto.send_msg(ConfigMsgOut::VoOctOutputSet).await
 }
 
+/// End the fader sweep started by `StartFaderCalibration` and store the ends
+/// of the faders that went far enough.
+async fn finish_fader_calibration() -> FaderCalibration {
+    let sweep = FADER_SWEEP.lock(|sweep| sweep.take()).map(|(_, ends)| ends);
+    let mut calibration = FADER_CALIBRATION.lock(|c| *c.borrow());
+    let Some(ends) = sweep else {
+        return calibration;
+    };
+
+    let mut changed = false;
+    for (fader, &(min, max)) in ends.iter().enumerate() {
+        changed |= calibration.set_from_sweep(fader, min, max);
+    }
+    if changed {
+        FADER_CALIBRATION.lock(|c| c.replace(calibration));
+        store_fader_calibration(&calibration).await;
+    }
+    calibration
+}
+
+/// Drive `output_jack` through the test mode steps in both ranges and read
+/// each step back on `input_jack`, sending one `CalibrationCheckPoint` per
+/// step. The calibration applies on both jacks like for any app, so the
+/// residual is what apps actually get, leaving out the output's V/Oct trim.
+/// Apps on either jack are evicted for the duration and both jacks are left
+/// in high-Z.
+async fn handle_calibration_check(
+    proto: &mut ConfigTransport<'_>,
+    layout: &Layout,
+    pending_eviction: &mut Option<(u8, EvictedApp)>,
+    output_jack: u8,
+    input_jack: u8,
+) -> Result<(), ProtocolError> {
+    let ports = Port::try_from(output_jack as usize)
+        .and_then(|output| Port::try_from(input_jack as usize).map(|input| (output, input)));
+    let (output_port, input_port) = match ports {
+        Ok(ports) if output_jack < 16 && input_jack < 16 && output_jack != input_jack => ports,
+        _ => {
+            proto.send_msg(ConfigMsgOut::BatchMsgStart(0)).await?;
+            return proto.send_msg(ConfigMsgOut::BatchMsgEnd).await;
+        }
+    };
+
+    if let Some((_, evicted)) = pending_eviction.take() {
+        restore_jack_owner(evicted).await;
+    }
+    let evicted_output = evict_jack_owner(layout, output_jack).await;
+    // Both jacks might belong to the same app
+    let evicted_input = match (evicted_output, find_jack_owner(layout, input_jack)) {
+        (Some((_, output_start, _, _)), Some((_, input_start, _, _)))
+            if output_start == input_start =>
+        {
+            None
+        }
+        _ => evict_jack_owner(layout, input_jack).await,
+    };
+
+    let mut res = proto
+        .send_msg(ConfigMsgOut::BatchMsgStart(
+            TEST_STEPS_0_10V.len() + TEST_STEPS_NEG5_5V.len(),
+        ))
+        .await;
+
+    let ranges = [
+        (Range::_0_10V, &TEST_STEPS_0_10V[..]),
+        (Range::_Neg5_5V, &TEST_STEPS_NEG5_5V[..]),
+    ];
+    for (range, steps) in ranges {
+        let (dac_range, adc_range) = match range {
+            Range::_Neg5_5V => (DACRANGE::RgNeg5_5v, ADCRANGE::RgNeg5_5v),
+            _ => (DACRANGE::Rg0_10v, ADCRANGE::Rg0_10v),
+        };
+        MAX_CHANNEL
+            .send(MaxCmd::ConfigurePort {
+                port: output_port,
+                mode: Mode::Mode5(ConfigMode5(dac_range)),
+                gpo_level: None,
+            })
+            .await;
+        MAX_CHANNEL
+            .send(MaxCmd::ConfigurePort {
+                port: input_port,
+                mode: Mode::Mode7(ConfigMode7(
+                    AVR::InternalRef,
+                    adc_range,
+                    NSAMPLES::Samples16,
+                )),
+                gpo_level: None,
+            })
+            .await;
+
+        for &ideal in steps {
+            if res.is_err() {
+                break;
+            }
+            MAX_VALUES_DAC[output_jack as usize].store(ideal, Ordering::Relaxed);
+            // Let the output and the averaging ADC settle
+            Timer::after_millis(50).await;
+            let measured = MAX_VALUES_ADC[input_jack as usize].load(Ordering::Relaxed);
+            res = proto
+                .send_msg(ConfigMsgOut::CalibrationCheckPoint(
+                    CalibrationCheckPoint::new(range, ideal, measured),
+                ))
+                .await;
+        }
+    }
+
+    for port in [output_port, input_port] {
+        MAX_CHANNEL
+            .send(MaxCmd::ConfigurePort {
+                port,
+                mode: Mode::Mode0(ConfigMode0),
+                gpo_level: None,
+            })
+            .await;
+    }
+    if let Some(evicted) = evicted_output {
+        restore_jack_owner(evicted).await;
+    }
+    if let Some(evicted) = evicted_input {
+        restore_jack_owner(evicted).await;
+    }
+
+    if res.is_ok() {
+        res = proto.send_msg(ConfigMsgOut::BatchMsgEnd).await;
+    }
+    res
+}
+
 impl<'a> ConfigTransport<'a> {
     fn new(usb_tx: &'a SharedUsbSender<'a>) -> Self {
         ConfigTransport {
@@ -519,7 +820,18 @@ impl<'a> ConfigTransport<'a> {
         from_bytes(&self.plain_buf[2..plain_len]).map_err(|_| ProtocolError::DecodingError)
     }
 
-    async fn send_msg(&mut self, msg: ConfigMsgOut<'_>) -> Result<(), ProtocolError> {
+    /// Encodes `msg` right away, so the returned future doesn't hold it
+    /// across the USB writes
+    fn send_msg(
+        &mut self,
+        msg: ConfigMsgOut<'_>,
+    ) -> impl Future<Output = Result<(), ProtocolError>> + use<'_, 'a> {
+        let frame_len = self.encode_msg(msg);
+        async move { self.send_frame(frame_len?).await }
+    }
+
+    /// Encode `msg` into a SysEx frame in `frame_buf`, returning its length
+    fn encode_msg(&mut self, msg: ConfigMsgOut<'_>) -> Result<usize, ProtocolError> {
         let payload_len = to_slice(&msg, &mut self.plain_buf[2..])
             .map_err(|_| ProtocolError::EncodingError)?
             .len();
@@ -536,7 +848,10 @@ impl<'a> ConfigTransport<'a> {
         .map_err(|_| ProtocolError::BufferTooSmall)?;
         let frame_len = 1 + SYSEX_HEADER.len() + packed_len + 1;
         self.frame_buf[frame_len - 1] = SYSEX_EOX;
+        Ok(frame_len)
+    }
 
+    async fn send_frame(&mut self, frame_len: usize) -> Result<(), ProtocolError> {
         // Packetize into cable-1 USB-MIDI event packets, flushed per 64-byte
         // USB packet. The sender mutex is released between USB packets so
         // performance MIDI (cable 0) interleaves during long transfers.
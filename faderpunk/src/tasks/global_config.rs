@@ -1,20 +1,24 @@
 use embassy_executor::Spawner;
 use embassy_futures::select::{select, Either};
-use embassy_sync::{blocking_mutex::raw::CriticalSectionRawMutex, watch::Watch};
+use embassy_sync::{blocking_mutex::raw::CriticalSectionRawMutex, signal::Signal, watch::Watch};
 use embassy_time::Timer;
 use libfp::{
-    deadzone_curve_inverse, AuxJackMode, Curve, GlobalConfig, Key, Note, DEADZONE_CENTER,
-    LED_BRIGHTNESS_RANGE,
+    custom_curve::{custom_curve, set_custom_curve},
+    deadzone_curve_inverse, AuxJackMode, Curve, GlobalConfig, Key, LedConfig, Note, Tuning,
+    VoOctTrim, DEADZONE_CENTER, GLOBAL_CHANNELS, LED_BRIGHTNESS_RANGE,
 };
 use max11300::config::{ConfigMode0, ConfigMode3, Mode, Port};
 use portable_atomic::Ordering;
 
 use crate::layout::FORCE_RESPAWN_SIGNAL;
-use crate::storage::store_global_config;
+use crate::storage::{
+    load_custom_curves, load_led_config, load_tuning, load_voct_trims, store_custom_curve,
+    store_global_config, store_led_config, store_tuning, store_voct_trims,
+};
 use crate::tasks::buttons::is_scene_button_pressed;
 use crate::tasks::input_handlers::{show_config_top_leds, show_scale_keyboard};
-use crate::tasks::leds::LED_BRIGHTNESS;
-use crate::tasks::max::{MaxCmd, MAX_CHANNEL};
+use crate::tasks::leds::{LED_BRIGHTNESS, LED_CONFIG};
+use crate::tasks::max::{MaxCmd, FADER_HYSTERESIS, FADER_SMOOTHING, MAX_CHANNEL, VOCT_TRIMS};
 use crate::QUANTIZER;
 
 // Receivers: unified clock engine (1), clock gatekeeper (1)
@@ -27,6 +31,9 @@ const QUANTIZER_TONIC_FADER: usize = 4;
 const SWING_FADER: usize = 14;
 const INTERNAL_BPM_FADER: usize = 15;
 
+// Built-in scales, then the custom ones
+const QUANTIZER_KEY_STEP: u16 = 4095 / Key::COUNT as u16;
+
 fn val_to_swing(val: u16) -> i8 {
     let val = Curve::Deadzone.at(val) as i32;
     (((val * 70) / 4095) - 35).clamp(-35, 35) as i8
@@ -49,6 +56,19 @@ pub static GLOBAL_CONFIG_WATCH: Watch<
     GLOBAL_CONFIG_WATCH_SUBSCRIBERS,
 > = Watch::new_with(GlobalConfig::new());
 
+/// A validated tuning from the configurator, applied and stored by the tuning storer
+pub static TUNING_SIGNAL: Signal<CriticalSectionRawMutex, Tuning> = Signal::new();
+
+/// Validated V/Oct trims from the configurator, applied and stored by the trims storer
+pub static VOCT_TRIMS_SIGNAL: Signal<CriticalSectionRawMutex, [VoOctTrim; GLOBAL_CHANNELS]> =
+    Signal::new();
+
+/// Raised after a custom curve was applied, the curve storer stores the changed ones
+pub static CUSTOM_CURVES_SIGNAL: Signal<CriticalSectionRawMutex, ()> = Signal::new();
+
+/// A validated LED config from the configurator, applied and stored by the LED config storer
+pub static LED_CONFIG_SIGNAL: Signal<CriticalSectionRawMutex, LedConfig> = Signal::new();
+
 pub fn get_global_config() -> GlobalConfig {
     // unwrap is fine here as it is always initialized (new_with)
     GLOBAL_CONFIG_WATCH.try_get().unwrap()
@@ -58,7 +78,9 @@ pub fn get_fader_value_from_config(chan: usize, config: &GlobalConfig) -> u16 {
     match chan {
         INTERNAL_BPM_FADER => (((config.clock.internal_bpm - 45.0) * 16.0) as u16).clamp(0, 4095),
         SWING_FADER => swing_to_val(config.clock.swing_amount),
-        QUANTIZER_KEY_FADER => (config.quantizer.key as u16 * 241).clamp(0, 4095),
+        QUANTIZER_KEY_FADER => {
+            (config.quantizer.key.index() as u16 * QUANTIZER_KEY_STEP).clamp(0, 4095)
+        }
         QUANTIZER_TONIC_FADER => (config.quantizer.tonic as u16 * 342).clamp(0, 4095),
         LED_BRIGHTNESS_FADER => {
             let brightness_range = (LED_BRIGHTNESS_RANGE.end - LED_BRIGHTNESS_RANGE.start) as u32;
@@ -100,7 +122,7 @@ pub fn set_global_config_via_chan(chan: usize, val: u16) {
         QUANTIZER_KEY_FADER => {
             global_config_sender.send_if_modified(|c| {
                 if let Some(config) = c {
-                    let new_key: Key = unsafe { core::mem::transmute((val / 241).min(16) as u8) };
+                    let new_key = Key::from_index((val / QUANTIZER_KEY_STEP) as u8);
                     if config.quantizer.key != new_key {
                         config.quantizer.key = new_key;
                         return true;
@@ -149,9 +171,45 @@ pub fn set_global_config_via_chan(chan: usize, val: u16) {
     }
 }
 
+/// Flips `note` (0 = C) of the selected custom scale. Does nothing for the
+/// built-in scales.
+pub fn toggle_custom_scale_note(note: Note) {
+    GLOBAL_CONFIG_WATCH.sender().send_if_modified(|c| {
+        if let Some(config) = c {
+            if let Key::Custom(idx) = config.quantizer.key {
+                if let Some(scale) = config.custom_scales.get_mut(idx as usize) {
+                    scale.toggle(note);
+                    return true;
+                }
+            }
+        }
+        false
+    });
+}
+
+/// Sets the global tonic and, when `key` is given, the scale. Used by apps
+/// following the key from MIDI or CV.
+pub fn set_global_key(key: Option<Key>, tonic: Note) {
+    GLOBAL_CONFIG_WATCH.sender().send_if_modified(|c| {
+        if let Some(config) = c {
+            let key = key.unwrap_or(config.quantizer.key);
+            if config.quantizer.key != key || config.quantizer.tonic != tonic {
+                config.quantizer.key = key;
+                config.quantizer.tonic = tonic;
+                return true;
+            }
+        }
+        false
+    });
+}
+
 pub async fn start_global_config(spawner: &Spawner) {
     spawner.spawn(config_storer()).unwrap();
     spawner.spawn(global_config_change()).unwrap();
+    spawner.spawn(tuning_storer()).unwrap();
+    spawner.spawn(voct_trims_storer()).unwrap();
+    spawner.spawn(custom_curves_storer()).unwrap();
+    spawner.spawn(led_config_storer()).unwrap();
 }
 
 async fn set_aux_config(aux_port: usize, aux_jack_mode: &AuxJackMode) {
@@ -198,6 +256,37 @@ async fn config_storer() {
     }
 }
 
+#[embassy_executor::task]
+async fn tuning_storer() {
+    loop {
+        let tuning = TUNING_SIGNAL.wait().await;
+        QUANTIZER.get().lock().await.set_tuning(tuning);
+        store_tuning(&tuning).await;
+    }
+}
+
+#[embassy_executor::task]
+async fn voct_trims_storer() {
+    let trims = load_voct_trims().await;
+    VOCT_TRIMS.lock(|t| t.replace(trims));
+    loop {
+        let trims = VOCT_TRIMS_SIGNAL.wait().await;
+        VOCT_TRIMS.lock(|t| t.replace(trims));
+        store_voct_trims(&trims).await;
+    }
+}
+
+#[embassy_executor::task]
+async fn led_config_storer() {
+    let config = load_led_config().await;
+    LED_CONFIG.lock(|c| c.set(config));
+    loop {
+        let config = LED_CONFIG_SIGNAL.wait().await;
+        LED_CONFIG.lock(|c| c.set(config));
+        store_led_config(&config).await;
+    }
+}
+
 #[embassy_executor::task]
 async fn global_config_change() {
     let mut receiver = GLOBAL_CONFIG_WATCH.receiver().unwrap();
@@ -205,10 +294,14 @@ async fn global_config_change() {
 
     // Initialize leds with loaded config
     LED_BRIGHTNESS.store(old.led_brightness, Ordering::Relaxed);
+    FADER_HYSTERESIS.store(old.fader.hysteresis, Ordering::Relaxed);
+    FADER_SMOOTHING.store(old.fader.smoothing, Ordering::Relaxed);
 
-    // Initialize quantizer with loaded config
+    // Initialize quantizer with loaded config and tuning
+    let tuning = load_tuning().await;
     let mut quantizer = QUANTIZER.get().lock().await;
-    quantizer.set_scale(old.quantizer.key, old.quantizer.tonic);
+    quantizer.set_tuning(tuning);
+    quantizer.set_scale_with_scales(old.quantizer.key, old.quantizer.tonic, &old.custom_scales);
     drop(quantizer);
 
     for (i, aux_jack) in old.aux.iter().enumerate() {
@@ -224,11 +317,16 @@ async fn global_config_change() {
         let config = receiver.changed().await;
         if config.quantizer.key != old.quantizer.key
             || config.quantizer.tonic != old.quantizer.tonic
+            || config.custom_scales != old.custom_scales
         {
             let mut quantizer = QUANTIZER.get().lock().await;
-            quantizer.set_scale(config.quantizer.key, config.quantizer.tonic);
+            quantizer.set_scale_with_scales(
+                config.quantizer.key,
+                config.quantizer.tonic,
+                &config.custom_scales,
+            );
             if is_scene_button_pressed() {
-                show_scale_keyboard(config.quantizer.key, config.quantizer.tonic).await;
+                show_scale_keyboard(&config).await;
                 show_config_top_leds(&config).await;
             }
         }
@@ -238,6 +336,10 @@ async fn global_config_change() {
         if config.led_brightness != old.led_brightness {
             LED_BRIGHTNESS.store(config.led_brightness, Ordering::Relaxed);
         }
+        if config.fader != old.fader {
+            FADER_HYSTERESIS.store(config.fader.hysteresis, Ordering::Relaxed);
+            FADER_SMOOTHING.store(config.fader.smoothing, Ordering::Relaxed);
+        }
 
         for (i, (new_aux, old_aux)) in config.aux.iter().zip(old.aux.iter()).enumerate() {
             if new_aux != old_aux {
@@ -254,3 +356,21 @@ async fn global_config_change() {
         old = config;
     }
 }
+
+#[embassy_executor::task]
+async fn custom_curves_storer() {
+    let mut stored = load_custom_curves().await;
+    for (idx, curve) in stored.iter().enumerate() {
+        set_custom_curve(idx, curve);
+    }
+    loop {
+        CUSTOM_CURVES_SIGNAL.wait().await;
+        for (idx, curve) in stored.iter_mut().enumerate() {
+            let current = custom_curve(idx);
+            if current != *curve {
+                store_custom_curve(idx, &current).await;
+                *curve = current;
+            }
+        }
+    }
+}
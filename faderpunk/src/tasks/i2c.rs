@@ -1,32 +1,52 @@
+use core::future::pending;
+
 use defmt::{error, info};
 use embassy_executor::Spawner;
+use embassy_futures::select::{select3, Either3};
 use embassy_rp::i2c::{self, Async};
 use embassy_rp::i2c_slave::{self, Command, I2cSlave};
 use embassy_rp::peripherals::{I2C0, PIN_20, PIN_21};
 use embassy_rp::Peri;
 use embassy_sync::blocking_mutex::raw::{CriticalSectionRawMutex, ThreadModeRawMutex};
 use embassy_sync::channel::{Channel, Receiver, Sender};
-use embassy_time::Timer;
+use embassy_sync::signal::Signal;
+use embassy_time::{with_timeout, Duration, Instant, Timer};
 use embedded_hal_async::i2c::I2c;
-use max11300::config::{ConfigMode5, ConfigMode7, Mode, Port, AVR, NSAMPLES};
+use heapless::{Deque, Vec};
+use max11300::config::{
+    ConfigMode3, ConfigMode5, ConfigMode7, Mode, Port, AVR, DACRANGE, NSAMPLES,
+};
+use midly::num::u7;
 use mii::{
-    devices::{ansible, er301, telexo},
+    devices::{ansible, er301, just_friends, telexo},
     Command as MiiCommand,
 };
-use portable_atomic::Ordering;
+use portable_atomic::{AtomicU16, Ordering};
 
 use libfp::{
+    i2c_follower::{
+        fader_to_tt, parse_command, response, tt_to_dac, Command as FollowerCommand, Query,
+        I2C_ADDRESS_FOLLOWER,
+    },
+    i2c_leader::{
+        default_address, device_at, note_to_ii, scale_value, velocity_to_ii, Crow, DistingEx,
+        I2cParam, WSynth, WTape, CROW_ADDRESS, MAX_LENGTH,
+    },
     i2c_proto::{
         DeviceStatus, ErrorCode, Response, WriteCommand, WriteReadCommand, MAX_MESSAGE_SIZE,
     },
-    types::{RegressionValuesInput, RegressionValuesOutput},
-    I2cMode, Range, I2C_ADDRESS_CALIBRATION,
+    types::{PiecewiseCalibration, RegressionValuesInput, RegressionValuesOutput},
+    I2cDeviceType, I2cMapping, I2cMode, MidiNote, Range, GLOBAL_CHANNELS, I2C_ADDRESS_CALIBRATION,
 };
 use postcard::{from_bytes, to_slice};
 
+use crate::events::{InputEvent, EVENT_PUBSUB};
+use crate::layout::LAYOUT_WATCH;
+use crate::tasks::buttons::BUTTON_PRESSED;
 use crate::tasks::calibration::run_calibration;
-use crate::tasks::global_config::get_global_config;
-use crate::tasks::max::{MaxCmd, CALIBRATING, MAX_CHANNEL, MAX_VALUES_ADC};
+use crate::tasks::clock::{TransportCmd, TRANSPORT_CMD_CHANNEL};
+use crate::tasks::global_config::{get_global_config, GLOBAL_CONFIG_WATCH};
+use crate::tasks::max::{MaxCmd, CALIBRATING, MAX_CHANNEL, MAX_VALUES_ADC, MAX_VALUES_FADER};
 use crate::Irqs;
 
 use super::max::MAX_VALUES_DAC;
@@ -37,6 +57,7 @@ pub type I2cDevice = I2cSlave<'static, I2C0>;
 pub enum I2cFollowerMessage {
     Start,
     SetRegressionValues(RegressionValuesInput, RegressionValuesOutput),
+    SetPiecewise(PiecewiseCalibration),
     /// Sent whenever a DacSetVoltage command targets a channel during calibration,
     /// so the calibration task can light the corresponding button LED.
     ChannelUpdate(usize),
@@ -44,9 +65,16 @@ pub enum I2cFollowerMessage {
 
 pub enum I2cLeaderMessage {
     FaderValue(usize, u16, Range),
+    /// (channel, note, velocity), velocity 0 ends the note
+    Note(usize, MidiNote, u16),
+    Gate(usize, bool),
+    Trigger(usize),
+    Param(usize, I2cParam, i16),
 }
 
 const I2C_LEADER_CHANNEL_SIZE: usize = 16;
+/// Length of triggers sent as a gate and its release
+const I2C_TRIGGER_MS: u64 = 10;
 const I2C_FOLLOWER_CHANNEL_SIZE: usize = 8;
 
 pub static I2C_LEADER_CHANNEL: Channel<
@@ -87,15 +115,11 @@ pub async fn start_i2c(
             run_calibration(msg_receiver).await;
         }
         I2cMode::Follower => {
-            // Currently unimplemented
-            // let msg_receiver = I2C_FOLLOWER_CHANNEL.receiver();
-            // let msg_sender = I2C_FOLLOWER_CHANNEL.sender();
-            // let mut i2c0_config = i2c_slave::Config::default();
-            // i2c0_config.addr = I2C_ADDRESS;
-            // let i2c_device = i2c_slave::I2cSlave::new(i2c0, scl, sda, Irqs, i2c0_config);
-            // spawner
-            //     .spawn(run_i2c_follower(i2c_device, msg_sender, false))
-            //     .unwrap();
+            let mut i2c0_config = i2c_slave::Config::default();
+            i2c0_config.addr = I2C_ADDRESS_FOLLOWER;
+            let i2c_device = i2c_slave::I2cSlave::new(i2c0, scl, sda, Irqs, i2c0_config);
+            spawner.spawn(run_i2c_remote(i2c_device)).unwrap();
+            spawner.spawn(run_i2c_triggers()).unwrap();
         }
         I2cMode::Leader => {
             let mut i2c0_config = i2c::Config::default();
@@ -106,53 +130,144 @@ pub async fn start_i2c(
     }
 }
 
-#[derive(Default, Clone, Copy)]
-struct DiscoveredDevices {
-    ansible: bool,
-    er301: bool,
-    txo: bool,
+/// Followers found on the bus, (type, address)
+pub type I2cScan = Vec<(I2cDeviceType, u8), 16>;
+
+static I2C_SCAN_REQUEST: Signal<CriticalSectionRawMutex, ()> = Signal::new();
+static I2C_SCAN_RESULT: Signal<CriticalSectionRawMutex, I2cScan> = Signal::new();
+
+/// Has the leader rescan the bus. Empty if it isn't running or doesn't answer
+/// in time.
+pub async fn scan_i2c() -> I2cScan {
+    I2C_SCAN_RESULT.reset();
+    I2C_SCAN_REQUEST.signal(());
+    with_timeout(Duration::from_secs(1), I2C_SCAN_RESULT.wait())
+        .await
+        .unwrap_or_default()
 }
 
 struct Compat16N<'a> {
     i2c: &'a mut i2c::I2c<'static, I2C0, Async>,
-    devices: DiscoveredDevices,
+    found: I2cScan,
+    map: [I2cMapping; GLOBAL_CHANNELS],
     buffer: [u8; 8],
 }
 
 impl<'a> Compat16N<'a> {
     async fn new(i2c: &'a mut i2c::I2c<'static, I2C0, Async>) -> Self {
-        // Scan for known i2c devices using 1-byte read probes
-        let mut devices = DiscoveredDevices::default();
-        let mut probe_buf = [0u8; 1];
+        let mut compat16n = Self {
+            i2c,
+            found: Vec::new(),
+            map: get_global_config().i2c_map,
+            buffer: [0u8; 8],
+        };
+        compat16n.scan().await;
+        compat16n
+    }
 
-        for addr in 8..=120 {
-            if i2c.read(addr, &mut probe_buf).await.is_ok() {
-                match addr {
-                    ansible::ADDRESS => {
-                        devices.ansible = true;
-                    }
-                    er301::ADDRESS => {
-                        devices.er301 = true;
-                    }
-                    a if (telexo::BASE_ADDRESS..telexo::BASE_ADDRESS + 8).contains(&a) => {
-                        devices.txo = true;
-                    }
-                    _ => {}
-                }
+    async fn scan(&mut self) {
+        // Scan for known i2c devices using 1-byte read probes. crow sits below
+        // the range reserved by the I2C spec.
+        let mut probe_buf = [0u8; 1];
+        self.found.clear();
+        for addr in core::iter::once(CROW_ADDRESS).chain(8..=120) {
+            let Some(device) = device_at(addr) else {
+                continue;
+            };
+            if self.i2c.read(addr, &mut probe_buf).await.is_ok() {
+                let _ = self.found.push((device, addr));
             }
         }
+    }
 
-        Self {
-            i2c,
-            devices,
-            buffer: [0u8; 8],
+    fn has(&self, device: I2cDeviceType) -> bool {
+        self.found.iter().any(|(d, _)| *d == device)
+    }
+
+    /// Where `chan` goes if it's mapped to `device`, or if it's on auto and
+    /// a `device` was found
+    fn target(&self, chan: usize, device: I2cDeviceType) -> Option<(u8, u8)> {
+        let mapping = self.map[chan];
+        if mapping.device == device {
+            Some((mapping.address, mapping.port))
+        } else if mapping.device == I2cDeviceType::Auto && self.has(device) {
+            default_address(device).map(|address| (address, 0))
+        } else {
+            None
         }
     }
 
-    async fn handle_fader_update(&mut self, chan: usize, value: u16, _range: Range) {
-        let scaled: i16 = (value as u32 * 16383 / 4095) as i16;
+    async fn write(&mut self, address: u8, msg: &[u8]) {
+        if self.i2c.write(address, msg).await.is_err() {
+            error!("I2C write to 0x{:02X} failed", address);
+        }
+    }
 
-        if self.devices.er301 {
+    async fn write_mii(&mut self, address: u8, cmd: impl MiiCommand) {
+        let mut buf = [0u8; 8];
+        if let Ok(msg) = cmd.to_bytes(&mut buf) {
+            self.write(address, msg).await;
+        }
+    }
+
+    async fn send_crow(&mut self, address: u8, port: u8, volts: i16) {
+        let mut buf = [0u8; MAX_LENGTH];
+        let cmd = Crow::Volts {
+            output: port % 4 + 1,
+            volts,
+        };
+        self.write(address, cmd.to_bytes(&mut buf)).await;
+    }
+
+    async fn handle_fader_update(&mut self, chan: usize, value: u16, range: Range) {
+        let mapping = self.map[chan];
+        let scaled = scale_value(value, range, mapping.scaling);
+        let (address, port) = (mapping.address, mapping.port);
+        let mut buf = [0u8; MAX_LENGTH];
+        match mapping.device {
+            I2cDeviceType::Auto => self.handle_fader_update_16n(chan, value, scaled).await,
+            I2cDeviceType::Ansible => {
+                let cmd = ansible::Commands::SetCv {
+                    port,
+                    value: scaled,
+                };
+                self.write_mii(address, cmd).await;
+            }
+            I2cDeviceType::Er301 => {
+                let cmd = er301::Commands::SetCv {
+                    port,
+                    value: scaled,
+                };
+                self.write_mii(address, cmd).await;
+            }
+            I2cDeviceType::Txo => {
+                let cmd = telexo::Commands::SetCv {
+                    port,
+                    value: scaled,
+                };
+                self.write_mii(address, cmd).await;
+            }
+            I2cDeviceType::WTape => {
+                self.write(address, WTape::Speed(scaled).to_bytes(&mut buf))
+                    .await;
+            }
+            I2cDeviceType::DistingEx => {
+                let cmd = DistingEx::SetParam {
+                    param: port,
+                    value: scaled,
+                };
+                self.write(address, cmd.to_bytes(&mut buf)).await;
+            }
+            I2cDeviceType::Crow => self.send_crow(address, port, scaled).await,
+            // Notes and gates only
+            I2cDeviceType::Off | I2cDeviceType::JustFriends | I2cDeviceType::WSynth => {}
+        }
+    }
+
+    /// Every device found gets the update at a port worked out from the
+    /// channel, like the 16n does it
+    async fn handle_fader_update_16n(&mut self, chan: usize, value: u16, scaled: i16) {
+        if self.has(I2cDeviceType::Er301) {
             let cmd = er301::Commands::SetCv {
                 port: chan as u8,
                 value: scaled,
@@ -165,7 +280,7 @@ impl<'a> Compat16N<'a> {
         }
 
         // Send to TXo if present
-        if self.devices.txo {
+        if self.has(I2cDeviceType::Txo) {
             let device_index = (chan / 4) as u8;
             let port = (chan % 4) as u8;
             let address = telexo::BASE_ADDRESS + device_index;
@@ -182,7 +297,7 @@ impl<'a> Compat16N<'a> {
         }
 
         // Send to Ansible if present
-        if self.devices.ansible {
+        if self.has(I2cDeviceType::Ansible) {
             let device_port = ((chan / 4) << 1) as u8;
             let cmd = ansible::Commands::SetCvFromFader { device_port, value };
 
@@ -192,6 +307,149 @@ impl<'a> Compat16N<'a> {
                 }
             }
         }
+
+        // crow only has four outputs, so only the first four channels get one
+        if chan < 4 && self.has(I2cDeviceType::Crow) {
+            self.send_crow(CROW_ADDRESS, chan as u8, scaled).await;
+        }
+    }
+
+    async fn handle_note(&mut self, chan: usize, note: MidiNote, velocity: u16) {
+        let mut buf = [0u8; MAX_LENGTH];
+        let pitch = note_to_ii(note);
+        let level = velocity_to_ii(velocity);
+        // On auto, voices follow the channel
+        let auto = self.map[chan].device == I2cDeviceType::Auto;
+        let voice = |port: u8, voices: usize| {
+            if auto {
+                (chan % voices) as u8 + 1
+            } else {
+                port % voices as u8 + 1
+            }
+        };
+
+        // Level 0 ends a sustained note on Just Friends and W/
+        if let Some((address, port)) = self.target(chan, I2cDeviceType::JustFriends) {
+            let cmd = just_friends::Commands::PlayNote {
+                output: voice(port, 6),
+                pitch,
+                volume: level,
+            };
+            self.write_mii(address, cmd).await;
+        }
+
+        if let Some((address, port)) = self.target(chan, I2cDeviceType::WSynth) {
+            let cmd = WSynth::PlayVoice {
+                voice: voice(port, 4) as i8,
+                pitch,
+                level,
+            };
+            self.write(address, cmd.to_bytes(&mut buf)).await;
+        }
+
+        if let Some((address, _)) = self.target(chan, I2cDeviceType::DistingEx) {
+            let note = u7::from(note).as_int();
+            if velocity > 0 {
+                let cmd = DistingEx::NotePitch { note, pitch };
+                self.write(address, cmd.to_bytes(&mut buf)).await;
+                let cmd = DistingEx::NoteOn {
+                    note,
+                    velocity: level,
+                };
+                self.write(address, cmd.to_bytes(&mut buf)).await;
+            } else {
+                let cmd = DistingEx::NoteOff { note };
+                self.write(address, cmd.to_bytes(&mut buf)).await;
+            }
+        }
+
+        // A mapped crow output follows the pitch
+        if self.map[chan].device == I2cDeviceType::Crow && velocity > 0 {
+            let mapping = self.map[chan];
+            self.send_crow(mapping.address, mapping.port, pitch).await;
+        }
+    }
+
+    /// Sets a gate. For a `trigger` Ansible pulses by itself and skips the
+    /// release.
+    async fn handle_gate(&mut self, chan: usize, high: bool, trigger: bool) {
+        let mut buf = [0u8; MAX_LENGTH];
+        let auto = self.map[chan].device == I2cDeviceType::Auto;
+
+        if let Some((address, port)) = self.target(chan, I2cDeviceType::Txo) {
+            let (address, port) = if auto {
+                (address + (chan / 4) as u8, (chan % 4) as u8)
+            } else {
+                (address, port)
+            };
+            let cmd = telexo::Commands::SetGate { port, state: high };
+            self.write_mii(address, cmd).await;
+        }
+
+        if let Some((address, port)) = self.target(chan, I2cDeviceType::Er301) {
+            let port = if auto { chan as u8 } else { port };
+            let cmd = er301::Commands::SetGate { port, state: high };
+            self.write_mii(address, cmd).await;
+        }
+
+        // Only on an explicit mapping, where CV and gates share the mapped port.
+        // On auto the 16n CV routing has no matching trigger output.
+        if self.map[chan].device == I2cDeviceType::Ansible {
+            let (address, port) = (self.map[chan].address, self.map[chan].port);
+            if !trigger {
+                let cmd = ansible::Commands::SetTrState { port, state: high };
+                self.write_mii(address, cmd).await;
+            } else if high {
+                self.write_mii(address, ansible::Commands::SetTrPulse { port })
+                    .await;
+            }
+        }
+
+        if let Some((address, port)) = self.target(chan, I2cDeviceType::JustFriends) {
+            let output = if auto { chan % 6 } else { port as usize % 6 };
+            let cmd = just_friends::Commands::SetGate {
+                output: output as u8 + 1,
+                state: high,
+            };
+            self.write_mii(address, cmd).await;
+        }
+
+        // 5V gates like the jacks
+        if let Some((address, port)) = self.target(chan, I2cDeviceType::Crow) {
+            if !auto || chan < 4 {
+                let port = if auto { chan as u8 } else { port };
+                self.send_crow(address, port, if high { 8192 } else { 0 })
+                    .await;
+            }
+        }
+
+        if self.map[chan].device == I2cDeviceType::WTape {
+            let cmd = WTape::Play(high as i8);
+            self.write(self.map[chan].address, cmd.to_bytes(&mut buf))
+                .await;
+        }
+    }
+
+    async fn handle_param(&mut self, chan: usize, param: I2cParam, value: i16) {
+        let mut buf = [0u8; MAX_LENGTH];
+        match param {
+            I2cParam::DistingEx(param) => {
+                if let Some((address, _)) = self.target(chan, I2cDeviceType::DistingEx) {
+                    let cmd = DistingEx::SetParam { param, value };
+                    self.write(address, cmd.to_bytes(&mut buf)).await;
+                }
+            }
+            I2cParam::TapePlay | I2cParam::TapeRecord | I2cParam::TapeSpeed => {
+                if let Some((address, _)) = self.target(chan, I2cDeviceType::WTape) {
+                    let cmd = match param {
+                        I2cParam::TapePlay => WTape::Play((value != 0) as i8),
+                        I2cParam::TapeRecord => WTape::Record((value != 0) as i8),
+                        _ => WTape::Speed(value),
+                    };
+                    self.write(address, cmd.to_bytes(&mut buf)).await;
+                }
+            }
+        }
     }
 }
 
@@ -256,6 +514,9 @@ async fn process_write(command: WriteCommand, sender: &mut I2cFollowerSender) {
         WriteCommand::SysReset => {
             cortex_m::peripheral::SCB::sys_reset();
         }
+        WriteCommand::CalibSetPiecewise(points) => {
+            sender.send(I2cFollowerMessage::SetPiecewise(points)).await;
+        }
     }
 }
 
@@ -318,15 +579,268 @@ async fn run_i2c_follower(
     }
 }
 
+/// Trigger length for `TR.P` until set with `TR.TIME`
+const REMOTE_TRIGGER_MS: u16 = 10;
+
+/// Jacks with a trigger pending from the follower protocol, a bit per jack
+static REMOTE_TRIGGERS: AtomicU16 = AtomicU16::new(0);
+static REMOTE_TRIGGER_MS_SET: AtomicU16 = AtomicU16::new(REMOTE_TRIGGER_MS);
+static REMOTE_TRIGGER_SIGNAL: Signal<CriticalSectionRawMutex, ()> = Signal::new();
+
+#[derive(Clone, Copy, PartialEq)]
+enum RemoteJack {
+    Unused,
+    Cv,
+    Gate,
+}
+
+/// Jacks driven by the follower protocol. Jacks in use by an app are left
+/// alone.
+struct RemoteJacks {
+    modes: [RemoteJack; 16],
+    occupied: u16,
+}
+
+impl RemoteJacks {
+    fn new() -> Self {
+        Self {
+            modes: [RemoteJack::Unused; 16],
+            occupied: 0,
+        }
+    }
+
+    /// Puts `jack` in the mode for `use_as`. Returns false if an app has it.
+    async fn claim(&mut self, jack: u8, use_as: RemoteJack) -> bool {
+        let jack = jack as usize;
+        if jack >= self.modes.len() {
+            return false;
+        }
+        let mut occupied = 0u16;
+        if let Some(layout) = LAYOUT_WATCH.try_get() {
+            for (_, start_channel, channels, _) in layout.iter() {
+                for chan in start_channel..(start_channel + channels).min(16) {
+                    occupied |= 1 << chan;
+                }
+            }
+        }
+        // An app came or went: whatever it left the jack in is unknown
+        let changed = occupied ^ self.occupied;
+        for (i, mode) in self.modes.iter_mut().enumerate() {
+            if changed & (1 << i) != 0 {
+                *mode = RemoteJack::Unused;
+            }
+        }
+        self.occupied = occupied;
+        if occupied & (1 << jack) != 0 {
+            return false;
+        }
+        if self.modes[jack] != use_as {
+            let port = Port::try_from(jack).unwrap();
+            let (mode, gpo_level) = match use_as {
+                RemoteJack::Gate => (Mode::Mode3(ConfigMode3), Some(4095)),
+                _ => (Mode::Mode5(ConfigMode5(DACRANGE::Rg0_10v)), None),
+            };
+            MAX_CHANNEL
+                .send(MaxCmd::ConfigurePort {
+                    port,
+                    mode,
+                    gpo_level,
+                })
+                .await;
+            self.modes[jack] = use_as;
+        }
+        true
+    }
+}
+
+fn remote_query_value(query: Query) -> u16 {
+    match query {
+        Query::Fader(n) => MAX_VALUES_FADER
+            .get(n as usize)
+            .map_or(0, |v| fader_to_tt(v.load(Ordering::Relaxed))),
+        Query::Button(n) => BUTTON_PRESSED
+            .get(n as usize)
+            .map_or(0, |b| b.load(Ordering::Relaxed) as u16),
+        Query::Bpm => libm::roundf(get_global_config().clock.internal_bpm) as u16,
+    }
+}
+
+async fn run_remote_command(command: FollowerCommand, jacks: &mut RemoteJacks) {
+    match command {
+        FollowerCommand::Select(_) => {}
+        FollowerCommand::SetCv { jack, value } => {
+            if jacks.claim(jack, RemoteJack::Cv).await {
+                MAX_VALUES_DAC[jack as usize].store(tt_to_dac(value), Ordering::Relaxed);
+            }
+        }
+        FollowerCommand::SetGate { jack, high } => {
+            if jacks.claim(jack, RemoteJack::Gate).await {
+                let port = Port::try_from(jack as usize).unwrap();
+                let cmd = if high {
+                    MaxCmd::GpoSetHigh { port }
+                } else {
+                    MaxCmd::GpoSetLow { port }
+                };
+                MAX_CHANNEL.send(cmd).await;
+            }
+        }
+        FollowerCommand::Trigger { jack } => {
+            if jacks.claim(jack, RemoteJack::Gate).await {
+                REMOTE_TRIGGERS.fetch_or(1 << jack, Ordering::Relaxed);
+                REMOTE_TRIGGER_SIGNAL.signal(());
+            }
+        }
+        FollowerCommand::SetTriggerTime(ms) => {
+            REMOTE_TRIGGER_MS_SET.store(ms.max(1), Ordering::Relaxed);
+        }
+        FollowerCommand::SetBpm(bpm) => {
+            let bpm = (bpm as f32).clamp(45.0, 300.0);
+            GLOBAL_CONFIG_WATCH.sender().send_if_modified(|c| {
+                if let Some(config) = c {
+                    if config.clock.internal_bpm != bpm {
+                        config.clock.internal_bpm = bpm;
+                        return true;
+                    }
+                }
+                false
+            });
+        }
+        FollowerCommand::LoadScene(scene) => {
+            if (scene as usize) < GLOBAL_CHANNELS {
+                // Loads like a scene change coming in over MIDI
+                EVENT_PUBSUB
+                    .immediate_publisher()
+                    .publish_immediate(InputEvent::LoadSceneFromMidi(scene));
+            }
+        }
+        FollowerCommand::Transport(run) => {
+            let cmd = if run {
+                TransportCmd::Start
+            } else {
+                TransportCmd::Stop
+            };
+            TRANSPORT_CMD_CHANNEL.send(cmd).await;
+        }
+    }
+}
+
+/// Follower for Teletype, crow and other leaders, see `libfp::i2c_follower`
+#[embassy_executor::task]
+async fn run_i2c_remote(mut i2c_device: I2cDevice) {
+    let mut buf = [0u8; 16];
+    let mut query = Query::Fader(0);
+    let mut jacks = RemoteJacks::new();
+    loop {
+        match i2c_device.listen(&mut buf).await {
+            Ok(Command::Write(len)) => match parse_command(&buf[..len]) {
+                Some(FollowerCommand::Select(selected)) => query = selected,
+                Some(command) => run_remote_command(command, &mut jacks).await,
+                None => error!("Unknown I2C follower command: {}", &buf[..len]),
+            },
+            Ok(Command::WriteRead(len)) => {
+                match parse_command(&buf[..len]) {
+                    Some(FollowerCommand::Select(selected)) => query = selected,
+                    Some(command) => run_remote_command(command, &mut jacks).await,
+                    None => error!("Unknown I2C follower command: {}", &buf[..len]),
+                }
+                let value = response(remote_query_value(query));
+                if i2c_device.respond_and_fill(&value, 0x00).await.is_err() {
+                    error!("Error while responding");
+                }
+            }
+            Ok(Command::Read) => {
+                let value = response(remote_query_value(query));
+                if i2c_device.respond_to_read(&value).await.is_err() {
+                    error!("Failed to respond to I2C read request");
+                }
+            }
+            Ok(Command::GeneralCall(_)) => {}
+            Err(e) => error!("I2C listen error: {}", e),
+        }
+    }
+}
+
+/// Fires the triggers queued by `run_i2c_remote`, together where they coincide
+#[embassy_executor::task]
+async fn run_i2c_triggers() {
+    loop {
+        REMOTE_TRIGGER_SIGNAL.wait().await;
+        let jacks = REMOTE_TRIGGERS.swap(0, Ordering::Relaxed);
+        let ports = || {
+            (0..16usize)
+                .filter(move |i| jacks & (1 << i) != 0)
+                .map(|i| Port::try_from(i).unwrap())
+        };
+        for port in ports() {
+            MAX_CHANNEL.send(MaxCmd::GpoSetHigh { port }).await;
+        }
+        Timer::after_millis(REMOTE_TRIGGER_MS_SET.load(Ordering::Relaxed) as u64).await;
+        for port in ports() {
+            MAX_CHANNEL.send(MaxCmd::GpoSetLow { port }).await;
+        }
+    }
+}
+
 #[embassy_executor::task]
 async fn run_i2c_leader(mut i2c: i2c::I2c<'static, I2C0, Async>) {
     // Wait for followers to boot
     Timer::after_secs(10).await;
 
     let mut compat16n = Compat16N::new(&mut i2c).await;
+    let mut config_receiver = GLOBAL_CONFIG_WATCH.anon_receiver();
+    // Triggers waiting to be released, (due, channel)
+    let mut releases: Deque<(Instant, usize), 16> = Deque::new();
 
     loop {
-        let I2cLeaderMessage::FaderValue(chan, value, range) = I2C_LEADER_CHANNEL.receive().await;
-        compat16n.handle_fader_update(chan, value, range).await;
+        let next_release = releases.front().map(|(due, _)| *due);
+        let release_fut = async {
+            match next_release {
+                Some(due) => Timer::at(due).await,
+                None => pending().await,
+            }
+        };
+        let msg = match select3(
+            I2C_LEADER_CHANNEL.receive(),
+            I2C_SCAN_REQUEST.wait(),
+            release_fut,
+        )
+        .await
+        {
+            Either3::First(msg) => msg,
+            Either3::Second(_) => {
+                compat16n.scan().await;
+                I2C_SCAN_RESULT.signal(compat16n.found.clone());
+                continue;
+            }
+            Either3::Third(_) => {
+                if let Some((_, chan)) = releases.pop_front() {
+                    compat16n.handle_gate(chan, false, true).await;
+                }
+                continue;
+            }
+        };
+        if let Some(config) = config_receiver.try_changed() {
+            compat16n.map = config.i2c_map;
+        }
+        match msg {
+            I2cLeaderMessage::FaderValue(chan, value, range) => {
+                compat16n.handle_fader_update(chan, value, range).await;
+            }
+            I2cLeaderMessage::Note(chan, note, velocity) => {
+                compat16n.handle_note(chan, note, velocity).await;
+            }
+            I2cLeaderMessage::Gate(chan, high) => {
+                compat16n.handle_gate(chan, high, false).await;
+            }
+            I2cLeaderMessage::Trigger(chan) => {
+                compat16n.handle_gate(chan, true, true).await;
+                let due = Instant::now() + Duration::from_millis(I2C_TRIGGER_MS);
+                // Full up, the trigger turns into a gate until the next one
+                let _ = releases.push_back((due, chan));
+            }
+            I2cLeaderMessage::Param(chan, param, value) => {
+                compat16n.handle_param(chan, param, value).await;
+            }
+        }
     }
 }
@@ -4,7 +4,7 @@ use portable_atomic::{AtomicU8, Ordering};
 
 use crate::app::Led;
 use crate::events::{InputEvent, EVENT_PUBSUB};
-use crate::tasks::global_config::get_global_config;
+use crate::tasks::global_config::{get_global_config, toggle_custom_scale_note};
 use crate::tasks::leds::{clear_led_overlay, set_led_overlay_mode, LedMode};
 
 static LAST_SCENE: AtomicU8 = AtomicU8::new(u8::MAX);
@@ -98,7 +98,7 @@ async fn run_input_handlers() {
                 }
 
                 let config = get_global_config();
-                show_scale_keyboard(config.quantizer.key, config.quantizer.tonic).await;
+                show_scale_keyboard(&config).await;
                 show_config_top_leds(&config).await;
 
                 let last = LAST_SCENE.load(Ordering::Relaxed);
@@ -111,6 +111,15 @@ async fn run_input_handlers() {
                     .await;
                 }
             }
+            InputEvent::ToggleScaleNote(chan) => {
+                let chan = chan as usize;
+                if (SCALE_LED_FIRST_CHANNEL..SCALE_LED_LAST_CHANNEL).contains(&chan) {
+                    // Keys on the keyboard start at the tonic
+                    let tonic = get_global_config().quantizer.tonic as usize;
+                    let note = (chan - SCALE_LED_FIRST_CHANNEL + tonic) % 12;
+                    toggle_custom_scale_note(Note::from(note as u8));
+                }
+            }
             InputEvent::SceneButtonUp => {
                 for i in 0..NUM_CHANNELS {
                     clear_led_overlay(i, Led::Top).await;
@@ -123,7 +132,15 @@ async fn run_input_handlers() {
     }
 }
 
-pub async fn show_scale_keyboard(key: Key, tonic: Note) {
+/// Whether the button of `chan` is under a key of the scale keyboard while it
+/// shows a custom scale, so the button edits the scale instead of the scenes
+pub fn is_custom_scale_key(chan: usize) -> bool {
+    (SCALE_LED_FIRST_CHANNEL..SCALE_LED_LAST_CHANNEL).contains(&chan)
+        && matches!(get_global_config().quantizer.key, Key::Custom(_))
+}
+
+pub async fn show_scale_keyboard(config: &GlobalConfig) {
+    let (key, tonic) = (config.quantizer.key, config.quantizer.tonic);
     if key == Key::Off {
         for ch in 0..NUM_CHANNELS {
             set_led_overlay_mode(
@@ -136,7 +153,7 @@ pub async fn show_scale_keyboard(key: Key, tonic: Note) {
         return;
     }
 
-    let mask = key.as_u16_key();
+    let mask = config.scale_mask();
     let tonic_offset = tonic as usize;
 
     for (semitone, &black_key) in IS_BLACK_KEY.iter().enumerate() {
@@ -190,10 +207,11 @@ pub async fn show_config_top_leds(config: &GlobalConfig) {
     )
     .await;
 
-    let (key_color, key_brightness) = if config.quantizer.key == Key::Off {
-        (Color::White, Brightness::Off)
-    } else {
-        (Color::from(config.quantizer.key as usize), Brightness::Mid)
+    let (key_color, key_brightness) = match config.quantizer.key {
+        Key::Off => (Color::White, Brightness::Off),
+        // The palette only has a color per built-in scale
+        Key::Custom(_) => (Color::White, Brightness::High),
+        key => (Color::from(key.index() as usize), Brightness::Mid),
     };
     set_led_overlay_mode(
         QUANTIZER_KEY_FADER,
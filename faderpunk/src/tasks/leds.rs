@@ -1,24 +1,33 @@
+use core::cell::Cell;
+
 use embassy_executor::Spawner;
 use embassy_rp::clocks::RoscRng;
 use embassy_rp::peripherals::SPI1;
 use embassy_rp::spi::{Async, Spi};
 use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use embassy_sync::blocking_mutex::Mutex as BlockingMutex;
 use embassy_sync::channel::Channel;
 use embassy_sync::signal::Signal;
 use embassy_time::{Duration, Instant, Timer};
+use libfp::colors::{hue, mix};
 use libfp::{constants::CHAN_LED_MAP, ext::BrightnessExt};
-use libfp::{Brightness, Color, LED_BRIGHTNESS_RANGE};
+use libfp::{Brightness, Color, LedConfig, GLOBAL_CHANNELS, LED_BRIGHTNESS_RANGE};
 use portable_atomic::{AtomicU8, Ordering};
 use smart_leds::colors::BLACK;
 use smart_leds::{brightness, gamma, SmartLedsWriteAsync, RGB8};
 use ws2812_async::{Grb, Ws2812};
 
+use crate::events::EVENT_PUBSUB;
 use crate::tasks::clock::METRONOME_HIGH;
 
 const REFRESH_RATE: u64 = 60;
 const T: u64 = 1000 / REFRESH_RATE;
 const NUM_LEDS: usize = 50;
 const LED_OVERLAY_CHANNEL_SIZE: usize = 16;
+/// How long the pickup guidance stays up after the fader stopped moving
+const PICKUP_GUIDANCE_HOLD_MS: u64 = 1500;
+/// Dimmest pickup guidance, shown when the fader is right next to the stored value
+const PICKUP_GUIDANCE_MIN_BRIGHTNESS: i32 = 40;
 
 /// Fixed brightness for the boot animation, independent of the
 /// user-configured `LED_BRIGHTNESS`. On boards with the inline USB current
@@ -27,9 +36,14 @@ const LED_OVERLAY_CHANNEL_SIZE: usize = 16;
 /// animation runs before the config is loaded from FRAM anyway, so it can't
 /// honor the configured brightness even if it wanted to.
 const STARTUP_BRIGHTNESS: u8 = 155;
+/// Brightness while the LEDs are dimmed after `LedConfig::idle_dim_minutes`
+const IDLE_BRIGHTNESS: u8 = 20;
 
 pub static LED_BRIGHTNESS: AtomicU8 = AtomicU8::new(LED_BRIGHTNESS_RANGE.end);
 
+pub static LED_CONFIG: BlockingMutex<CriticalSectionRawMutex, Cell<LedConfig>> =
+    BlockingMutex::new(Cell::new(LedConfig::new()));
+
 static LED_SIGNALS: [Signal<CriticalSectionRawMutex, LedMsg>; NUM_LEDS] =
     [const { Signal::new() }; NUM_LEDS];
 
@@ -39,6 +53,9 @@ static LED_OVERLAY_CHANNEL: Channel<
     LED_OVERLAY_CHANNEL_SIZE,
 > = Channel::new();
 
+static PICKUP_GUIDANCE_SIGNALS: [Signal<CriticalSectionRawMutex, Option<i16>>; GLOBAL_CHANNELS] =
+    [const { Signal::new() }; GLOBAL_CHANNELS];
+
 pub async fn start_leds(spawner: &Spawner, spi1: Spi<'static, SPI1, Async>) {
     spawner.spawn(run_leds(spi1)).unwrap();
 }
@@ -65,10 +82,20 @@ pub enum LedMode {
     StaticFade(Color, u16),
     ClockFlash(Color, Brightness, Brightness),
     FlashThenStatic(Color, usize, Color, Brightness),
+    /// Breathes in and out once every period (ms)
+    Pulse(Color, u16),
+    /// Blends from the first color to the second as the value goes up (0-4095)
+    Gradient(Color, Color, u16),
+    /// One segment of a meter filling the button, bottom and top LED of a
+    /// channel as the value goes up (0-4095). Use `Leds::set_meter` to set all
+    /// three.
+    Meter(Color, u16),
+    /// Goes around the color wheel once every period (ms)
+    HueCycle(Brightness, u16),
 }
 
 impl LedMode {
-    fn into_effect(self) -> LedEffect {
+    fn into_effect(self, no: usize) -> LedEffect {
         match self {
             LedMode::Static(color, brightness) => LedEffect::Static {
                 color: color.into(),
@@ -102,6 +129,22 @@ impl LedMode {
                     then_brightness: then_brightness.into(),
                 }
             }
+            LedMode::Pulse(color, period_ms) => LedEffect::Pulse {
+                color: color.into(),
+                period_ms,
+            },
+            LedMode::Gradient(from, to, value) => LedEffect::Static {
+                color: mix(from.into(), to.into(), (value.min(4095) >> 4) as u8),
+                brightness: 255,
+            },
+            LedMode::Meter(color, value) => LedEffect::Static {
+                color: color.into(),
+                brightness: meter_level(meter_segment(no), value),
+            },
+            LedMode::HueCycle(brightness, period_ms) => LedEffect::HueCycle {
+                brightness: brightness.into(),
+                period_ms,
+            },
         }
     }
 }
@@ -139,6 +182,14 @@ enum LedEffect {
         then_color: RGB8,
         then_brightness: u8,
     },
+    Pulse {
+        color: RGB8,
+        period_ms: u16,
+    },
+    HueCycle {
+        brightness: u8,
+        period_ms: u16,
+    },
 }
 
 impl LedEffect {
@@ -237,6 +288,20 @@ impl LedEffect {
 
                 result
             }
+            LedEffect::Pulse { color, period_ms } => {
+                let phase = cycle_phase(*period_ms);
+                // Triangle up and back down over one period
+                let level = if phase < 128 {
+                    phase * 2
+                } else {
+                    (255 - phase) * 2
+                };
+                color.scale(level)
+            }
+            LedEffect::HueCycle {
+                brightness,
+                period_ms,
+            } => hue(cycle_phase(*period_ms)).scale(*brightness),
             LedEffect::StaticFade {
                 color,
                 delay_ms,
@@ -264,9 +329,68 @@ impl LedEffect {
     }
 }
 
+/// Where we are in a repeating effect, 0-255. Taken from the clock so all LEDs
+/// running the same period stay in step.
+fn cycle_phase(period_ms: u16) -> u8 {
+    if period_ms == 0 {
+        return 0;
+    }
+    let period = period_ms as u64;
+    ((Instant::now().as_millis() % period) * 256 / period) as u8
+}
+
+/// Position of an LED in a channel's meter, counting up from the button
+fn meter_segment(no: usize) -> u8 {
+    if CHAN_LED_MAP[1].contains(&no) {
+        1
+    } else if CHAN_LED_MAP[0].contains(&no) {
+        2
+    } else {
+        0
+    }
+}
+
+/// How full a meter segment is at `value`, each segment covers a third of the range
+fn meter_level(segment: u8, value: u16) -> u8 {
+    let filled = (value.min(4095) as i32 * 3 - segment as i32 * 4095).clamp(0, 4095);
+    (filled >> 4) as u8
+}
+
+#[derive(Clone, Copy)]
+struct PickupGuidance {
+    distance: i16,
+    frames_left: u64,
+}
+
+impl PickupGuidance {
+    fn new(distance: i16) -> Self {
+        Self {
+            distance,
+            frames_left: PICKUP_GUIDANCE_HOLD_MS / T,
+        }
+    }
+
+    /// The LED pointing the way to the stored value, brighter the further it is away
+    fn render(&self) -> (Led, RGB8) {
+        let led = if self.distance > 0 {
+            Led::Top
+        } else {
+            Led::Bottom
+        };
+        let level = PICKUP_GUIDANCE_MIN_BRIGHTNESS
+            + (self.distance.unsigned_abs() as i32).min(4095)
+                * (255 - PICKUP_GUIDANCE_MIN_BRIGHTNESS)
+                / 4095;
+        let color: RGB8 = Color::White.into();
+        (led, color.scale(level as u8))
+    }
+}
+
 struct LedProcessor {
     base_layer: [LedEffect; 50],
     overlay_layer: [LedEffect; 50],
+    pickup_guidance: [Option<PickupGuidance>; GLOBAL_CHANNELS],
+    last_input: Instant,
     buffer: [RGB8; NUM_LEDS],
     ws: Ws2812<Spi<'static, SPI1, Async>, Grb, { 12 * NUM_LEDS }>,
 }
@@ -296,15 +420,38 @@ impl LedProcessor {
                 }
             }
         }
+        // Pickup guidance sits on top of both layers of the fader's channel
+        for (channel, guidance) in self.pickup_guidance.iter_mut().enumerate() {
+            if let Some(g) = guidance {
+                let (led, color) = g.render();
+                let other = match led {
+                    Led::Top => Led::Bottom,
+                    _ => Led::Top,
+                };
+                self.buffer[get_no(channel, led)] = color;
+                self.buffer[get_no(channel, other)] = BLACK;
+                g.frames_left = g.frames_left.saturating_sub(1);
+                if g.frames_left == 0 {
+                    *guidance = None;
+                }
+            }
+        }
         self.flush_buffer(LED_BRIGHTNESS.load(Ordering::Relaxed))
             .await;
     }
 
+    /// Writes the buffer out through the LED theme, palette and idle dimming
     async fn flush_buffer(&mut self, brightness_level: u8) {
+        let config = LED_CONFIG.lock(|c| c.get());
+        let mut level = brightness_level.min(config.theme.max_brightness());
+        let idle_after = Duration::from_secs(config.idle_dim_minutes as u64 * 60);
+        if config.idle_dim_minutes > 0 && self.last_input.elapsed() >= idle_after {
+            level = level.min(IDLE_BRIGHTNESS);
+        }
         self.ws
             .write(gamma(brightness(
-                self.buffer.iter().cloned(),
-                brightness_level,
+                self.buffer.iter().map(|&color| config.apply(color)),
+                level,
             )))
             .await
             .ok();
@@ -334,6 +481,14 @@ pub async fn clear_led_overlay(channel: usize, position: Led) {
     LED_OVERLAY_CHANNEL.send((no, None)).await;
 }
 
+/// Point the fader on `channel` towards the value it has to pick up, `None` clears it.
+///
+/// `distance` is the stored value minus the fader position. The guidance goes away by
+/// itself when it isn't refreshed for a while.
+pub fn set_pickup_guidance(channel: usize, distance: Option<i16>) {
+    PICKUP_GUIDANCE_SIGNALS[channel].signal(distance);
+}
+
 #[embassy_executor::task]
 async fn run_leds(spi1: Spi<'static, SPI1, Async>) {
     let ws: Ws2812<_, Grb, { 12 * NUM_LEDS }> = Ws2812::new(spi1);
@@ -341,12 +496,18 @@ async fn run_leds(spi1: Spi<'static, SPI1, Async>) {
     let mut leds = LedProcessor {
         base_layer: [LedEffect::Off; NUM_LEDS],
         overlay_layer: [LedEffect::Off; NUM_LEDS],
+        pickup_guidance: [None; GLOBAL_CHANNELS],
+        last_input: Instant::now(),
         buffer: [BLACK; NUM_LEDS],
         ws,
     };
-
     startup_animation(&mut leds).await;
 
+    // Any input wakes the LEDs up from idle dimming. Only subscribed now, as
+    // the queue isn't drained during the startup animation and would block
+    // the publishers once full.
+    let mut input_subscriber = EVENT_PUBSUB.subscriber().unwrap();
+
     leds.base_layer[16] = LedEffect::ClockFlash {
         color: Color::Pink.into(),
         brightness_high: Brightness::High.into(),
@@ -366,7 +527,7 @@ async fn run_leds(spi1: Spi<'static, SPI1, Async>) {
             if let Some(msg) = led_signal.try_take() {
                 match msg {
                     LedMsg::Set(mode) => {
-                        leds.base_layer[i] = mode.into_effect();
+                        leds.base_layer[i] = mode.into_effect(i);
                     }
                     LedMsg::Reset => match leds.base_layer[i] {
                         LedEffect::Static { color, brightness } => {
@@ -391,11 +552,21 @@ async fn run_leds(spi1: Spi<'static, SPI1, Async>) {
 
         while let Ok((no, mode)) = LED_OVERLAY_CHANNEL.try_receive() {
             leds.overlay_layer[no] = match mode {
-                Some(m) => m.into_effect(),
+                Some(m) => m.into_effect(no),
                 None => LedEffect::Off,
             };
         }
 
+        for (channel, signal) in PICKUP_GUIDANCE_SIGNALS.iter().enumerate() {
+            if let Some(distance) = signal.try_take() {
+                leds.pickup_guidance[channel] = distance.map(PickupGuidance::new);
+            }
+        }
+
+        while input_subscriber.try_next_message().is_some() {
+            leds.last_input = Instant::now();
+        }
+
         leds.process().await;
     }
 }
@@ -1,3 +1,5 @@
+use core::cell::RefCell;
+
 use embassy_executor::Spawner;
 use embassy_futures::yield_now;
 use embassy_rp::{
@@ -7,16 +9,20 @@ use embassy_rp::{
     spi::{self, Async, Spi},
     Peri,
 };
+
 use embassy_sync::{
-    blocking_mutex::raw::{CriticalSectionRawMutex, NoopRawMutex},
+    blocking_mutex::{
+        raw::{CriticalSectionRawMutex, NoopRawMutex},
+        Mutex as BlockingMutex,
+    },
     channel::{Channel, Sender},
     mutex::Mutex,
 };
-use embassy_time::Timer;
+use embassy_time::{Duration, Instant, Timer};
 use libfp::{
     latch::{AnalogLatch, LatchLayer},
-    types::MaxCalibration,
-    CALIBRATION_SCALE_FACTOR,
+    types::{FaderCalibration, MaxCalibration},
+    VoOctTrim, FADER_HYSTERESIS_DEFAULT, GLOBAL_CHANNELS,
 };
 use max11300::{
     config::{
@@ -25,7 +31,7 @@ use max11300::{
     },
     ConfigurePort, IntoConfiguredPort, Max11300, Mode0Port, Ports,
 };
-use portable_atomic::{AtomicBool, AtomicU16, Ordering};
+use portable_atomic::{AtomicBool, AtomicU16, AtomicU8, Ordering};
 use static_cell::StaticCell;
 
 use crate::{
@@ -45,6 +51,8 @@ const MAX_CHANNEL_SIZE: usize = 16;
 /// refreshes the fader port's data register every sweep (~tens of µs), so each
 /// read is a fresh conversion; the median of the burst rejects sweep glitches.
 const FADER_BURST_READS: usize = 5;
+/// Fractional bits kept by the fader smoothing
+const FADER_SMOOTHING_FRAC_BITS: u32 = 4;
 
 type SharedMax = Mutex<NoopRawMutex, Max11300<Spi<'static, SPI0, Async>, Output<'static>>>;
 type MuxPins = (
@@ -62,6 +70,26 @@ pub static MAX_VALUES_FADER: [AtomicU16; 16] = [const { AtomicU16::new(0) }; 16]
 pub static MAX_VALUES_DAC: [AtomicU16; 20] = [const { AtomicU16::new(0) }; 20];
 pub static MAX_VALUES_ADC: [AtomicU16; 20] = [const { AtomicU16::new(0) }; 20];
 pub static CALIBRATING: AtomicBool = AtomicBool::new(false);
+/// Per-jack V/Oct trims, applied to the pitch values apps set on jacks 0-15
+pub static VOCT_TRIMS: BlockingMutex<
+    CriticalSectionRawMutex,
+    RefCell<[VoOctTrim; GLOBAL_CHANNELS]>,
+> = BlockingMutex::new(RefCell::new([VoOctTrim::new(0, 0); GLOBAL_CHANNELS]));
+pub static FADER_CALIBRATION: BlockingMutex<CriticalSectionRawMutex, RefCell<FaderCalibration>> =
+    BlockingMutex::new(RefCell::new(FaderCalibration::new()));
+/// Lowest and highest raw reading of each fader
+pub type FaderEnds = [(u16, u16); 16];
+/// Start and ends seen while the configurator calibrates the faders, `None`
+/// otherwise
+pub static FADER_SWEEP: BlockingMutex<
+    CriticalSectionRawMutex,
+    RefCell<Option<(Instant, FaderEnds)>>,
+> = BlockingMutex::new(RefCell::new(None));
+/// A sweep the configurator never finishes, e.g. because it was closed, is
+/// dropped after this long
+const FADER_SWEEP_TIMEOUT: Duration = Duration::from_secs(300);
+pub static FADER_HYSTERESIS: AtomicU8 = AtomicU8::new(FADER_HYSTERESIS_DEFAULT);
+pub static FADER_SMOOTHING: AtomicU8 = AtomicU8::new(0);
 
 #[derive(Clone)]
 #[allow(dead_code)]
@@ -108,7 +136,10 @@ pub async fn start_max(
     mux_pins: MuxPins,
     cs: Peri<'static, PIN_17>,
     calibration_data: Option<MaxCalibration>,
+    fader_calibration: FaderCalibration,
 ) {
+    FADER_CALIBRATION.lock(|c| c.replace(fader_calibration));
+
     let device_config = DeviceConfig {
         thshdn: THSHDN::Enabled,
         dacref: DACREF::InternalRef,
@@ -137,6 +168,29 @@ pub async fn start_max(
     spawner.spawn(message_loop(max)).unwrap();
 }
 
+/// Map a raw fader reading to 0-4095 with the fader's calibrated ends
+fn rescale_fader(channel: usize, raw: u16) -> u16 {
+    FADER_CALIBRATION
+        .lock(|c| c.borrow().apply(channel, raw))
+        .unwrap_or_else(|| {
+            // Scale a bit across the dead-zone (~4087 -> 4095) using integer math
+            (((raw as u32 * 1002) / 1000) as u16).clamp(0, 4095)
+        })
+}
+
+/// Exponential moving average of the fader values, `smoothing` being the
+/// log2 of its length
+fn smooth_fader(state: &mut u32, val: u16, smoothing: u8) -> u16 {
+    let target = (val as u32) << FADER_SMOOTHING_FRAC_BITS;
+    if smoothing == 0 || val == 0 || val == 4095 {
+        // The ends pass straight through so a fader still fully closes and opens
+        *state = target;
+    } else {
+        *state = (*state * ((1 << smoothing) - 1) + target) >> smoothing;
+    }
+    ((*state + (1 << (FADER_SMOOTHING_FRAC_BITS - 1))) >> FADER_SMOOTHING_FRAC_BITS) as u16
+}
+
 #[embassy_executor::task]
 async fn read_fader(
     pio0: Peri<'static, PIO0>,
@@ -186,6 +240,7 @@ async fn read_fader(
         sm0.tx().wait_push(chan as u32).await;
         Timer::after_millis(1).await;
         let value = fader_port.get_value().await.unwrap();
+        let value = rescale_fader(channel, value);
         main_fader_values[channel] = value;
         // Also initialize the shared state
         MAX_VALUES_FADER[channel].store(value, Ordering::Relaxed);
@@ -199,6 +254,10 @@ async fn read_fader(
         AnalogLatch::new(main_fader_values[channel], global_config.takeover_mode)
     });
 
+    let mut smoothed: [u32; 16] = core::array::from_fn(|channel| {
+        (main_fader_values[channel] as u32) << FADER_SMOOTHING_FRAC_BITS
+    });
+
     let mut chan: usize = 0;
 
     loop {
@@ -231,10 +290,31 @@ async fn read_fader(
         }
         let mut sorted = burst;
         sorted.sort_unstable();
-        let val = sorted[FADER_BURST_READS / 2];
+        let raw = sorted[FADER_BURST_READS / 2];
+
+        // While the configurator calibrates the faders their ends are tracked
+        FADER_SWEEP.lock(|sweep| {
+            let mut sweep = sweep.borrow_mut();
+            let expired = match sweep.as_mut() {
+                Some((started, _)) if started.elapsed() > FADER_SWEEP_TIMEOUT => true,
+                Some((_, ends)) => {
+                    let (min, max) = &mut ends[channel];
+                    *min = (*min).min(raw);
+                    *max = (*max).max(raw);
+                    false
+                }
+                None => false,
+            };
+            if expired {
+                *sweep = None;
+            }
+        });
 
-        // Scale a bit across the dead-zone (~4087 -> 4095) using integer math
-        let val = (((val as u32 * 1002) / 1000) as u16).clamp(0, 4095);
+        let val = smooth_fader(
+            &mut smoothed[channel],
+            rescale_fader(channel, raw),
+            FADER_SMOOTHING.load(Ordering::Relaxed),
+        );
 
         let latch = &mut fader_latches[channel];
 
@@ -246,10 +326,11 @@ async fn read_fader(
 
         if let Some(new_value) = latch.update(val, active_layer, target_value) {
             let diff = (new_value as i32 - target_value as i32).abs();
+            let hysteresis = FADER_HYSTERESIS.load(Ordering::Relaxed) as i32;
 
             match active_layer {
                 LatchLayer::Main => {
-                    if diff >= 4 {
+                    if diff >= hysteresis {
                         event_publisher
                             .publish(InputEvent::FaderChange(channel))
                             .await;
@@ -258,7 +339,7 @@ async fn read_fader(
                     MAX_VALUES_FADER[channel].store(new_value, Ordering::Relaxed)
                 }
                 LatchLayer::Alt => {
-                    if diff >= 4 {
+                    if diff >= hysteresis {
                         set_global_config_via_chan(channel, new_value);
                         global_settings_fader_values[channel] = new_value;
                     }
@@ -299,13 +380,7 @@ async fn process_channel_values(
                             max11300::config::DACRANGE::RgNeg5_5v => 1,
                             _ => 0, // Default to 0-10V range for other ranges
                         };
-                        let (slope, intercept) = data.outputs[i][range_idx];
-
-                        (((target_dac_value as i64 * slope)
-                            + intercept
-                            + (CALIBRATION_SCALE_FACTOR / 2))
-                            >> 16)
-                            .clamp(0, 4095) as u16
+                        data.output_value(i, range_idx, target_dac_value)
                     } else {
                         target_dac_value
                     };
@@ -322,15 +397,7 @@ async fn process_channel_values(
                             max11300::config::ADCRANGE::RgNeg5_5v => 1,
                             _ => 0, // Default to 0-10V range for other ranges
                         };
-                        let (slope, intercept) = data.inputs[range_idx];
-
-                        // ideal = slope * raw + intercept
-                        // Using scaled integers: ideal = (raw * slope*S + intercept*S) / S
-                        // We use a bit shift for division by SCALE_FACTOR (a power of 2)
-                        // We add SCALE_FACTOR/2 for rounding
-                        (((value as i64 * slope) + intercept + (CALIBRATION_SCALE_FACTOR / 2))
-                            >> 16)
-                            .clamp(0, 4095) as u16
+                        data.input_value(range_idx, value)
                     } else {
                         value
                     };
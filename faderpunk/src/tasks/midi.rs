@@ -1,11 +1,11 @@
 use defmt::info;
 use embassy_futures::{
-    join::join3,
+    join::join,
     select::{select, select3, select4, Either, Either3, Either4},
 };
 use embassy_rp::{
     peripherals::USB,
-    uart::{Async, BufferedUartRx, BufferedUartTx, Error as UartError, UartTx},
+    uart::{Async, BufferedUartRx, BufferedUartTx, UartTx},
     usb::Driver,
 };
 use embassy_sync::{
@@ -14,8 +14,8 @@ use embassy_sync::{
     mutex::Mutex,
     pubsub::{PubSubChannel, Publisher, Subscriber},
 };
-use embassy_time::{with_timeout, Duration, Instant, Ticker, TimeoutError};
-use embedded_io_async::{Read, Write};
+use embassy_time::{with_timeout, Duration, Instant, Ticker, TimeoutError, Timer};
+use embedded_io_async::Read;
 use heapless::{Deque, Vec};
 use midly::{
     io::Cursor,
@@ -25,8 +25,15 @@ use midly::{
     MidiMessage,
 };
 use portable_atomic::Ordering;
+use static_cell::StaticCell;
 
-use libfp::{ClockSrc, MidiIn, MidiOut, MidiOutConfig, MidiOutMode, GLOBAL_CHANNELS};
+use libfp::{
+    cc14::Cc14Tracker,
+    mtc::parse_full_frame,
+    sysex::{identity_reply, is_identity_request, SYSEX_EOX, SYSEX_START},
+    utils::scale_bits_12_14,
+    ClockSrc, MidiIn, MidiLogPort, MidiOut, MidiOutConfig, MidiOutMode, GLOBAL_CHANNELS,
+};
 
 use crate::{
     events::{EventPubSubPublisher, InputEvent, EVENT_PUBSUB},
@@ -34,8 +41,13 @@ use crate::{
         clock::{ClockInEvent, SyncEngineEvent, SYNC_ENGINE_CHANNEL},
         configure::{CONFIG_FRAME_BUF, CONFIG_RX_CHANNEL},
         global_config::GLOBAL_CONFIG_WATCH,
+        midi_clock_out::ClockOutputs,
+        midi_din::{din_out_task, queue_din, queue_din_realtime, queue_din_sysex, DinMsg},
+        midi_log::{log_event, log_event_out, log_sysex},
+        midi_sysex::{claim_out, release_out, store_in, with_out, SysExIn, SYSEX_MAX_LEN},
     },
     usb_midi::{Receiver as UsbReceiver, Sender as UsbSender},
+    version::FIRMWARE_VERSION,
 };
 
 /// Virtual USB-MIDI cable carrying the configurator SysEx protocol.
@@ -65,6 +77,9 @@ const MIDI_PUBSUB_SENDERS: usize = 1;
 pub enum MidiEventSource {
     Local,
     Passthrough,
+    /// Generated timecode, routed by its own send flags rather than the
+    /// output mode
+    Sync,
 }
 
 #[derive(Clone, Copy)]
@@ -80,6 +95,18 @@ pub enum MidiMsg {
         value: u16,
         target: MidiOut,
     },
+    Cc14 {
+        channel: u4,
+        controller: u7,
+        value: u16,
+        target: MidiOut,
+    },
+    /// Body is held in the outgoing SysEx pool until routed
+    SysEx {
+        slot: u8,
+        target: MidiOut,
+        source: MidiEventSource,
+    },
 }
 
 impl MidiMsg {
@@ -99,6 +126,24 @@ impl MidiMsg {
             target,
         }
     }
+
+    /// 14-bit CC pair: `controller` (0-31) carries the MSB, `controller + 32`
+    /// the LSB. value is normalized to a range of 0-4095
+    pub fn cc14(channel: u4, controller: u7, value: u16, target: MidiOut) -> Self {
+        Self::Cc14 {
+            channel,
+            controller,
+            value,
+            target,
+        }
+    }
+
+    /// Release what a message holds when it is dropped instead of routed.
+    pub fn discard(self) {
+        if let Self::SysEx { slot, .. } = self {
+            release_out(slot);
+        }
+    }
 }
 
 #[derive(Clone, Copy)]
@@ -116,7 +161,17 @@ impl MidiRealtimeMsg {
 #[derive(Clone, Copy)]
 pub enum MidiEvent {
     Live(LiveEvent<'static>),
-    Nrpn { channel: u4, param: u16, value: u16 },
+    Nrpn {
+        channel: u4,
+        param: u16,
+        value: u16,
+    },
+    Cc14 {
+        channel: u4,
+        controller: u7,
+        value: u16,
+    },
+    SysEx(SysExIn),
 }
 
 pub static MIDI_CHANNEL: Channel<CriticalSectionRawMutex, MidiMsg, MIDI_CHANNEL_SIZE> =
@@ -238,29 +293,44 @@ async fn write_msg_to_usb<'a>(
     Ok(())
 }
 
-async fn write_msg_to_uart0(
-    uart0_tx: &mut UartTx<'static, Async>,
-    midi_ev: LiveEvent<'_>,
-) -> Result<(), UartError> {
-    let mut ser_buf = [0_u8; 3];
-    let mut ser_cursor = Cursor::new(&mut ser_buf);
-    midi_ev.write(&mut ser_cursor).unwrap();
-    let bytes_written = ser_cursor.cursor();
-    uart0_tx.write(&ser_buf[..bytes_written]).await?;
-    Ok(())
-}
-
-async fn write_msg_to_uart1(
-    uart1_tx: &mut BufferedUartTx,
-    midi_ev: LiveEvent<'_>,
-) -> Result<(), UartError> {
-    let mut ser_buf = [0_u8; 3];
-    let mut ser_cursor = Cursor::new(&mut ser_buf);
-    midi_ev.write(&mut ser_cursor).unwrap();
-    let bytes_written = ser_cursor.cursor();
-    uart1_tx.write_all(&ser_buf[..bytes_written]).await?;
-    uart1_tx.flush().await?;
-    Ok(())
+/// Writes the SysEx held in outgoing pool `slot` to USB cable 0 as CIN 0x4
+/// packets, ending with the 0x5/0x6/0x7 packet that carries F7.
+async fn write_sysex_to_usb<'a>(usb_tx: &SharedUsbSender<'a>, slot: u8) {
+    if !crate::tasks::transport::USB_CONNECTED.load(Ordering::Relaxed) {
+        return;
+    }
+    let len = with_out(slot, |body| body.len()) + 2;
+    let mut offset = 0;
+    while offset < len {
+        let chunk_len = (len - offset).min(3);
+        let mut usb_buf = [0_u8; 4];
+        usb_buf[0] = if offset + chunk_len < len {
+            CodeIndexNumber::SysExStarts as u8
+        } else {
+            match chunk_len {
+                1 => CodeIndexNumber::SystemCommonLen1 as u8,
+                2 => CodeIndexNumber::SysExEndsNext2 as u8,
+                _ => CodeIndexNumber::SysExEndsNext3 as u8,
+            }
+        };
+        with_out(slot, |body| {
+            for (i, byte) in usb_buf[1..=chunk_len].iter_mut().enumerate() {
+                *byte = match offset + i {
+                    0 => SYSEX_START,
+                    n if n == len - 1 => SYSEX_EOX,
+                    n => body[n - 1],
+                };
+            }
+        });
+        let res = with_timeout(Duration::from_millis(USB_WRITE_TIMEOUT_MS), async {
+            usb_tx.lock().await.write_packet(&usb_buf).await
+        })
+        .await;
+        if res.is_err() {
+            return;
+        }
+        offset += chunk_len;
+    }
 }
 
 #[embassy_executor::task]
@@ -276,8 +346,8 @@ pub async fn midi_distributor() {
         match select(app_midi_receiver.receive(), ticker.next()).await {
             // A new message from an app has arrived, enqueue it.
             Either::First((start_channel, ev)) => {
-                if !app_queues[start_channel].is_full() {
-                    let _ = app_queues[start_channel].push_back(ev);
+                if let Err(ev) = app_queues[start_channel].push_back(ev) {
+                    ev.discard();
                 }
             }
             // The throttle timer has fired, send a small burst.
@@ -305,42 +375,146 @@ pub async fn midi_distributor() {
     }
 }
 
-async fn write_realtime_msg<'a>(
-    usb_tx: &SharedUsbSender<'a>,
-    uart0_tx: &mut UartTx<'static, Async>,
-    uart1_tx: &mut BufferedUartTx,
-    msg: MidiRealtimeMsg,
-) {
-    let event = LiveEvent::Realtime(msg.event);
-    let usb_fut = async {
-        if let MidiOut([true, _, _]) = msg.target {
-            let _ = write_msg_to_usb(usb_tx, event).await;
+async fn write_realtime_msg<'a>(usb_tx: &SharedUsbSender<'a>, msg: MidiRealtimeMsg) {
+    log_event_out(msg.target, &LiveEvent::Realtime(msg.event), false);
+    for (i, enabled) in msg.target.0[1..].iter().enumerate() {
+        if *enabled {
+            queue_din_realtime(i, msg.event);
         }
-    };
-    let out1_fut = async {
-        if let MidiOut([_, true, _]) = msg.target {
-            let _ = write_msg_to_uart1(uart1_tx, event).await;
+    }
+    if let MidiOut([true, _, _]) = msg.target {
+        let _ = write_msg_to_usb(usb_tx, LiveEvent::Realtime(msg.event)).await;
+    }
+}
+
+/// CC messages making up an NRPN with a 14-bit value
+pub fn nrpn_ccs(param: u16, value: u16) -> [(u8, u8); 4] {
+    [
+        (99, (param >> 7) as u8),
+        (98, (param & 0x7F) as u8),
+        (6, (value >> 7) as u8),
+        (38, (value & 0x7F) as u8),
+    ]
+}
+
+/// MSB/LSB CC pair for a 14-bit value on `controller` (0-31)
+pub fn cc14_ccs(controller: u7, value: u16) -> [(u8, u8); 2] {
+    let msb_cc = controller.as_int();
+    [
+        (msb_cc, (value >> 7) as u8),
+        (msb_cc + 32, (value & 0x7F) as u8),
+    ]
+}
+
+fn cc_event(channel: u4, controller: u8, value: u8) -> LiveEvent<'static> {
+    LiveEvent::Midi {
+        channel,
+        message: MidiMessage::Controller {
+            controller: u7::new(controller),
+            value: u7::new(value),
+        },
+    }
+}
+
+/// Writes a sequence of CCs that must stay in order (NRPN, 14-bit CC pairs)
+/// to USB.
+async fn write_cc_sequence_to_usb<'a>(usb_tx: &SharedUsbSender<'a>, channel: u4, ccs: &[(u8, u8)]) {
+    for &(controller, value) in ccs {
+        let _ = write_msg_to_usb(usb_tx, cc_event(channel, controller, value)).await;
+    }
+}
+
+/// Records an outgoing message in the MIDI activity log
+fn log_msg_out(target: MidiOut, msg: DinMsg, thru: bool) {
+    let mut ccs = Vec::<(u8, u8), 4>::new();
+    let channel = match msg {
+        DinMsg::Live(event) => {
+            log_event_out(target, &event, thru);
+            return;
         }
-    };
-    let out2_fut = async {
-        if let MidiOut([_, _, true]) = msg.target {
-            let _ = write_msg_to_uart0(uart0_tx, event).await;
+        DinMsg::Nrpn {
+            channel,
+            param,
+            value,
+        } => {
+            let _ = ccs.extend_from_slice(&nrpn_ccs(param, value));
+            channel
+        }
+        DinMsg::Cc14 {
+            channel,
+            controller,
+            value,
+        } => {
+            let _ = ccs.extend_from_slice(&cc14_ccs(controller, value));
+            channel
         }
+        DinMsg::SysEx => return,
     };
-    join3(usb_fut, out1_fut, out2_fut).await;
+    for (controller, value) in ccs {
+        log_event_out(target, &cc_event(channel, controller, value), thru);
+    }
+}
+
+/// Hands a message to the schedulers of the enabled DIN outs and writes it to
+/// USB directly. `thru` marks passthrough for the activity log.
+async fn route_msg<'a>(usb_tx: &SharedUsbSender<'a>, target: MidiOut, msg: DinMsg, thru: bool) {
+    log_msg_out(target, msg, thru);
+    for (i, enabled) in target.0[1..].iter().enumerate() {
+        if *enabled {
+            queue_din(i, msg);
+        }
+    }
+    if let MidiOut([true, _, _]) = target {
+        match msg {
+            DinMsg::Live(event) => {
+                let _ = write_msg_to_usb(usb_tx, event).await;
+            }
+            DinMsg::Nrpn {
+                channel,
+                param,
+                value,
+            } => write_cc_sequence_to_usb(usb_tx, channel, &nrpn_ccs(param, value)).await,
+            DinMsg::Cc14 {
+                channel,
+                controller,
+                value,
+            } => write_cc_sequence_to_usb(usb_tx, channel, &cc14_ccs(controller, value)).await,
+            // Routed with its pool slot, see `MidiMsg::SysEx`
+            DinMsg::SysEx => {}
+        }
+    }
 }
 
 pub async fn midi_out_task<'a>(
     usb_tx: &SharedUsbSender<'a>,
-    mut uart0_tx: UartTx<'static, Async>,
-    mut uart1_tx: BufferedUartTx,
+    uart0_tx: UartTx<'static, Async>,
+    uart1_tx: BufferedUartTx,
+) {
+    join(midi_out_router(usb_tx), din_out_task(uart0_tx, uart1_tx)).await;
+}
+
+static CLOCK_OUTPUTS: StaticCell<ClockOutputs> = StaticCell::new();
+
+/// Sends `msg` to the outputs without a clock offset and queues it for the
+/// rest
+async fn delay_realtime_msg<'a>(
+    usb_tx: &SharedUsbSender<'a>,
+    clock_outs: &mut ClockOutputs,
+    msg: MidiRealtimeMsg,
 ) {
+    let target = clock_outs.push(msg.event, msg.target, Instant::now());
+    write_realtime_msg(usb_tx, MidiRealtimeMsg::new(msg.event, target)).await;
+}
+
+async fn midi_out_router<'a>(usb_tx: &SharedUsbSender<'a>) {
     let mut config_receiver = GLOBAL_CONFIG_WATCH.receiver().unwrap();
     let midi_receiver = MIDI_CHANNEL.receiver();
     let clock_receiver = MIDI_CLOCK_CHANNEL.receiver();
     let transport_receiver = MIDI_TRANSPORT_CHANNEL.receiver();
 
     let config = config_receiver.get().await;
+    let clock_outs = CLOCK_OUTPUTS.init(ClockOutputs::new());
+    clock_outs.configure(&config);
     let mut disabled_outs_for_local = config.midi.outs.map(|c| {
         matches!(
             c,
@@ -357,25 +531,45 @@ pub async fn midi_out_task<'a>(
     loop {
         // Realtime messages are drained before normal MIDI. Transport comes
         // first so Stop cannot sit behind stale timing clock ticks.
+        if let Some((event, target)) = clock_outs.pop_due(Instant::now()) {
+            write_realtime_msg(usb_tx, MidiRealtimeMsg::new(event, target)).await;
+            continue;
+        }
         if let Ok(msg) = transport_receiver.try_receive() {
-            write_realtime_msg(usb_tx, &mut uart0_tx, &mut uart1_tx, msg).await;
+            delay_realtime_msg(usb_tx, clock_outs, msg).await;
             continue;
         }
         if let Ok(msg) = clock_receiver.try_receive() {
-            write_realtime_msg(usb_tx, &mut uart0_tx, &mut uart1_tx, msg).await;
+            delay_realtime_msg(usb_tx, clock_outs, msg).await;
             continue;
         }
 
-        match select4(
-            transport_receiver.receive(),
-            clock_receiver.receive(),
-            midi_receiver.receive(),
-            config_receiver.changed(),
+        let next_due = clock_outs.next_due();
+        let due_fut = async {
+            match next_due {
+                Some(at) => Timer::at(at).await,
+                None => core::future::pending::<()>().await,
+            }
+        };
+
+        let event = select(
+            select4(
+                transport_receiver.receive(),
+                clock_receiver.receive(),
+                midi_receiver.receive(),
+                config_receiver.changed(),
+            ),
+            due_fut,
         )
-        .await
-        {
+        .await;
+        let Either::First(event) = event else {
+            // Delayed clock is due, sent at the top of the loop
+            continue;
+        };
+
+        match event {
             Either4::First(msg) | Either4::Second(msg) => {
-                write_realtime_msg(usb_tx, &mut uart0_tx, &mut uart1_tx, msg).await;
+                delay_realtime_msg(usb_tx, clock_outs, msg).await;
             }
             Either4::Third(midi_out_msg) => match midi_out_msg {
                 MidiMsg::Live {
@@ -390,23 +584,8 @@ pub async fn midi_out_task<'a>(
                             target.0[i] = target.0[i] && !disabled;
                         }
                     }
-
-                    let usb_fut = async {
-                        if let MidiOut([true, _, _]) = target {
-                            let _ = write_msg_to_usb(usb_tx, event).await;
-                        }
-                    };
-                    let out1_fut = async {
-                        if let MidiOut([_, true, _]) = target {
-                            let _ = write_msg_to_uart1(&mut uart1_tx, event).await;
-                        }
-                    };
-                    let out2_fut = async {
-                        if let MidiOut([_, _, true]) = target {
-                            let _ = write_msg_to_uart0(&mut uart0_tx, event).await;
-                        }
-                    };
-                    join3(usb_fut, out1_fut, out2_fut).await;
+                    let thru = matches!(source, MidiEventSource::Passthrough);
+                    route_msg(usb_tx, target, DinMsg::Live(event), thru).await;
                 }
                 MidiMsg::Nrpn {
                     channel,
@@ -414,55 +593,63 @@ pub async fn midi_out_task<'a>(
                     value,
                     mut target,
                 } => {
-                    use libfp::utils::scale_bits_12_14;
                     for (i, disabled) in disabled_outs_for_local.iter().enumerate() {
                         target.0[i] = target.0[i] && !disabled;
                     }
-                    let value_14 = scale_bits_12_14(value);
-                    let ccs: [LiveEvent<'static>; 4] = [
-                        LiveEvent::Midi {
-                            channel,
-                            message: MidiMessage::Controller {
-                                controller: u7::new(99),
-                                value: u7::new((param >> 7) as u8),
-                            },
-                        },
-                        LiveEvent::Midi {
-                            channel,
-                            message: MidiMessage::Controller {
-                                controller: u7::new(98),
-                                value: u7::new((param & 0x7F) as u8),
-                            },
-                        },
-                        LiveEvent::Midi {
-                            channel,
-                            message: MidiMessage::Controller {
-                                controller: u7::new(6),
-                                value: u7::new((value_14 >> 7) as u8),
-                            },
-                        },
-                        LiveEvent::Midi {
-                            channel,
-                            message: MidiMessage::Controller {
-                                controller: u7::new(38),
-                                value: u7::new((value_14 & 0x7F) as u8),
-                            },
-                        },
-                    ];
-                    for event in ccs {
-                        if let MidiOut([true, _, _]) = target {
-                            let _ = write_msg_to_usb(usb_tx, event).await;
+                    let msg = DinMsg::Nrpn {
+                        channel,
+                        param,
+                        value: scale_bits_12_14(value),
+                    };
+                    route_msg(usb_tx, target, msg, false).await;
+                }
+                MidiMsg::Cc14 {
+                    channel,
+                    controller,
+                    value,
+                    mut target,
+                } => {
+                    for (i, disabled) in disabled_outs_for_local.iter().enumerate() {
+                        target.0[i] = target.0[i] && !disabled;
+                    }
+                    let msg = DinMsg::Cc14 {
+                        channel,
+                        controller,
+                        value: scale_bits_12_14(value),
+                    };
+                    route_msg(usb_tx, target, msg, false).await;
+                }
+                MidiMsg::SysEx {
+                    slot,
+                    mut target,
+                    source,
+                } => {
+                    if let MidiEventSource::Local = source {
+                        for (i, disabled) in disabled_outs_for_local.iter().enumerate() {
+                            target.0[i] = target.0[i] && !disabled;
                         }
-                        if let MidiOut([_, true, _]) = target {
-                            let _ = write_msg_to_uart1(&mut uart1_tx, event).await;
+                    }
+                    let thru = matches!(source, MidiEventSource::Passthrough);
+                    with_out(slot, |body| {
+                        for (i, enabled) in target.0.iter().enumerate() {
+                            if *enabled {
+                                log_sysex(MidiLogPort::output(i), body, thru);
+                            }
                         }
-                        if let MidiOut([_, _, true]) = target {
-                            let _ = write_msg_to_uart0(&mut uart0_tx, event).await;
+                    });
+                    for (i, enabled) in target.0[1..].iter().enumerate() {
+                        if *enabled {
+                            queue_din_sysex(i, slot);
                         }
                     }
+                    if let MidiOut([true, _, _]) = target {
+                        write_sysex_to_usb(usb_tx, slot).await;
+                    }
+                    release_out(slot);
                 }
             },
             Either4::Fourth(new_config) => {
+                clock_outs.configure(&new_config);
                 disabled_outs_for_local = new_config.midi.outs.map(|c| {
                     matches!(
                         c,
@@ -496,9 +683,13 @@ pub async fn midi_in_task<'a>(
     let mut uart_rx_buffer = [0u8; 64];
     let mut midi_stream = MidiStream::<MidiStreamBuffer>::default();
     let mut uart_events = Vec::<LiveEvent<'static>, 64>::new();
-    let mut config_assembler = SysExAssembler::new();
+    let mut config_assembler = SysExAssembler::<CONFIG_FRAME_BUF>::new();
+    let usb_sysex = USB_SYSEX_ASSEMBLER.init(SysExAssembler::new());
+    let din_sysex = DIN_SYSEX_ASSEMBLER.init(SysExAssembler::new());
     let mut usb_nrpn_trackers: [NrpnTracker; 16] = Default::default();
     let mut din_nrpn_trackers: [NrpnTracker; 16] = Default::default();
+    let usb_cc14_trackers = USB_CC14_TRACKERS.init_with(Default::default);
+    let din_cc14_trackers = DIN_CC14_TRACKERS.init_with(Default::default);
 
     let config = config_receiver.get().await;
 
@@ -587,12 +778,33 @@ pub async fn midi_in_task<'a>(
 
                         let msg = &packet[1..1 + msg_len];
 
+                        let cin = packet[0] & 0x0F;
+                        if (0x4..=0x7).contains(&cin)
+                            && (usb_sysex.is_active() || msg[0] == SYSEX_START)
+                        {
+                            if usb_sysex.feed(cin, msg) {
+                                process_sysex(
+                                    usb_sysex.frame(),
+                                    &usb_publisher,
+                                    midi_passthru_from_usb,
+                                    MidiOut([true, false, false]),
+                                    ClockSrc::MidiUsb,
+                                    &sync_engine_sender,
+                                    &midi_sender,
+                                )
+                                .await;
+                                usb_sysex.clear();
+                            }
+                            continue;
+                        }
+
                         match LiveEvent::parse(msg) {
                             Ok(event) => {
                                 process_midi_event(
                                     &event,
                                     &usb_publisher,
                                     &mut usb_nrpn_trackers,
+                                    usb_cc14_trackers,
                                     midi_passthru_from_usb,
                                     ClockSrc::MidiUsb,
                                     &sync_engine_sender,
@@ -615,23 +827,57 @@ pub async fn midi_in_task<'a>(
                         continue;
                     }
 
-                    uart_events.clear();
-                    midi_stream.feed(&uart_rx_buffer[..bytes_read], |event| {
-                        let _ = uart_events.push(event.to_static());
-                    });
+                    // SysEx is split off before the stream parser, whose
+                    // buffer only fits channel and short system messages.
+                    // Events parsed before a completed frame go out first.
+                    let mut pos = 0;
+                    while pos < bytes_read {
+                        uart_events.clear();
+                        let mut sysex_complete = false;
+                        for &byte in &uart_rx_buffer[pos..bytes_read] {
+                            pos += 1;
+                            match din_sysex.feed_byte(byte) {
+                                SysExByte::Consumed => {}
+                                SysExByte::Complete => {
+                                    sysex_complete = true;
+                                    break;
+                                }
+                                SysExByte::Pass => {
+                                    midi_stream.feed(&[byte], |event| {
+                                        let _ = uart_events.push(event.to_static());
+                                    });
+                                }
+                            }
+                        }
 
-                    for event in uart_events.iter() {
-                        process_midi_event(
-                            event,
-                            &din_publisher,
-                            &mut din_nrpn_trackers,
-                            midi_passthru_from_din,
-                            ClockSrc::MidiIn,
-                            &sync_engine_sender,
-                            &midi_sender,
-                            &event_publisher,
-                        )
-                        .await;
+                        for event in uart_events.iter() {
+                            process_midi_event(
+                                event,
+                                &din_publisher,
+                                &mut din_nrpn_trackers,
+                                din_cc14_trackers,
+                                midi_passthru_from_din,
+                                ClockSrc::MidiIn,
+                                &sync_engine_sender,
+                                &midi_sender,
+                                &event_publisher,
+                            )
+                            .await;
+                        }
+
+                        if sysex_complete {
+                            process_sysex(
+                                din_sysex.frame(),
+                                &din_publisher,
+                                midi_passthru_from_din,
+                                MidiOut([false, true, true]),
+                                ClockSrc::MidiIn,
+                                &sync_engine_sender,
+                                &midi_sender,
+                            )
+                            .await;
+                            din_sysex.clear();
+                        }
                     }
                 }
             }
@@ -680,17 +926,28 @@ pub async fn midi_in_task<'a>(
     }
 }
 
-/// Reassembles SysEx frames from cable-1 USB-MIDI event packets (CIN 0x4
-/// start/continue, 0x5/0x6/0x7 end). Collects the frame body without the
-/// F0/F7 delimiters. Oversized frames are dropped whole.
-struct SysExAssembler {
-    buf: Vec<u8, CONFIG_FRAME_BUF>,
+/// Reassembles SysEx frames, either from USB-MIDI event packets (CIN 0x4
+/// start/continue, 0x5/0x6/0x7 end) or from a DIN byte stream. Collects the
+/// frame body without the F0/F7 delimiters. Oversized frames are dropped
+/// whole.
+struct SysExAssembler<const N: usize> {
+    buf: Vec<u8, N>,
     active: bool,
     overflow: bool,
 }
 
-impl SysExAssembler {
-    fn new() -> Self {
+/// What to do with a DIN byte after [`SysExAssembler::feed_byte`]
+enum SysExByte {
+    /// Part of a SysEx frame
+    Consumed,
+    /// Closed a frame, available via [`SysExAssembler::frame`]
+    Complete,
+    /// Not SysEx (or realtime inside SysEx), parse it as regular MIDI
+    Pass,
+}
+
+impl<const N: usize> SysExAssembler<N> {
+    const fn new() -> Self {
         Self {
             buf: Vec::new(),
             active: false,
@@ -698,6 +955,28 @@ impl SysExAssembler {
         }
     }
 
+    fn is_active(&self) -> bool {
+        self.active
+    }
+
+    fn start(&mut self) {
+        self.buf.clear();
+        self.overflow = false;
+        self.active = true;
+    }
+
+    /// Closes the current frame. Returns false if it overflowed.
+    fn finish(&mut self) -> bool {
+        self.active = false;
+        if self.overflow {
+            defmt::warn!("SysEx frame overflow, dropping");
+            self.buf.clear();
+            self.overflow = false;
+            return false;
+        }
+        true
+    }
+
     /// Feed the data bytes of one event packet. Returns true when a complete
     /// frame is available via [`Self::frame`]; call [`Self::clear`] after
     /// consuming it.
@@ -708,9 +987,7 @@ impl SysExAssembler {
             if bytes.first() != Some(&0xF0) {
                 return false;
             }
-            self.buf.clear();
-            self.overflow = false;
-            self.active = true;
+            self.start();
             bytes = &bytes[1..];
         }
         // End CINs carry a trailing F7 that is not part of the body
@@ -724,14 +1001,40 @@ impl SysExAssembler {
         if !is_end {
             return false;
         }
-        self.active = false;
-        if self.overflow {
-            defmt::warn!("Config SysEx frame overflow, dropping");
-            self.buf.clear();
-            self.overflow = false;
-            return false;
+        self.finish()
+    }
+
+    /// Feed one byte of a DIN stream. Realtime bytes may be interleaved with
+    /// a frame; any other status byte aborts it.
+    fn feed_byte(&mut self, byte: u8) -> SysExByte {
+        if !self.active {
+            if byte == SYSEX_START {
+                self.start();
+                return SysExByte::Consumed;
+            }
+            return SysExByte::Pass;
+        }
+        match byte {
+            0x00..=0x7F => {
+                if self.buf.push(byte).is_err() {
+                    self.overflow = true;
+                }
+                SysExByte::Consumed
+            }
+            SYSEX_EOX => {
+                if self.finish() {
+                    SysExByte::Complete
+                } else {
+                    SysExByte::Consumed
+                }
+            }
+            0xF8..=0xFF => SysExByte::Pass,
+            _ => {
+                self.active = false;
+                self.buf.clear();
+                SysExByte::Pass
+            }
         }
-        true
     }
 
     fn frame(&self) -> &[u8] {
@@ -743,6 +1046,10 @@ impl SysExAssembler {
     }
 }
 
+// Kept out of the transport future, like the CC14 trackers
+static USB_SYSEX_ASSEMBLER: StaticCell<SysExAssembler<SYSEX_MAX_LEN>> = StaticCell::new();
+static DIN_SYSEX_ASSEMBLER: StaticCell<SysExAssembler<SYSEX_MAX_LEN>> = StaticCell::new();
+
 #[derive(Default)]
 struct NrpnTracker {
     param_msb: Option<u8>,
@@ -806,17 +1113,35 @@ impl NrpnTracker {
     }
 }
 
+// Kept out of the transport future, which is already close to the size limit
+static USB_CC14_TRACKERS: StaticCell<[Cc14Tracker; 16]> = StaticCell::new();
+static DIN_CC14_TRACKERS: StaticCell<[Cc14Tracker; 16]> = StaticCell::new();
+
+/// Activity log port of the input a clock source stands for
+fn log_port(clock_src: ClockSrc) -> MidiLogPort {
+    if clock_src == ClockSrc::MidiUsb {
+        MidiLogPort::UsbIn
+    } else {
+        MidiLogPort::DinIn
+    }
+}
+
 #[allow(clippy::too_many_arguments)]
 async fn process_midi_event(
     event: &LiveEvent<'_>,
     publisher: &MidiPubSubPublisher,
     nrpn_trackers: &mut [NrpnTracker; 16],
+    cc14_trackers: &mut [Cc14Tracker; 16],
     thru_targets: [bool; 3],
     clock_src: ClockSrc,
     sync_engine_sender: &Sender<'static, ThreadModeRawMutex, SyncEngineEvent, 16>,
     midi_sender: &Sender<'static, CriticalSectionRawMutex, MidiMsg, MIDI_CHANNEL_SIZE>,
     event_publisher: &EventPubSubPublisher,
 ) {
+    // Realtime is consumed here, never passed through
+    let thru = !matches!(event, LiveEvent::Realtime(_)) && thru_targets.iter().any(|t| *t);
+    log_event(log_port(clock_src), event, thru);
+
     match event {
         LiveEvent::Realtime(msg) => match msg {
             SystemRealtime::TimingClock => {
@@ -870,17 +1195,52 @@ async fn process_midi_event(
                 ))
                 .await;
 
-            // Route CC through NRPN tracker
+            // Route CC through NRPN tracker, then pair plain CCs as 14-bit
             if let MidiMessage::Controller { controller, value } = message {
                 let tracker = &mut nrpn_trackers[channel.as_int() as usize];
                 if let Some(midi_event) = tracker.process_cc(*channel, *controller, *value) {
                     publisher.publish_immediate(midi_event);
+                    if let MidiEvent::Live(_) = midi_event {
+                        let cc14_tracker = &mut cc14_trackers[channel.as_int() as usize];
+                        if let Some((controller, value)) =
+                            cc14_tracker.process_cc(*controller, *value)
+                        {
+                            publisher.publish_immediate(MidiEvent::Cc14 {
+                                channel: *channel,
+                                controller,
+                                value,
+                            });
+                        }
+                    }
                 }
             } else {
                 publisher.publish_immediate(MidiEvent::Live(ev));
             }
         }
         _ => {
+            match event {
+                LiveEvent::Common(SystemCommon::SongPosition(beats)) => {
+                    // SPP counts 16th notes, six 24ppqn ticks each
+                    let tick = beats.as_int() as u64 * 6;
+                    sync_engine_sender
+                        .send(SyncEngineEvent::Transport(ClockInEvent::Locate(
+                            clock_src, tick,
+                        )))
+                        .await;
+                }
+                LiveEvent::Common(SystemCommon::MidiTimeCodeQuarterFrame(kind, nibble)) => {
+                    sync_engine_sender
+                        .send(SyncEngineEvent::QuarterFrame {
+                            source: clock_src,
+                            piece: *kind as u8,
+                            nibble: nibble.as_int(),
+                            timestamp: Instant::now(),
+                        })
+                        .await;
+                }
+                _ => {}
+            }
+
             let ev = event.to_static();
             publisher.publish_immediate(MidiEvent::Live(ev));
             midi_sender
@@ -894,6 +1254,66 @@ async fn process_midi_event(
     }
 }
 
+/// Handles a complete SysEx body from a performance port: answers Identity
+/// Requests on `reply_target`, hands MTC Full Frames to the clock, forwards it
+/// to thru/merge outputs and hands it to apps. Dropped where the SysEx pools
+/// are full.
+async fn process_sysex(
+    body: &[u8],
+    publisher: &MidiPubSubPublisher,
+    thru_targets: [bool; 3],
+    reply_target: MidiOut,
+    clock_src: ClockSrc,
+    sync_engine_sender: &Sender<'static, ThreadModeRawMutex, SyncEngineEvent, 16>,
+    midi_sender: &Sender<'static, CriticalSectionRawMutex, MidiMsg, MIDI_CHANNEL_SIZE>,
+) {
+    log_sysex(log_port(clock_src), body, thru_targets.iter().any(|t| *t));
+
+    if let Some((tc, fps)) = parse_full_frame(body) {
+        sync_engine_sender
+            .send(SyncEngineEvent::FullFrame {
+                source: clock_src,
+                frames: tc.to_frames(fps),
+                fps,
+            })
+            .await;
+    }
+
+    if is_identity_request(body) {
+        match claim_out(&identity_reply(FIRMWARE_VERSION)) {
+            Some(slot) => {
+                midi_sender
+                    .send(MidiMsg::SysEx {
+                        slot,
+                        target: reply_target,
+                        source: MidiEventSource::Local,
+                    })
+                    .await;
+            }
+            None => defmt::warn!("SysEx out pool full, dropping identity reply"),
+        }
+    }
+
+    if thru_targets.iter().any(|t| *t) {
+        match claim_out(body) {
+            Some(slot) => {
+                midi_sender
+                    .send(MidiMsg::SysEx {
+                        slot,
+                        target: MidiOut(thru_targets),
+                        source: MidiEventSource::Passthrough,
+                    })
+                    .await;
+            }
+            None => defmt::warn!("SysEx out pool full, dropping passthrough"),
+        }
+    }
+
+    if let Some(sysex) = store_in(body) {
+        publisher.publish_immediate(MidiEvent::SysEx(sysex));
+    }
+}
+
 fn cin_from_live_event(midi_ev: &LiveEvent) -> CodeIndexNumber {
     match midi_ev {
         LiveEvent::Realtime(..) => CodeIndexNumber::SingleByte,
@@ -0,0 +1,126 @@
+//! Per-output MIDI clock offset and division.
+//!
+//! Clock and transport bound for an output with an offset wait here until
+//! they are due. A positive offset simply delays the output. A negative
+//! offset needs the clock early: the internal clock engine sends its MIDI
+//! ticks ahead of the beat by the largest negative offset (see
+//! `clock::midi_clock_lead`), and every other output is held back by that
+//! lead again, so only the early outputs actually get ahead. Transport is
+//! never sent early, the receiver just sees the first clock sooner.
+
+use embassy_time::{Duration, Instant};
+use heapless::Deque;
+use midly::live::SystemRealtime;
+
+use libfp::{GlobalConfig, MidiOut};
+
+use super::clock::midi_clock_lead;
+
+/// Enough for the longest delay (offset plus lead, 200ms) at 300 BPM
+const CLOCK_DELAY_DEPTH: usize = 32;
+
+struct ClockOutput {
+    queue: Deque<(Instant, SystemRealtime), CLOCK_DELAY_DEPTH>,
+    tick_delay: Duration,
+    transport_delay: Duration,
+    div: u16,
+    div_count: u16,
+}
+
+impl ClockOutput {
+    const fn new() -> Self {
+        Self {
+            queue: Deque::new(),
+            tick_delay: Duration::from_ticks(0),
+            transport_delay: Duration::from_ticks(0),
+            div: 1,
+            div_count: 0,
+        }
+    }
+}
+
+/// Delay lines and clock dividers for [USB, out1, out2]
+pub struct ClockOutputs {
+    outs: [ClockOutput; 3],
+}
+
+impl ClockOutputs {
+    pub const fn new() -> Self {
+        Self {
+            outs: [const { ClockOutput::new() }; 3],
+        }
+    }
+
+    pub fn configure(&mut self, config: &GlobalConfig) {
+        let lead = midi_clock_lead(config).as_millis() as i64;
+        for (out, out_config) in self.outs.iter_mut().zip(config.midi.outs.iter()) {
+            let offset = out_config.clock_offset_ms as i64;
+            out.tick_delay = Duration::from_millis((offset + lead).max(0) as u64);
+            out.transport_delay = Duration::from_millis(offset.max(0) as u64);
+            out.div = out_config.clock_div as u16;
+            if out.div_count >= out.div {
+                out.div_count = 0;
+            }
+        }
+    }
+
+    /// Takes a realtime message for `target` and returns the outputs it
+    /// should be sent to right away. Everything else is queued until due.
+    pub fn push(&mut self, event: SystemRealtime, target: MidiOut, now: Instant) -> MidiOut {
+        let mut immediate = MidiOut([false; 3]);
+        for (i, out) in self.outs.iter_mut().enumerate() {
+            if !target.0[i] {
+                continue;
+            }
+            let delay = match event {
+                SystemRealtime::TimingClock => {
+                    let count = out.div_count;
+                    out.div_count = (count + 1) % out.div;
+                    if count != 0 {
+                        continue;
+                    }
+                    out.tick_delay
+                }
+                SystemRealtime::Start | SystemRealtime::Reset => {
+                    out.div_count = 0;
+                    out.transport_delay
+                }
+                _ => out.transport_delay,
+            };
+            if delay.as_ticks() == 0 && out.queue.is_empty() {
+                immediate.0[i] = true;
+                continue;
+            }
+            // Never overtake what's already queued, so the order holds when
+            // the offset shrinks
+            let mut due = now + delay;
+            if let Some((back, _)) = out.queue.back() {
+                due = due.max(*back);
+            }
+            // Lossy like the clock channel itself
+            let _ = out.queue.push_back((due, event));
+        }
+        immediate
+    }
+
+    /// Earliest time a queued message is due
+    pub fn next_due(&self) -> Option<Instant> {
+        self.outs
+            .iter()
+            .filter_map(|out| out.queue.front().map(|(due, _)| *due))
+            .min()
+    }
+
+    /// Takes the next message due by `now`, with its single output
+    pub fn pop_due(&mut self, now: Instant) -> Option<(SystemRealtime, MidiOut)> {
+        for (i, out) in self.outs.iter_mut().enumerate() {
+            if matches!(out.queue.front(), Some((due, _)) if *due <= now) {
+                let (_, event) = out.queue.pop_front()?;
+                let mut target = MidiOut([false; 3]);
+                target.0[i] = true;
+                return Some((event, target));
+            }
+        }
+        None
+    }
+}
@@ -0,0 +1,433 @@
+//! Outgoing scheduling for the DIN MIDI outputs.
+//!
+//! At 31.25 kbaud a three-byte message occupies the wire for ~1ms, so a few
+//! faders streaming CCs can easily back up notes and clock. Each DIN output
+//! gets its own scheduler: realtime goes first, then notes and other
+//! order-sensitive messages, then continuous values (CC, pitch bend,
+//! pressure, NRPN), which keep only their latest pending value. Sends are
+//! capped by a small message budget and use running status. SysEx is
+//! written in short chunks with realtime bytes interleaved, so a long dump
+//! doesn't stall clock.
+
+use core::cell::RefCell;
+
+use embassy_futures::{join::join, select::select};
+use embassy_rp::uart::{Async, BufferedUartTx, Error as UartError, UartTx};
+use embassy_sync::{
+    blocking_mutex::{raw::CriticalSectionRawMutex, Mutex},
+    signal::Signal,
+};
+use embassy_time::{Duration, Instant, Timer};
+use embedded_io_async::Write;
+use heapless::Deque;
+use midly::{
+    io::Cursor,
+    live::{LiveEvent, SystemRealtime},
+    num::{u4, u7},
+    MidiMessage,
+};
+
+use libfp::{
+    sysex::{SYSEX_EOX, SYSEX_START},
+    MidiOutStats,
+};
+
+use super::midi::{cc14_ccs, nrpn_ccs};
+use super::midi_sysex::{with_out, SysExData};
+
+/// DIN outputs handled here: [out1 (UART1), out2 (UART0)]
+pub const DIN_OUTS: usize = 2;
+
+const REALTIME_QUEUE_SIZE: usize = 8;
+const ORDERED_QUEUE_SIZE: usize = 32;
+const CONTINUOUS_SLOTS: usize = 32;
+const SYSEX_QUEUE_SIZE: usize = 2;
+/// Bytes written between realtime checks while sending SysEx (~2.5ms)
+const SYSEX_CHUNK: usize = 8;
+/// Sustained rate cap: one message per interval, with short bursts of up to
+/// `DIN_MSG_BURST` back to back. Realtime bytes are not counted.
+const DIN_MSG_INTERVAL: Duration = Duration::from_millis(1);
+const DIN_MSG_BURST: i32 = 4;
+/// Re-send the status byte at least this often, so a receiver connected in
+/// the middle of a stream picks up running status quickly.
+const RUNNING_STATUS_REFRESH: Duration = Duration::from_millis(250);
+
+/// A message queued for a DIN output
+#[derive(Clone, Copy)]
+pub enum DinMsg {
+    Live(LiveEvent<'static>),
+    /// 14-bit value, sent as CC 99/98/6/38
+    Nrpn {
+        channel: u4,
+        param: u16,
+        value: u16,
+    },
+    /// 14-bit value, sent as CC n/n+32
+    Cc14 {
+        channel: u4,
+        controller: u7,
+        value: u16,
+    },
+    /// Body is held in the scheduler's SysEx queue; only queued through
+    /// [`queue_din_sysex`]
+    SysEx,
+}
+
+/// Identifies a continuous value for coalescing
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum SlotKey {
+    Controller(u4, u7),
+    Aftertouch(u4, u7),
+    ChannelAftertouch(u4),
+    PitchBend(u4),
+    Nrpn(u4, u16),
+    Cc14(u4, u7),
+}
+
+impl DinMsg {
+    /// Key for messages where only the latest value matters. `None` for
+    /// messages where every occurrence and its order matter.
+    fn slot_key(&self) -> Option<SlotKey> {
+        match *self {
+            DinMsg::Live(LiveEvent::Midi { channel, message }) => match message {
+                MidiMessage::Controller { controller, .. } if is_continuous_cc(controller) => {
+                    Some(SlotKey::Controller(channel, controller))
+                }
+                MidiMessage::Aftertouch { key, .. } => Some(SlotKey::Aftertouch(channel, key)),
+                MidiMessage::ChannelAftertouch { .. } => Some(SlotKey::ChannelAftertouch(channel)),
+                MidiMessage::PitchBend { .. } => Some(SlotKey::PitchBend(channel)),
+                _ => None,
+            },
+            DinMsg::Live(_) => None,
+            DinMsg::Nrpn { channel, param, .. } => Some(SlotKey::Nrpn(channel, param)),
+            DinMsg::Cc14 {
+                channel,
+                controller,
+                ..
+            } => Some(SlotKey::Cc14(channel, controller)),
+            DinMsg::SysEx => None,
+        }
+    }
+
+    /// Number of wire messages this takes against the rate budget
+    fn cost(&self) -> i32 {
+        match self {
+            DinMsg::Live(LiveEvent::Realtime(_)) => 0,
+            DinMsg::Live(_) | DinMsg::SysEx => 1,
+            DinMsg::Nrpn { .. } => 4,
+            DinMsg::Cc14 { .. } => 2,
+        }
+    }
+}
+
+/// Bank select, data entry, (N)RPN addressing and channel mode messages only
+/// make sense in sequence with their neighbours, so they are never coalesced.
+/// Neither are the pedal and switch CCs (sustain to hold 2, portamento
+/// control): a down/up pair must not collapse into one, and they have to stay
+/// in order with the notes around them.
+fn is_continuous_cc(controller: u7) -> bool {
+    !matches!(
+        controller.as_int(),
+        0 | 6 | 32 | 38 | 64..=69 | 84 | 96..=101 | 120..=127
+    )
+}
+
+struct DinScheduler {
+    realtime: Deque<SystemRealtime, REALTIME_QUEUE_SIZE>,
+    ordered: Deque<DinMsg, ORDERED_QUEUE_SIZE>,
+    /// Pending continuous values in arrival order, at most one per key
+    continuous: Deque<(SlotKey, DinMsg), CONTINUOUS_SLOTS>,
+    /// Bodies for the `DinMsg::SysEx` markers in `ordered`, in the same order
+    sysex: Deque<SysExData, SYSEX_QUEUE_SIZE>,
+    stats: MidiOutStats,
+}
+
+impl DinScheduler {
+    const fn new() -> Self {
+        Self {
+            realtime: Deque::new(),
+            ordered: Deque::new(),
+            continuous: Deque::new(),
+            sysex: Deque::new(),
+            stats: MidiOutStats {
+                dropped: 0,
+                coalesced: 0,
+            },
+        }
+    }
+
+    fn push_realtime(&mut self, event: SystemRealtime) {
+        if self.realtime.push_back(event).is_err() {
+            self.stats.dropped = self.stats.dropped.wrapping_add(1);
+        }
+    }
+
+    fn push(&mut self, msg: DinMsg) {
+        let res = match msg.slot_key() {
+            Some(key) => {
+                // A newer value replaces the pending one in place, so a busy
+                // controller keeps its position instead of being starved
+                if let Some(slot) = self.continuous.iter_mut().find(|(k, _)| *k == key) {
+                    slot.1 = msg;
+                    self.stats.coalesced = self.stats.coalesced.wrapping_add(1);
+                    return;
+                }
+                self.continuous.push_back((key, msg)).map_err(|_| ())
+            }
+            None => self.ordered.push_back(msg).map_err(|_| ()),
+        };
+        if res.is_err() {
+            self.stats.dropped = self.stats.dropped.wrapping_add(1);
+        }
+    }
+
+    fn push_sysex(&mut self, body: &[u8]) {
+        let mut data = SysExData::new();
+        // Bodies come from the SysEx pool, which has the same bound
+        let _ = data.extend_from_slice(body);
+        if self.ordered.is_full() || self.sysex.push_back(data).is_err() {
+            self.stats.dropped = self.stats.dropped.wrapping_add(1);
+            return;
+        }
+        let _ = self.ordered.push_back(DinMsg::SysEx);
+    }
+
+    /// Next message to write. Realtime is always returned; everything else
+    /// only while the rate budget allows.
+    fn pop(&mut self, within_budget: bool) -> Option<DinMsg> {
+        if let Some(event) = self.realtime.pop_front() {
+            return Some(DinMsg::Live(LiveEvent::Realtime(event)));
+        }
+        if !within_budget {
+            return None;
+        }
+        self.ordered
+            .pop_front()
+            .or_else(|| self.continuous.pop_front().map(|(_, msg)| msg))
+    }
+
+    fn has_pending(&self) -> bool {
+        !self.realtime.is_empty() || !self.ordered.is_empty() || !self.continuous.is_empty()
+    }
+}
+
+static DIN_SCHEDULERS: [Mutex<CriticalSectionRawMutex, RefCell<DinScheduler>>; DIN_OUTS] =
+    [const { Mutex::new(RefCell::new(DinScheduler::new())) }; DIN_OUTS];
+
+static DIN_WAKE: [Signal<CriticalSectionRawMutex, ()>; DIN_OUTS] =
+    [const { Signal::new() }; DIN_OUTS];
+
+/// Queue a message for DIN output `out` (0 = out1, 1 = out2).
+pub fn queue_din(out: usize, msg: DinMsg) {
+    DIN_SCHEDULERS[out].lock(|s| s.borrow_mut().push(msg));
+    DIN_WAKE[out].signal(());
+}
+
+/// Queue a realtime message for DIN output `out`, ahead of everything else.
+pub fn queue_din_realtime(out: usize, event: SystemRealtime) {
+    DIN_SCHEDULERS[out].lock(|s| s.borrow_mut().push_realtime(event));
+    DIN_WAKE[out].signal(());
+}
+
+/// Queue the SysEx body held in outgoing pool `slot` for DIN output `out`.
+pub fn queue_din_sysex(out: usize, slot: u8) {
+    DIN_SCHEDULERS[out].lock(|s| with_out(slot, |body| s.borrow_mut().push_sysex(body)));
+    DIN_WAKE[out].signal(());
+}
+
+/// Scheduler counters for [out1, out2]
+pub fn din_stats() -> [MidiOutStats; DIN_OUTS] {
+    core::array::from_fn(|i| DIN_SCHEDULERS[i].lock(|s| s.borrow().stats))
+}
+
+/// Running status per the MIDI 1.0 spec: a channel message repeating the
+/// last status byte omits it. Realtime bytes leave it untouched, system
+/// common messages cancel it.
+#[derive(Default)]
+struct RunningStatus {
+    /// Current status byte and when it must next be sent in full
+    current: Option<(u8, Instant)>,
+}
+
+impl RunningStatus {
+    fn apply<'b>(&mut self, bytes: &'b [u8]) -> &'b [u8] {
+        let status = bytes[0];
+        match status {
+            0x80..=0xEF => {
+                let now = Instant::now();
+                match self.current {
+                    Some((current, refresh_at)) if current == status && now < refresh_at => {
+                        &bytes[1..]
+                    }
+                    _ => {
+                        self.current = Some((status, now + RUNNING_STATUS_REFRESH));
+                        bytes
+                    }
+                }
+            }
+            0xF8..=0xFF => bytes,
+            _ => {
+                self.current = None;
+                bytes
+            }
+        }
+    }
+
+    fn reset(&mut self) {
+        self.current = None;
+    }
+}
+
+trait DinTx {
+    async fn write_bytes(&mut self, bytes: &[u8]) -> Result<(), UartError>;
+}
+
+impl DinTx for UartTx<'static, Async> {
+    async fn write_bytes(&mut self, bytes: &[u8]) -> Result<(), UartError> {
+        self.write(bytes).await
+    }
+}
+
+impl DinTx for BufferedUartTx {
+    async fn write_bytes(&mut self, bytes: &[u8]) -> Result<(), UartError> {
+        self.write_all(bytes).await?;
+        self.flush().await
+    }
+}
+
+async fn write_event(
+    tx: &mut impl DinTx,
+    running_status: &mut RunningStatus,
+    event: LiveEvent<'_>,
+) {
+    let mut ser_buf = [0_u8; 3];
+    let mut ser_cursor = Cursor::new(&mut ser_buf);
+    if event.write(&mut ser_cursor).is_err() {
+        return;
+    }
+    let bytes_written = ser_cursor.cursor();
+    let bytes = running_status.apply(&ser_buf[..bytes_written]);
+    if tx.write_bytes(bytes).await.is_err() {
+        // The receiver may have missed the status byte
+        running_status.reset();
+    }
+}
+
+async fn write_ccs(
+    tx: &mut impl DinTx,
+    running_status: &mut RunningStatus,
+    channel: u4,
+    ccs: &[(u8, u8)],
+) {
+    for &(controller, value) in ccs {
+        let event = LiveEvent::Midi {
+            channel,
+            message: MidiMessage::Controller {
+                controller: u7::new(controller),
+                value: u7::new(value),
+            },
+        };
+        write_event(tx, running_status, event).await;
+    }
+}
+
+/// Writes the SysEx at the front of the queue, F0 to F7, letting queued
+/// realtime bytes through between chunks.
+async fn write_sysex(out: usize, tx: &mut impl DinTx, running_status: &mut RunningStatus) {
+    running_status.reset();
+    let _ = tx.write_bytes(&[SYSEX_START]).await;
+    let mut offset = 0;
+    loop {
+        let mut chunk = [0_u8; SYSEX_CHUNK];
+        let (len, realtime) = DIN_SCHEDULERS[out].lock(|s| {
+            let mut s = s.borrow_mut();
+            let len = s.sysex.front().map_or(0, |body| {
+                let rest = body.get(offset..).unwrap_or_default();
+                let len = rest.len().min(SYSEX_CHUNK);
+                chunk[..len].copy_from_slice(&rest[..len]);
+                len
+            });
+            (len, s.realtime.pop_front())
+        });
+        if let Some(event) = realtime {
+            write_event(tx, running_status, LiveEvent::Realtime(event)).await;
+        }
+        if len == 0 {
+            break;
+        }
+        let _ = tx.write_bytes(&chunk[..len]).await;
+        offset += len;
+    }
+    let _ = tx.write_bytes(&[SYSEX_EOX]).await;
+    DIN_SCHEDULERS[out].lock(|s| s.borrow_mut().sysex.pop_front());
+}
+
+async fn din_writer(out: usize, mut tx: impl DinTx) {
+    let mut running_status = RunningStatus::default();
+    let mut budget = DIN_MSG_BURST;
+    let mut last_refill = Instant::now();
+
+    loop {
+        let elapsed = Instant::now() - last_refill;
+        let refill = (elapsed.as_ticks() / DIN_MSG_INTERVAL.as_ticks()) as i32;
+        if refill > 0 {
+            budget = (budget + refill).min(DIN_MSG_BURST);
+            last_refill += DIN_MSG_INTERVAL * refill as u32;
+        }
+
+        let (next, pending) = DIN_SCHEDULERS[out].lock(|s| {
+            let mut s = s.borrow_mut();
+            (s.pop(budget > 0), s.has_pending())
+        });
+
+        match next {
+            Some(msg) => {
+                budget -= msg.cost();
+                match msg {
+                    DinMsg::Live(event) => write_event(&mut tx, &mut running_status, event).await,
+                    DinMsg::Nrpn {
+                        channel,
+                        param,
+                        value,
+                    } => {
+                        write_ccs(
+                            &mut tx,
+                            &mut running_status,
+                            channel,
+                            &nrpn_ccs(param, value),
+                        )
+                        .await
+                    }
+                    DinMsg::Cc14 {
+                        channel,
+                        controller,
+                        value,
+                    } => {
+                        write_ccs(
+                            &mut tx,
+                            &mut running_status,
+                            channel,
+                            &cc14_ccs(controller, value),
+                        )
+                        .await
+                    }
+                    DinMsg::SysEx => write_sysex(out, &mut tx, &mut running_status).await,
+                }
+            }
+            None if pending => {
+                // Over budget: wait for the next refill, or for realtime
+                select(
+                    Timer::at(last_refill + DIN_MSG_INTERVAL),
+                    DIN_WAKE[out].wait(),
+                )
+                .await;
+            }
+            None => DIN_WAKE[out].wait().await,
+        }
+    }
+}
+
+/// Drains the DIN schedulers into the UARTs: out1 is UART1, out2 is UART0.
+pub async fn din_out_task(uart0_tx: UartTx<'static, Async>, uart1_tx: BufferedUartTx) {
+    join(din_writer(0, uart1_tx), din_writer(1, uart0_tx)).await;
+}
@@ -0,0 +1,118 @@
+//! MIDI activity log: a fixed-size ring of recent messages in and out of
+//! every port, read over the config cable (`GetMidiLog`) to see where
+//! routing goes wrong. The oldest entry is overwritten when the ring is full.
+
+use core::cell::RefCell;
+
+use embassy_sync::blocking_mutex::{raw::CriticalSectionRawMutex, Mutex};
+use embassy_time::Instant;
+use heapless::Deque;
+use midly::{io::Cursor, live::LiveEvent};
+
+use libfp::{
+    sysex::{SYSEX_EOX, SYSEX_START},
+    MidiLogEntry, MidiLogFilter, MidiLogPort, MidiOut,
+};
+
+const MIDI_LOG_SIZE: usize = 64;
+
+struct MidiLog {
+    entries: Deque<MidiLogEntry, MIDI_LOG_SIZE>,
+    next_seq: u32,
+    filter: MidiLogFilter,
+}
+
+static MIDI_LOG: Mutex<CriticalSectionRawMutex, RefCell<MidiLog>> =
+    Mutex::new(RefCell::new(MidiLog {
+        entries: Deque::new(),
+        next_seq: 0,
+        filter: MidiLogFilter::new(),
+    }));
+
+/// Records a message of `len` bytes starting with `bytes`
+fn log_bytes(port: MidiLogPort, bytes: &[u8], len: usize, thru: bool) {
+    let Some(&status) = bytes.first() else {
+        return;
+    };
+    MIDI_LOG.lock(|log| {
+        let mut log = log.borrow_mut();
+        if !log.filter.accepts(port, status) {
+            return;
+        }
+        let mut entry = MidiLogEntry {
+            seq: log.next_seq,
+            time_ms: Instant::now().as_millis() as u32,
+            port,
+            thru,
+            len: len.min(u8::MAX as usize) as u8,
+            bytes: [0; 3],
+        };
+        let shown = bytes.len().min(entry.bytes.len());
+        entry.bytes[..shown].copy_from_slice(&bytes[..shown]);
+        log.next_seq = log.next_seq.wrapping_add(1);
+        if log.entries.is_full() {
+            log.entries.pop_front();
+        }
+        let _ = log.entries.push_back(entry);
+    });
+}
+
+pub fn log_event(port: MidiLogPort, event: &LiveEvent, thru: bool) {
+    let mut buf = [0u8; 3];
+    let mut cursor = Cursor::new(&mut buf);
+    if event.write(&mut cursor).is_ok() {
+        let written = cursor.written();
+        log_bytes(port, written, written.len(), thru);
+    }
+}
+
+/// Records a SysEx message from its body (without F0/F7)
+pub fn log_sysex(port: MidiLogPort, body: &[u8], thru: bool) {
+    let mut bytes = [SYSEX_START, 0, 0];
+    let shown = body.len().min(2);
+    bytes[1..1 + shown].copy_from_slice(&body[..shown]);
+    if shown < 2 {
+        bytes[1 + shown] = SYSEX_EOX;
+    }
+    log_bytes(port, &bytes, body.len() + 2, thru);
+}
+
+/// Records an outgoing event on each output in `target`
+pub fn log_event_out(target: MidiOut, event: &LiveEvent, thru: bool) {
+    for (i, enabled) in target.0.iter().enumerate() {
+        if *enabled {
+            log_event(MidiLogPort::output(i), event, thru);
+        }
+    }
+}
+
+/// Sets what gets recorded from now on and clears the log
+pub fn set_midi_log_filter(filter: MidiLogFilter) {
+    MIDI_LOG.lock(|log| {
+        let mut log = log.borrow_mut();
+        log.filter = filter;
+        log.entries.clear();
+    });
+}
+
+/// Number of held entries from `since` on
+pub fn midi_log_count(since: u32) -> usize {
+    MIDI_LOG.lock(|log| {
+        let log = log.borrow();
+        log.entries
+            .iter()
+            .filter(|e| e.seq.wrapping_sub(since) < u32::MAX / 2)
+            .count()
+    })
+}
+
+/// Oldest held entry from `since` on
+pub fn midi_log_entry(since: u32) -> Option<MidiLogEntry> {
+    MIDI_LOG.lock(|log| {
+        let log = log.borrow();
+        log.entries
+            .iter()
+            .find(|e| e.seq.wrapping_sub(since) < u32::MAX / 2)
+            .copied()
+    })
+}
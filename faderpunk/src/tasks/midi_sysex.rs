@@ -0,0 +1,122 @@
+//! Bounded buffers for performance SysEx (cable 0 and DIN).
+//!
+//! SysEx bodies are too large to travel through the `Copy` MIDI channels, so
+//! they live in small static pools and the channels carry slot handles.
+//! Outgoing messages claim a slot until the output router has handed them to
+//! every port; incoming messages go into a ring that apps copy out of. When
+//! a pool is exhausted the message is dropped.
+
+use core::cell::RefCell;
+
+use embassy_sync::blocking_mutex::{raw::CriticalSectionRawMutex, Mutex};
+use heapless::Vec;
+
+/// Max SysEx body length (without F0/F7). Longer messages are dropped whole.
+pub const SYSEX_MAX_LEN: usize = 256;
+const SYSEX_OUT_SLOTS: usize = 4;
+const SYSEX_IN_SLOTS: usize = 4;
+
+pub type SysExData = Vec<u8, SYSEX_MAX_LEN>;
+
+struct OutSlot {
+    in_use: bool,
+    data: SysExData,
+}
+
+static SYSEX_OUT_POOL: Mutex<CriticalSectionRawMutex, RefCell<[OutSlot; SYSEX_OUT_SLOTS]>> =
+    Mutex::new(RefCell::new(
+        [const {
+            OutSlot {
+                in_use: false,
+                data: Vec::new(),
+            }
+        }; SYSEX_OUT_SLOTS],
+    ));
+
+/// Copy `body` into a free outgoing slot. `None` if the body is too long,
+/// not 7-bit clean or all slots are taken.
+pub fn claim_out(body: &[u8]) -> Option<u8> {
+    if body.iter().any(|b| b & 0x80 != 0) {
+        return None;
+    }
+    SYSEX_OUT_POOL.lock(|pool| {
+        let mut pool = pool.borrow_mut();
+        let (idx, slot) = pool.iter_mut().enumerate().find(|(_, s)| !s.in_use)?;
+        slot.data.clear();
+        slot.data.extend_from_slice(body).ok()?;
+        slot.in_use = true;
+        Some(idx as u8)
+    })
+}
+
+/// Run `f` on the body held by an outgoing slot.
+pub fn with_out<R>(slot: u8, f: impl FnOnce(&[u8]) -> R) -> R {
+    SYSEX_OUT_POOL.lock(|pool| f(&pool.borrow()[slot as usize].data))
+}
+
+pub fn release_out(slot: u8) {
+    SYSEX_OUT_POOL.lock(|pool| pool.borrow_mut()[slot as usize].in_use = false);
+}
+
+struct InSlot {
+    seq: u16,
+    data: SysExData,
+}
+
+struct InRing {
+    slots: [InSlot; SYSEX_IN_SLOTS],
+    next: usize,
+    seq: u16,
+}
+
+static SYSEX_IN_RING: Mutex<CriticalSectionRawMutex, RefCell<InRing>> =
+    Mutex::new(RefCell::new(InRing {
+        slots: [const {
+            InSlot {
+                seq: 0,
+                data: Vec::new(),
+            }
+        }; SYSEX_IN_SLOTS],
+        next: 0,
+        seq: 0,
+    }));
+
+/// Handle to a received SysEx body. Cheap to pass around; the body is only
+/// copied out on [`SysExIn::read`].
+#[derive(Clone, Copy)]
+pub struct SysExIn {
+    slot: u8,
+    seq: u16,
+}
+
+impl SysExIn {
+    /// Copy the body out of the ring. `None` if it has since been
+    /// overwritten by newer messages.
+    #[allow(dead_code)]
+    pub fn read(self) -> Option<SysExData> {
+        SYSEX_IN_RING.lock(|ring| {
+            let ring = ring.borrow();
+            let slot = &ring.slots[self.slot as usize];
+            (slot.seq == self.seq).then(|| slot.data.clone())
+        })
+    }
+}
+
+/// Store a received body, overwriting the oldest one.
+pub fn store_in(body: &[u8]) -> Option<SysExIn> {
+    SYSEX_IN_RING.lock(|ring| {
+        let mut ring = ring.borrow_mut();
+        let idx = ring.next;
+        ring.next = (idx + 1) % SYSEX_IN_SLOTS;
+        ring.seq = ring.seq.wrapping_add(1);
+        let seq = ring.seq;
+        let slot = &mut ring.slots[idx];
+        slot.seq = seq;
+        slot.data.clear();
+        slot.data.extend_from_slice(body).ok()?;
+        Some(SysExIn {
+            slot: idx as u8,
+            seq,
+        })
+    })
+}
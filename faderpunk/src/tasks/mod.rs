@@ -9,5 +9,10 @@ pub mod input_handlers;
 pub mod leds;
 pub mod max;
 pub mod midi;
+pub mod midi_clock_out;
+pub mod midi_din;
+pub mod midi_log;
+pub mod midi_sysex;
+pub mod mtc;
 pub mod transport;
 pub mod voct_freq;
@@ -0,0 +1,153 @@
+//! MIDI Timecode generation. Follows the transport position reported by the
+//! clock gatekeeper and sends quarter frames (plus a Full Frame on every
+//! locate) to the outputs with `send_mtc` set.
+//!
+//! Only runs on the internal clock: the position in time is derived from the
+//! 24ppqn tick position at the internal BPM, which an external clock doesn't
+//! pin down. BPM changes while running don't move the timecode, it keeps
+//! running in real time from the last locate.
+
+use embassy_futures::select::{select3, Either3};
+use embassy_sync::{
+    blocking_mutex::raw::CriticalSectionRawMutex, channel::TrySendError, signal::Signal,
+};
+use embassy_time::{Duration, Instant, Timer};
+use midly::{
+    live::{LiveEvent, MtcQuarterFrameMessage, SystemCommon},
+    num::u4,
+};
+
+use libfp::{
+    mtc::{full_frame, quarter_frame_at, Timecode},
+    utils::bpm_to_clock_duration,
+    ClockSrc, GlobalConfig, MidiOut,
+};
+
+use crate::tasks::{
+    global_config::GLOBAL_CONFIG_WATCH,
+    midi::{MidiEventSource, MidiMsg, MIDI_CHANNEL},
+    midi_sysex::claim_out,
+};
+
+/// Transport position handed over by the gatekeeper
+#[derive(Clone, Copy)]
+struct MtcLocate {
+    /// 24ppqn tick the transport is at
+    tick: u64,
+    running: bool,
+    at: Instant,
+}
+
+static MTC_LOCATE: Signal<CriticalSectionRawMutex, MtcLocate> = Signal::new();
+
+/// Report a transport change: the next tick is `tick`.
+pub fn mtc_locate(tick: u64, running: bool) {
+    MTC_LOCATE.signal(MtcLocate {
+        tick,
+        running,
+        at: Instant::now(),
+    });
+}
+
+fn mtc_targets(config: &GlobalConfig) -> Option<MidiOut> {
+    let targets = config.midi.outs.map(|o| o.send_mtc);
+    (config.clock.clock_src == ClockSrc::Internal && targets.iter().any(|t| *t))
+        .then_some(MidiOut(targets))
+}
+
+fn quarter_frame_kind(piece: u8) -> MtcQuarterFrameMessage {
+    match piece {
+        0 => MtcQuarterFrameMessage::FramesLow,
+        1 => MtcQuarterFrameMessage::FramesHigh,
+        2 => MtcQuarterFrameMessage::SecondsLow,
+        3 => MtcQuarterFrameMessage::SecondsHigh,
+        4 => MtcQuarterFrameMessage::MinutesLow,
+        5 => MtcQuarterFrameMessage::MinutesHigh,
+        6 => MtcQuarterFrameMessage::HoursLow,
+        _ => MtcQuarterFrameMessage::HoursHigh,
+    }
+}
+
+fn send_full_frame(target: MidiOut, frames: u64, config: &GlobalConfig) {
+    let fps = config.midi.mtc_fps;
+    let Some(slot) = claim_out(&full_frame(Timecode::from_frames(frames, fps), fps)) else {
+        return;
+    };
+    let msg = MidiMsg::SysEx {
+        slot,
+        target,
+        source: MidiEventSource::Sync,
+    };
+    if let Err(TrySendError::Full(msg)) = MIDI_CHANNEL.try_send(msg) {
+        msg.discard();
+    }
+}
+
+#[embassy_executor::task]
+pub async fn run_mtc_generator() {
+    let mut config_receiver = GLOBAL_CONFIG_WATCH.receiver().unwrap();
+
+    let mut config = config_receiver.get().await;
+    let mut running = false;
+    // Timecode position `anchor_us` was reached at `anchor_at`
+    let mut anchor_at = Instant::now();
+    let mut anchor_us: u64 = 0;
+    let mut next_qf: u64 = 0;
+
+    loop {
+        let targets = mtc_targets(&config);
+        let fps = config.midi.mtc_fps;
+        let next_at =
+            anchor_at + Duration::from_micros(fps.quarter_frames_to_micros(next_qf) - anchor_us);
+        let timer_fut = async {
+            if running && targets.is_some() {
+                Timer::at(next_at).await;
+            } else {
+                core::future::pending::<()>().await;
+            }
+        };
+
+        match select3(MTC_LOCATE.wait(), config_receiver.changed(), timer_fut).await {
+            Either3::First(locate) => {
+                let tick_us = bpm_to_clock_duration(config.clock.internal_bpm, 24).as_micros();
+                anchor_at = locate.at;
+                anchor_us = locate.tick * tick_us;
+                running = locate.running;
+                next_qf = fps.micros_to_quarter_frames(anchor_us);
+                if fps.quarter_frames_to_micros(next_qf) < anchor_us {
+                    next_qf += 1;
+                }
+                if let Some(target) = targets {
+                    send_full_frame(target, next_qf / 4, &config);
+                }
+            }
+            Either3::Second(new_config) => {
+                if new_config.midi.mtc_fps != fps {
+                    // Re-anchor at the current position on the new frame grid
+                    let now = Instant::now();
+                    if running {
+                        anchor_us += now.saturating_duration_since(anchor_at).as_micros();
+                        anchor_at = now;
+                    }
+                    let new_fps = new_config.midi.mtc_fps;
+                    next_qf = new_fps.micros_to_quarter_frames(anchor_us) + 1;
+                }
+                config = new_config;
+            }
+            Either3::Third(_) => {
+                let Some(target) = targets else {
+                    continue;
+                };
+                let (piece, nibble) = quarter_frame_at(next_qf, fps);
+                next_qf += 1;
+                let event = LiveEvent::Common(SystemCommon::MidiTimeCodeQuarterFrame(
+                    quarter_frame_kind(piece),
+                    u4::new(nibble),
+                ));
+                // Lossy like MIDI clock: a stalled output must not hold up
+                // the timecode schedule
+                let _ = MIDI_CHANNEL.try_send(MidiMsg::new(event, target, MidiEventSource::Sync));
+            }
+        }
+    }
+}
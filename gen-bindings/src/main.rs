@@ -20,7 +20,10 @@ fn main() {
         javascript::GenerationSettings::enable_all(),
         generate_bindings!(
             libfp::AppIcon,
+            libfp::AppScale,
             libfp::AuxJackMode,
+            libfp::types::CalibFile,
+            libfp::CalibrationCheckPoint,
             libfp::ClockConfig,
             libfp::ClockDivision,
             libfp::ClockSrc,
@@ -28,27 +31,48 @@ fn main() {
             libfp::ConfigMsgIn,
             libfp::ConfigMsgOut,
             libfp::Curve,
+            libfp::CustomCurve,
+            libfp::CustomScale,
             libfp::CustomVoOctCurve,
+            libfp::types::FaderCalibration,
+            libfp::FaderConfig,
             libfp::GlobalConfig,
+            libfp::I2cDeviceType,
+            libfp::I2cMapping,
             libfp::I2cMode,
+            libfp::I2cScaling,
             libfp::Key,
             libfp::Layout,
+            libfp::LedConfig,
+            libfp::LedPalette,
+            libfp::LedTheme,
+            libfp::types::MaxCalibration,
             libfp::MidiCc,
             libfp::MidiChannel,
             libfp::MidiConfig,
             libfp::MidiIn,
+            libfp::MidiLogEntry,
+            libfp::MidiLogFilter,
+            libfp::MidiLogPort,
             libfp::MidiMode,
             libfp::MidiNote,
             libfp::MidiOut,
             libfp::MidiOutConfig,
             libfp::MidiOutMode,
+            libfp::MidiOutStats,
+            libfp::MtcChaseSrc,
+            libfp::MtcFps,
             libfp::Note,
             libfp::Param,
+            libfp::types::PiecewiseCalibration,
+            libfp::types::PiecewiseFile,
             libfp::QuantizerConfig,
             libfp::Range,
             libfp::ResetSrc,
             libfp::TakeoverMode,
+            libfp::Tuning,
             libfp::Value,
+            libfp::VoOctTrim,
             libfp::VoltPerOct,
             libfp::Waveform
         ),
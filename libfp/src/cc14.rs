@@ -0,0 +1,97 @@
+//! Pairing of 14-bit MIDI CCs: CC 0-31 carry the MSB, CC 32-63 the matching
+//! LSB.
+
+use midly::num::u7;
+
+/// Outside the 7-bit data range, so it can't collide with a received MSB
+const NO_MSB: u8 = 0x80;
+
+/// Pairs the 14-bit CCs of one MIDI channel. A new MSB resets the LSB to 0, as
+/// the MIDI spec asks. The MSB is kept after a pair completes so senders that
+/// only refresh the LSB still resolve.
+#[derive(Clone, Copy)]
+pub struct Cc14Tracker {
+    /// Last MSB per controller, `NO_MSB` until one has been seen
+    msb: [u8; 32],
+}
+
+impl Default for Cc14Tracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Cc14Tracker {
+    pub const fn new() -> Self {
+        Self { msb: [NO_MSB; 32] }
+    }
+
+    /// Process a CC message. Returns the controller (0-31) and the 14-bit
+    /// value whenever a CC changes one. The CC itself is never consumed.
+    pub fn process_cc(&mut self, controller: u7, value: u7) -> Option<(u7, u16)> {
+        let cc = controller.as_int() as usize;
+        match cc {
+            0..=31 => {
+                self.msb[cc] = value.as_int();
+                Some((controller, (value.as_int() as u16) << 7))
+            }
+            32..=63 if self.msb[cc - 32] != NO_MSB => Some((
+                u7::new((cc - 32) as u8),
+                ((self.msb[cc - 32] as u16) << 7) | (value.as_int() as u16),
+            )),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cc(tracker: &mut Cc14Tracker, controller: u8, value: u8) -> Option<(u8, u16)> {
+        tracker
+            .process_cc(u7::new(controller), u7::new(value))
+            .map(|(controller, value)| (controller.as_int(), value))
+    }
+
+    #[test]
+    fn msb_then_lsb_gives_the_full_value() {
+        let mut tracker = Cc14Tracker::new();
+        assert_eq!(cc(&mut tracker, 7, 0x40), Some((7, 0x40 << 7)));
+        assert_eq!(cc(&mut tracker, 39, 0x15), Some((7, (0x40 << 7) | 0x15)));
+        // Senders that only refresh the LSB keep the MSB
+        assert_eq!(cc(&mut tracker, 39, 0x7F), Some((7, (0x40 << 7) | 0x7F)));
+    }
+
+    #[test]
+    fn lsb_without_msb_is_ignored() {
+        let mut tracker = Cc14Tracker::new();
+        assert_eq!(cc(&mut tracker, 39, 0x15), None);
+        // An MSB on another controller doesn't complete it either
+        cc(&mut tracker, 8, 0x40);
+        assert_eq!(cc(&mut tracker, 39, 0x15), None);
+    }
+
+    #[test]
+    fn msb_alone_resolves_with_a_zero_lsb() {
+        let mut tracker = Cc14Tracker::new();
+        assert_eq!(cc(&mut tracker, 0, 0x7F), Some((0, 0x7F << 7)));
+        assert_eq!(cc(&mut tracker, 31, 1), Some((31, 1 << 7)));
+    }
+
+    #[test]
+    fn new_msb_resets_a_stale_lsb() {
+        let mut tracker = Cc14Tracker::new();
+        cc(&mut tracker, 1, 0x10);
+        assert_eq!(cc(&mut tracker, 33, 0x7F), Some((1, (0x10 << 7) | 0x7F)));
+        assert_eq!(cc(&mut tracker, 1, 0x20), Some((1, 0x20 << 7)));
+        assert_eq!(cc(&mut tracker, 33, 0x01), Some((1, (0x20 << 7) | 0x01)));
+    }
+
+    #[test]
+    fn ccs_above_63_are_not_paired() {
+        let mut tracker = Cc14Tracker::new();
+        assert_eq!(cc(&mut tracker, 64, 0x7F), None);
+        assert_eq!(cc(&mut tracker, 127, 0x7F), None);
+    }
+}
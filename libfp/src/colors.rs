@@ -78,3 +78,56 @@ pub const LIGHT_BLUE: RGB8 = RGB8 {
     g: 129,
     b: 255,
 };
+
+/// A fully saturated color from the color wheel. 0 is red, going through
+/// green at 85 and blue at 170 back to red.
+pub fn hue(hue: u8) -> RGB8 {
+    let region = hue / 43;
+    let up = (hue - region * 43) * 6;
+    let down = 255 - up;
+    match region {
+        0 => RGB8::new(255, up, 0),
+        1 => RGB8::new(down, 255, 0),
+        2 => RGB8::new(0, 255, up),
+        3 => RGB8::new(0, down, 255),
+        4 => RGB8::new(up, 0, 255),
+        _ => RGB8::new(255, 0, down),
+    }
+}
+
+/// Blends `from` into `to`, 0 is all `from` and 255 is all `to`
+pub fn mix(from: RGB8, to: RGB8, amount: u8) -> RGB8 {
+    let blend = |a: u8, b: u8| (a as i32 + (b as i32 - a as i32) * amount as i32 / 255) as u8;
+    RGB8::new(
+        blend(from.r, to.r),
+        blend(from.g, to.g),
+        blend(from.b, to.b),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hue_goes_around_the_wheel() {
+        assert_eq!(hue(0), RED);
+        assert_eq!(hue(43), RGB8::new(255, 255, 0));
+        assert_eq!(hue(86), RGB8::new(0, 255, 0));
+        assert_eq!(hue(172), RGB8::new(0, 0, 255));
+        assert_eq!(hue(255), RGB8::new(255, 0, 15));
+        // Every step stays fully saturated
+        for h in 0..=255 {
+            let c = hue(h);
+            assert_eq!(c.r.max(c.g).max(c.b), 255, "{h}");
+            assert_eq!(c.r.min(c.g).min(c.b), 0, "{h}");
+        }
+    }
+
+    #[test]
+    fn mix_hits_both_ends() {
+        assert_eq!(mix(RED, BLUE, 0), RED);
+        assert_eq!(mix(RED, BLUE, 255), BLUE);
+        assert_eq!(mix(RGB8::default(), WHITE, 128), RGB8::new(128, 128, 128));
+    }
+}
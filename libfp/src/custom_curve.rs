@@ -0,0 +1,261 @@
+//! User-drawn fader response curves, selectable as `Curve::Custom(idx)`.
+//!
+//! A curve is a run of breakpoints spread evenly over the fader travel, drawn
+//! in the configurator. `Curve::at` interpolates linearly between them. The
+//! firmware loads the stored curves into a lock-free table with
+//! `set_custom_curve`, so every app offering a `Param::Curve` can use them
+//! without having them passed in.
+
+use core::hint::spin_loop;
+use core::sync::atomic::{fence, AtomicU16, AtomicU8, Ordering};
+
+use minicbor::{Decode, Encode};
+use postcard_bindgen::PostcardBindings;
+use serde::{Deserialize, Serialize};
+
+/// Number of user-drawn curves
+pub const CUSTOM_CURVES: usize = 4;
+
+/// Most breakpoints a curve can have
+pub const MAX_CURVE_POINTS: usize = 32;
+
+/// A straight line needs both ends
+pub const MIN_CURVE_POINTS: usize = 2;
+
+const CURVE_MAX: u16 = 4095;
+
+/// Stored in FRAM as CBOR next to the app storage, one slot per curve. Tags
+/// are append-only like `GlobalConfig`.
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, PostcardBindings, Encode, Decode,
+)]
+pub struct CustomCurve {
+    /// Output at each breakpoint, 0-4095, from the bottom of the fader to
+    /// the top. Only the first `len` are used.
+    #[n(0)]
+    #[cbor(default)]
+    pub points: [u16; MAX_CURVE_POINTS],
+    /// Number of breakpoints
+    #[n(1)]
+    #[cbor(default)]
+    pub len: u8,
+}
+
+impl Default for CustomCurve {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CustomCurve {
+    /// A straight line, the same as `Curve::Linear`
+    pub const fn new() -> Self {
+        let mut points = [0; MAX_CURVE_POINTS];
+        points[1] = CURVE_MAX;
+        Self {
+            points,
+            len: MIN_CURVE_POINTS as u8,
+        }
+    }
+
+    /// Checks data from the configurator or FRAM before it's used
+    pub fn is_valid(&self) -> bool {
+        (MIN_CURVE_POINTS..=MAX_CURVE_POINTS).contains(&(self.len as usize))
+            && self.points().iter().all(|&p| p <= CURVE_MAX)
+    }
+
+    /// The breakpoints in use
+    pub fn points(&self) -> &[u16] {
+        &self.points[..(self.len as usize).min(MAX_CURVE_POINTS)]
+    }
+
+    pub fn at(&self, value: u16) -> u16 {
+        interpolate(self.len as usize, |i| self.points[i], value)
+    }
+}
+
+/// Linear interpolation between `len` breakpoints spread evenly over 0-4095.
+/// The first and last breakpoint are hit exactly at the ends of the travel.
+fn interpolate(len: usize, point: impl Fn(usize) -> u16, value: u16) -> u16 {
+    if !(MIN_CURVE_POINTS..=MAX_CURVE_POINTS).contains(&len) {
+        return value.min(CURVE_MAX);
+    }
+    let segments = (len - 1) as u32;
+    let pos = value.min(CURVE_MAX) as u32 * segments;
+    let idx = (pos / CURVE_MAX as u32) as usize;
+    if idx >= len - 1 {
+        return point(len - 1).min(CURVE_MAX);
+    }
+    let frac = (pos % CURVE_MAX as u32) as i32;
+    let from = point(idx).min(CURVE_MAX) as i32;
+    let to = point(idx + 1).min(CURVE_MAX) as i32;
+    (from + (to - from) * frac / CURVE_MAX as i32) as u16
+}
+
+static CURVE_POINTS: [[AtomicU16; MAX_CURVE_POINTS]; CUSTOM_CURVES] =
+    [const { [const { AtomicU16::new(0) }; MAX_CURVE_POINTS] }; CUSTOM_CURVES];
+/// 0 until a curve is set, which `interpolate` treats as a straight line
+static CURVE_LENS: [AtomicU8; CUSTOM_CURVES] = [const { AtomicU8::new(0) }; CUSTOM_CURVES];
+/// Odd while `set_custom_curve` changes a curve, bumped twice per change
+static CURVE_SEQS: [AtomicU8; CUSTOM_CURVES] = [const { AtomicU8::new(0) }; CUSTOM_CURVES];
+
+/// Makes `curve` the one `Curve::Custom(idx)` follows. Invalid curves and
+/// indices are ignored.
+pub fn set_custom_curve(idx: usize, curve: &CustomCurve) {
+    if idx >= CUSTOM_CURVES || !curve.is_valid() {
+        return;
+    }
+    // Readers that overlap the change see the sequence move and read again,
+    // see `read_curve`
+    let seq = CURVE_SEQS[idx].load(Ordering::Relaxed);
+    CURVE_SEQS[idx].store(seq.wrapping_add(1), Ordering::Relaxed);
+    fence(Ordering::Release);
+    for (slot, &p) in CURVE_POINTS[idx].iter().zip(curve.points()) {
+        slot.store(p, Ordering::Relaxed);
+    }
+    CURVE_LENS[idx].store(curve.len, Ordering::Relaxed);
+    CURVE_SEQS[idx].store(seq.wrapping_add(2), Ordering::Release);
+}
+
+/// Runs `read` on curve `idx` until no `set_custom_curve` overlapped it, so
+/// it never sees a mix of old and new points. The firmware only sets curves
+/// from one task, which never awaits mid-change, so a reader on the other
+/// core spins for a few stores at most.
+fn read_curve<T>(idx: usize, read: impl Fn() -> T) -> T {
+    loop {
+        let seq = CURVE_SEQS[idx].load(Ordering::Acquire);
+        if seq.is_multiple_of(2) {
+            let value = read();
+            fence(Ordering::Acquire);
+            if CURVE_SEQS[idx].load(Ordering::Relaxed) == seq {
+                return value;
+            }
+        }
+        spin_loop();
+    }
+}
+
+/// The curve `Curve::Custom(idx)` currently follows
+pub fn custom_curve(idx: usize) -> CustomCurve {
+    if idx >= CUSTOM_CURVES {
+        return CustomCurve::new();
+    }
+    read_curve(idx, || {
+        let mut curve = CustomCurve::new();
+        let len = CURVE_LENS[idx].load(Ordering::Relaxed) as usize;
+        if len >= MIN_CURVE_POINTS {
+            for (p, slot) in curve.points.iter_mut().zip(&CURVE_POINTS[idx][..len]) {
+                *p = slot.load(Ordering::Relaxed);
+            }
+            curve.len = len as u8;
+        }
+        curve
+    })
+}
+
+pub(crate) fn custom_curve_at(idx: usize, value: u16) -> u16 {
+    if idx >= CUSTOM_CURVES {
+        return value.min(CURVE_MAX);
+    }
+    read_curve(idx, || {
+        let len = CURVE_LENS[idx].load(Ordering::Relaxed) as usize;
+        interpolate(len, |i| CURVE_POINTS[idx][i].load(Ordering::Relaxed), value)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Curve;
+
+    fn curve(points: &[u16]) -> CustomCurve {
+        let mut curve = CustomCurve::new();
+        curve.points[..points.len()].copy_from_slice(points);
+        curve.len = points.len() as u8;
+        curve
+    }
+
+    #[test]
+    fn endpoints_are_hit_exactly() {
+        for len in MIN_CURVE_POINTS..=MAX_CURVE_POINTS {
+            let mut points = [0; MAX_CURVE_POINTS];
+            for (i, p) in points.iter_mut().take(len).enumerate() {
+                *p = 100 + (i as u16 * 37) % 3900;
+            }
+            let c = curve(&points[..len]);
+            assert_eq!(c.at(0), points[0], "{len} points");
+            assert_eq!(c.at(4095), points[len - 1], "{len} points");
+            assert_eq!(c.at(u16::MAX), points[len - 1], "{len} points");
+        }
+        assert_eq!(CustomCurve::new().at(0), 0);
+        assert_eq!(CustomCurve::new().at(4095), 4095);
+    }
+
+    #[test]
+    fn straight_line_matches_linear() {
+        let linear = CustomCurve::new();
+        let sixteen = curve(&core::array::from_fn::<u16, 16, _>(|i| (i * 273) as u16));
+        for v in 0..=4095 {
+            assert_eq!(linear.at(v), v);
+            assert!(sixteen.at(v).abs_diff(v) <= 1, "{v}");
+        }
+    }
+
+    #[test]
+    fn monotonic_breakpoints_give_a_monotonic_curve() {
+        // Steep at the bottom, flat in the middle, steep again at the top
+        let rising = curve(&[
+            0, 900, 1500, 1800, 1950, 2000, 2040, 2048, 2056, 2100, 2200, 2400, 2800, 3300, 3800,
+            4095,
+        ]);
+        let falling = curve(&core::array::from_fn::<u16, 32, _>(|i| {
+            4095 - ((i * i * 4095) / (31 * 31)) as u16
+        }));
+        let mut prev = (rising.at(0), falling.at(0));
+        for v in 1..=4095 {
+            let next = (rising.at(v), falling.at(v));
+            assert!(next.0 >= prev.0, "rising at {v}");
+            assert!(next.1 <= prev.1, "falling at {v}");
+            prev = next;
+        }
+    }
+
+    #[test]
+    fn breakpoints_are_spread_evenly() {
+        let c = curve(&[0, 4095, 0]);
+        assert_eq!(c.at(2047), 4094);
+        assert_eq!(c.at(2048), 4094);
+        assert_eq!(c.at(1024), 2048);
+        assert_eq!(c.at(3071), 2048);
+    }
+
+    #[test]
+    fn invalid_curves_are_rejected() {
+        assert!(CustomCurve::new().is_valid());
+        assert!(!curve(&[0]).is_valid());
+        assert!(!curve(&[0, 4096]).is_valid());
+        let mut c = CustomCurve::new();
+        c.len = MAX_CURVE_POINTS as u8 + 1;
+        assert!(!c.is_valid());
+    }
+
+    #[test]
+    fn custom_curves_follow_the_table() {
+        // Only this test touches the table
+        let idx = CUSTOM_CURVES - 1;
+        assert_eq!(Curve::Custom(idx as u8).at(1234), 1234);
+
+        let inverted = curve(&[4095, 0]);
+        set_custom_curve(idx, &inverted);
+        assert_eq!(custom_curve(idx), inverted);
+        assert_eq!(Curve::Custom(idx as u8).at(0), 4095);
+        assert_eq!(Curve::Custom(idx as u8).at(4095), 0);
+        assert_eq!(Curve::Custom(idx as u8).at(1234), inverted.at(1234));
+
+        // Invalid curves and indices leave it alone
+        set_custom_curve(idx, &curve(&[0]));
+        set_custom_curve(CUSTOM_CURVES, &CustomCurve::new());
+        assert_eq!(custom_curve(idx), inverted);
+        assert_eq!(Curve::Custom(CUSTOM_CURVES as u8).at(1234), 1234);
+    }
+}
@@ -26,6 +26,7 @@ impl FromValue for bool {
         match value {
             Value::bool(i) => i,
             Value::MidiNrpn(b) => b,
+            Value::MidiCc14(b) => b,
             _ => Self::default(),
         }
     }
@@ -0,0 +1,169 @@
+//! I2C follower protocol for Teletype, crow and other leaders, used in
+//! `I2cMode::Follower`. See `docs/i2c-follower.md` for the command table.
+//!
+//! The device answers at the 16n faderbank address so the Teletype `FADER`
+//! and `FB` ops work unchanged: a single byte write selects a fader, the
+//! following two byte read returns its position. Extended commands are a
+//! command byte followed by big-endian 16-bit arguments, which is what the
+//! generic Teletype `IIS1`/`IIS2`/`IIQS1` ops and crow's `ii.raw` send.
+
+/// 16n faderbank address, which the Teletype `FADER`/`FB` ops talk to
+pub const I2C_ADDRESS_FOLLOWER: u16 = 0x34;
+
+/// Full scale of Teletype values: 16384 is 10V
+pub const TT_FULL_SCALE: u16 = 16384;
+
+/// Select fader n for the next read (arg: fader)
+pub const CMD_FADER: u8 = 0x10;
+/// Select button n for the next read (arg: button)
+pub const CMD_BUTTON: u8 = 0x11;
+/// Select the internal BPM for the next read
+pub const CMD_BPM_GET: u8 = 0x12;
+/// Set jack n to a voltage, 0-16383 for 0-10V (args: jack, value)
+pub const CMD_CV: u8 = 0x20;
+/// Set jack n as a gate, high if the value is not 0 (args: jack, state)
+pub const CMD_TR: u8 = 0x21;
+/// Fire a trigger on jack n (arg: jack)
+pub const CMD_TR_PULSE: u8 = 0x22;
+/// Set the trigger length in ms (arg: ms)
+pub const CMD_TR_TIME: u8 = 0x23;
+/// Set the internal BPM (arg: bpm)
+pub const CMD_BPM: u8 = 0x30;
+/// Load scene n (arg: scene)
+pub const CMD_SCENE: u8 = 0x31;
+/// Start (1) or stop (0) the internal clock (arg: state)
+pub const CMD_TRANSPORT: u8 = 0x32;
+
+/// Value a read returns
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Query {
+    /// Fader position, 0-16383
+    Fader(u8),
+    /// 1 if held, 0 if not
+    Button(u8),
+    /// Internal BPM, rounded
+    Bpm,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Command {
+    Select(Query),
+    SetCv { jack: u8, value: u16 },
+    SetGate { jack: u8, high: bool },
+    Trigger { jack: u8 },
+    SetTriggerTime(u16),
+    SetBpm(u16),
+    LoadScene(u8),
+    Transport(bool),
+}
+
+fn arg(bytes: &[u8], i: usize) -> Option<u16> {
+    let at = 1 + 2 * i;
+    Some(u16::from_be_bytes([*bytes.get(at)?, *bytes.get(at + 1)?]))
+}
+
+fn arg_u8(bytes: &[u8], i: usize) -> Option<u8> {
+    u8::try_from(arg(bytes, i)?).ok()
+}
+
+/// Parses one write from the leader
+pub fn parse_command(bytes: &[u8]) -> Option<Command> {
+    match *bytes.first()? {
+        // 16n: single byte fader index
+        n @ 0x00..=0x0F if bytes.len() == 1 => Some(Command::Select(Query::Fader(n))),
+        CMD_FADER => Some(Command::Select(Query::Fader(arg_u8(bytes, 0)?))),
+        CMD_BUTTON => Some(Command::Select(Query::Button(arg_u8(bytes, 0)?))),
+        CMD_BPM_GET => Some(Command::Select(Query::Bpm)),
+        CMD_CV => Some(Command::SetCv {
+            jack: arg_u8(bytes, 0)?,
+            value: arg(bytes, 1)?,
+        }),
+        CMD_TR => Some(Command::SetGate {
+            jack: arg_u8(bytes, 0)?,
+            high: arg(bytes, 1)? != 0,
+        }),
+        CMD_TR_PULSE => Some(Command::Trigger {
+            jack: arg_u8(bytes, 0)?,
+        }),
+        CMD_TR_TIME => Some(Command::SetTriggerTime(arg(bytes, 0)?)),
+        CMD_BPM => Some(Command::SetBpm(arg(bytes, 0)?)),
+        CMD_SCENE => Some(Command::LoadScene(arg_u8(bytes, 0)?)),
+        CMD_TRANSPORT => Some(Command::Transport(arg(bytes, 0)? != 0)),
+        _ => None,
+    }
+}
+
+/// 12-bit fader value in Teletype units (0-16383), as the 16n reports it
+pub fn fader_to_tt(value: u16) -> u16 {
+    (value.min(4095) << 2) | (value.min(4095) >> 10)
+}
+
+/// Teletype value (0-16383 for 0-10V) to 12-bit DAC counts
+pub fn tt_to_dac(value: u16) -> u16 {
+    (value.min(TT_FULL_SCALE - 1) >> 2).min(4095)
+}
+
+/// Bytes a read returns for `value`
+pub fn response(value: u16) -> [u8; 2] {
+    value.to_be_bytes()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn single_byte_selects_fader_like_16n() {
+        assert_eq!(parse_command(&[3]), Some(Command::Select(Query::Fader(3))));
+        assert_eq!(
+            parse_command(&[0x0F]),
+            Some(Command::Select(Query::Fader(15)))
+        );
+        // Only the bare byte is a 16n select
+        assert_eq!(parse_command(&[3, 0]), None);
+    }
+
+    #[test]
+    fn extended_commands_take_big_endian_args() {
+        assert_eq!(
+            parse_command(&[CMD_CV, 0, 5, 0x20, 0x00]),
+            Some(Command::SetCv {
+                jack: 5,
+                value: 8192
+            })
+        );
+        assert_eq!(
+            parse_command(&[CMD_TR, 0, 2, 0, 1]),
+            Some(Command::SetGate {
+                jack: 2,
+                high: true
+            })
+        );
+        assert_eq!(
+            parse_command(&[CMD_BPM, 0, 120]),
+            Some(Command::SetBpm(120))
+        );
+        assert_eq!(
+            parse_command(&[CMD_BPM_GET]),
+            Some(Command::Select(Query::Bpm))
+        );
+    }
+
+    #[test]
+    fn truncated_or_unknown_commands_are_rejected() {
+        assert_eq!(parse_command(&[]), None);
+        assert_eq!(parse_command(&[CMD_CV, 0, 5, 0x20]), None);
+        assert_eq!(parse_command(&[CMD_SCENE, 1, 0]), None);
+        assert_eq!(parse_command(&[0x7E, 0, 0]), None);
+    }
+
+    #[test]
+    fn value_conversions_cover_full_scale() {
+        assert_eq!(fader_to_tt(0), 0);
+        assert_eq!(fader_to_tt(4095), 16383);
+        assert_eq!(tt_to_dac(0), 0);
+        assert_eq!(tt_to_dac(16383), 4095);
+        assert_eq!(tt_to_dac(u16::MAX), 4095);
+        assert_eq!(response(16383), [0x3F, 0xFF]);
+    }
+}
@@ -0,0 +1,264 @@
+//! Encoding for II followers driven in `I2cMode::Leader` that the `mii` crate
+//! doesn't cover: W/ synth and tape, Disting EX and crow.
+//!
+//! All of them take a command byte followed by big-endian arguments, with
+//! voltages as signed 16-bit values where 16384 is 10V (the Teletype `V` and
+//! `N` scale).
+
+use crate::{I2cDeviceType, I2cScaling, MidiNote, Range};
+
+pub const ANSIBLE_ADDRESS: u8 = 0x20;
+/// First ER-301, up to three at the following addresses
+pub const ER301_ADDRESS: u8 = 0x31;
+/// First TXo, up to eight at the following addresses
+pub const TXO_ADDRESS: u8 = 0x60;
+pub const JUST_FRIENDS_ADDRESS: u8 = 0x70;
+/// W/ in synth mode
+pub const W_SYNTH_ADDRESS: u8 = 0x76;
+/// W/ in tape mode
+pub const W_TAPE_ADDRESS: u8 = 0x71;
+/// First Disting EX, up to four at the following addresses
+pub const DISTING_EX_ADDRESS: u8 = 0x41;
+pub const CROW_ADDRESS: u8 = 0x01;
+
+/// Longest message below
+pub const MAX_LENGTH: usize = 6;
+
+/// What apps can set besides CV, notes and gates
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum I2cParam {
+    /// Disting EX algorithm parameter by index
+    DistingEx(u8),
+    /// W/ tape playback, stopped at 0, otherwise playing
+    TapePlay,
+    /// W/ tape recording, off at 0, otherwise recording
+    TapeRecord,
+    /// W/ tape speed, 1.0 at 1V (1638)
+    TapeSpeed,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum WSynth {
+    /// `WS.S.PLAY.VOICE`: voice 1-4 (0 for all), pitch and level in II volts
+    PlayVoice { voice: i8, pitch: i16, level: i16 },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum WTape {
+    /// `WS.T.PLAY`
+    Play(i8),
+    /// `WS.T.REC`
+    Record(i8),
+    /// `WS.T.SPEED`
+    Speed(i16),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DistingEx {
+    /// `EX.PARAM`
+    SetParam {
+        param: u8,
+        value: i16,
+    },
+    /// `EX.NOTE`: sets the pitch of a note id before `NoteOn`
+    NotePitch {
+        note: u8,
+        pitch: i16,
+    },
+    NoteOn {
+        note: u8,
+        velocity: i16,
+    },
+    NoteOff {
+        note: u8,
+    },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Crow {
+    /// `CROW.V`: output 1-4 to a voltage in II volts
+    Volts { output: u8, volts: i16 },
+}
+
+fn write<'a>(buffer: &'a mut [u8], cmd: u8, head: &[u8], args: &[i16]) -> &'a [u8] {
+    buffer[0] = cmd;
+    buffer[1..1 + head.len()].copy_from_slice(head);
+    let mut len = 1 + head.len();
+    for arg in args {
+        buffer[len..len + 2].copy_from_slice(&arg.to_be_bytes());
+        len += 2;
+    }
+    &buffer[..len]
+}
+
+impl WSynth {
+    pub fn to_bytes<'a>(&self, buffer: &'a mut [u8; MAX_LENGTH]) -> &'a [u8] {
+        match *self {
+            Self::PlayVoice {
+                voice,
+                pitch,
+                level,
+            } => write(buffer, 0x07, &[voice as u8], &[pitch, level]),
+        }
+    }
+}
+
+impl WTape {
+    pub fn to_bytes<'a>(&self, buffer: &'a mut [u8; MAX_LENGTH]) -> &'a [u8] {
+        match *self {
+            Self::Record(on) => write(buffer, 0x01, &[on as u8], &[]),
+            Self::Play(on) => write(buffer, 0x02, &[on as u8], &[]),
+            Self::Speed(speed) => write(buffer, 0x04, &[], &[speed]),
+        }
+    }
+}
+
+impl DistingEx {
+    pub fn to_bytes<'a>(&self, buffer: &'a mut [u8; MAX_LENGTH]) -> &'a [u8] {
+        match *self {
+            Self::SetParam { param, value } => write(buffer, 0x46, &[param], &[value]),
+            Self::NotePitch { note, pitch } => write(buffer, 0x54, &[note], &[pitch]),
+            Self::NoteOn { note, velocity } => write(buffer, 0x55, &[note], &[velocity]),
+            Self::NoteOff { note } => write(buffer, 0x56, &[note], &[]),
+        }
+    }
+}
+
+impl Crow {
+    pub fn to_bytes<'a>(&self, buffer: &'a mut [u8; MAX_LENGTH]) -> &'a [u8] {
+        match *self {
+            Self::Volts { output, volts } => write(buffer, 0x01, &[output], &[volts]),
+        }
+    }
+}
+
+/// Follower type usually found at `address`
+pub fn device_at(address: u8) -> Option<I2cDeviceType> {
+    match address {
+        CROW_ADDRESS => Some(I2cDeviceType::Crow),
+        ANSIBLE_ADDRESS => Some(I2cDeviceType::Ansible),
+        a if (ER301_ADDRESS..ER301_ADDRESS + 3).contains(&a) => Some(I2cDeviceType::Er301),
+        a if (DISTING_EX_ADDRESS..DISTING_EX_ADDRESS + 4).contains(&a) => {
+            Some(I2cDeviceType::DistingEx)
+        }
+        a if (TXO_ADDRESS..TXO_ADDRESS + 8).contains(&a) => Some(I2cDeviceType::Txo),
+        JUST_FRIENDS_ADDRESS => Some(I2cDeviceType::JustFriends),
+        W_TAPE_ADDRESS => Some(I2cDeviceType::WTape),
+        W_SYNTH_ADDRESS => Some(I2cDeviceType::WSynth),
+        _ => None,
+    }
+}
+
+/// Address of the first follower of `device`
+pub fn default_address(device: I2cDeviceType) -> Option<u8> {
+    match device {
+        I2cDeviceType::Auto | I2cDeviceType::Off => None,
+        I2cDeviceType::Ansible => Some(ANSIBLE_ADDRESS),
+        I2cDeviceType::Er301 => Some(ER301_ADDRESS),
+        I2cDeviceType::Txo => Some(TXO_ADDRESS),
+        I2cDeviceType::JustFriends => Some(JUST_FRIENDS_ADDRESS),
+        I2cDeviceType::WSynth => Some(W_SYNTH_ADDRESS),
+        I2cDeviceType::WTape => Some(W_TAPE_ADDRESS),
+        I2cDeviceType::DistingEx => Some(DISTING_EX_ADDRESS),
+        I2cDeviceType::Crow => Some(CROW_ADDRESS),
+    }
+}
+
+/// 12-bit value from an app with `range` to II volts
+pub fn scale_value(value: u16, range: Range, scaling: I2cScaling) -> i16 {
+    match scaling {
+        I2cScaling::Full => value_to_ii(value, Range::_0_10V),
+        I2cScaling::Volts => value_to_ii(value, range),
+    }
+}
+
+/// 12-bit value in `range` to II volts, the voltage a jack would put out
+pub fn value_to_ii(value: u16, range: Range) -> i16 {
+    let value = value.min(4095) as i32;
+    let scaled = match range {
+        Range::_0_10V => value * 16383 / 4095,
+        Range::_0_5V => value * 8191 / 4095,
+        Range::_Neg5_5V => value * 16383 / 4095 - 8192,
+    };
+    scaled as i16
+}
+
+/// Note as II volts at 1V/oct, with C0 (note 12) at 0V like the jacks
+pub fn note_to_ii(note: MidiNote) -> i16 {
+    let semitones = note.0 as i32 - 12;
+    (semitones * 16384 / 120) as i16
+}
+
+/// 12-bit velocity to an II level, full velocity being 5V
+pub fn velocity_to_ii(velocity: u16) -> i16 {
+    (velocity.min(4095) as i32 * 8192 / 4095) as i16
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn commands_are_big_endian() {
+        let mut buf = [0u8; MAX_LENGTH];
+        assert_eq!(
+            DistingEx::SetParam {
+                param: 3,
+                value: 0x0102
+            }
+            .to_bytes(&mut buf),
+            &[0x46, 3, 0x01, 0x02]
+        );
+        assert_eq!(
+            WSynth::PlayVoice {
+                voice: 1,
+                pitch: -1,
+                level: 8192
+            }
+            .to_bytes(&mut buf),
+            &[0x07, 1, 0xFF, 0xFF, 0x20, 0x00]
+        );
+        assert_eq!(WTape::Play(1).to_bytes(&mut buf), &[0x02, 1]);
+        assert_eq!(
+            Crow::Volts {
+                output: 4,
+                volts: 1638
+            }
+            .to_bytes(&mut buf),
+            &[0x01, 4, 0x06, 0x66]
+        );
+    }
+
+    #[test]
+    fn values_follow_range_and_pitch() {
+        assert_eq!(value_to_ii(4095, Range::_0_10V), 16383);
+        assert_eq!(value_to_ii(4095, Range::_0_5V), 8191);
+        assert_eq!(value_to_ii(0, Range::_Neg5_5V), -8192);
+        assert_eq!(note_to_ii(MidiNote::from(12)), 0);
+        // An octave is 1V
+        assert_eq!(note_to_ii(MidiNote::from(24)), 1638);
+        assert_eq!(note_to_ii(MidiNote::from(0)), -1638);
+        assert_eq!(velocity_to_ii(4095), 8192);
+        assert_eq!(scale_value(4095, Range::_0_5V, I2cScaling::Full), 16383);
+        assert_eq!(scale_value(4095, Range::_0_5V, I2cScaling::Volts), 8191);
+    }
+
+    #[test]
+    fn addresses_identify_devices() {
+        assert_eq!(device_at(0x62), Some(I2cDeviceType::Txo));
+        assert_eq!(device_at(0x33), Some(I2cDeviceType::Er301));
+        assert_eq!(device_at(0x34), None);
+        for device in [
+            I2cDeviceType::Ansible,
+            I2cDeviceType::Er301,
+            I2cDeviceType::Txo,
+            I2cDeviceType::JustFriends,
+            I2cDeviceType::WSynth,
+            I2cDeviceType::WTape,
+            I2cDeviceType::DistingEx,
+            I2cDeviceType::Crow,
+        ] {
+            assert_eq!(device_at(default_address(device).unwrap()), Some(device));
+        }
+    }
+}
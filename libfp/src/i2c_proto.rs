@@ -1,7 +1,7 @@
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    types::{RegressionValuesInput, RegressionValuesOutput},
+    types::{PiecewiseCalibration, RegressionValuesInput, RegressionValuesOutput},
     Range,
 };
 
@@ -34,6 +34,8 @@ pub enum WriteCommand {
     DacSetVoltage(usize, Range, u16),
     /// Reset the device
     SysReset,
+    /// Set the optional multi-point corrections, before `CalibSetRegValues`
+    CalibSetPiecewise(PiecewiseCalibration),
 }
 
 /// Responses sent from the device to the leader
@@ -0,0 +1,110 @@
+//! Held MIDI notes for following the global key from a keyboard: the tonic
+//! comes from the lowest or last held note, the mode from the held third.
+
+use crate::{Key, Note};
+
+/// Notes held on one MIDI channel
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct HeldNotes {
+    /// Bit `n` is set while MIDI note `n` is held
+    held: u128,
+    last: Option<u8>,
+}
+
+impl HeldNotes {
+    pub const fn new() -> Self {
+        Self {
+            held: 0,
+            last: None,
+        }
+    }
+
+    pub fn note_on(&mut self, note: u8) {
+        let note = note.min(127);
+        self.held |= 1 << note;
+        self.last = Some(note);
+    }
+
+    pub fn note_off(&mut self, note: u8) {
+        let note = note.min(127);
+        self.held &= !(1 << note);
+        if self.last == Some(note) {
+            self.last = None;
+        }
+    }
+
+    pub fn is_held(&self, note: u8) -> bool {
+        note < 128 && self.held & (1 << note) != 0
+    }
+
+    /// Lowest held note
+    pub fn lowest(&self) -> Option<u8> {
+        (self.held != 0).then(|| self.held.trailing_zeros() as u8)
+    }
+
+    /// Most recently pressed note, as long as it is held
+    pub fn last(&self) -> Option<u8> {
+        self.last
+    }
+
+    /// Major or minor, from the third held above the lowest note in any
+    /// octave. `None` without a lowest note or with neither or both thirds.
+    pub fn mode(&self) -> Option<Key> {
+        let lowest = self.lowest()?;
+        let mut intervals = 0_u16;
+        for note in lowest..128 {
+            if self.is_held(note) {
+                intervals |= 1 << ((note - lowest) % 12);
+            }
+        }
+        match (intervals & (1 << 3) != 0, intervals & (1 << 4) != 0) {
+            (true, false) => Some(Key::Aeolian),
+            (false, true) => Some(Key::Ionian),
+            _ => None,
+        }
+    }
+}
+
+/// Pitch class of a MIDI note
+pub fn note_name(note: u8) -> Note {
+    Note::from(note % 12)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lowest_and_last_track_held_notes() {
+        let mut held = HeldNotes::new();
+        assert_eq!(held.lowest(), None);
+        held.note_on(64);
+        held.note_on(60);
+        held.note_on(67);
+        assert_eq!(held.lowest(), Some(60));
+        assert_eq!(held.last(), Some(67));
+        held.note_off(60);
+        assert_eq!(held.lowest(), Some(64));
+        held.note_off(67);
+        assert_eq!(held.last(), None);
+        held.note_off(64);
+        assert_eq!(held.lowest(), None);
+        assert_eq!(note_name(62), Note::D);
+    }
+
+    #[test]
+    fn mode_follows_the_third() {
+        let mut held = HeldNotes::new();
+        // A minor triad with the third an octave up
+        held.note_on(57);
+        held.note_on(64);
+        held.note_on(72);
+        assert_eq!(held.mode(), Some(Key::Aeolian));
+        held.note_off(72);
+        assert_eq!(held.mode(), None);
+        held.note_on(61);
+        assert_eq!(held.mode(), Some(Key::Ionian));
+        held.note_on(60);
+        assert_eq!(held.mode(), None);
+    }
+}
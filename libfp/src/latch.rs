@@ -53,6 +53,19 @@ pub enum TakeoverMode {
     Scale,
 }
 
+impl TakeoverMode {
+    /// Maps a value of `APP_TAKEOVER_PARAM` onto the mode it selects.
+    /// Returns `None` for the app to follow the global takeover mode.
+    pub fn from_app_param(index: usize) -> Option<Self> {
+        match index {
+            1 => Some(TakeoverMode::Pickup),
+            2 => Some(TakeoverMode::Jump),
+            3 => Some(TakeoverMode::Scale),
+            _ => None,
+        }
+    }
+}
+
 /// A stateless machine that implements "catch-up" or "pickup" logic for a fader or knob.
 ///
 /// This struct determines when a physical fader should take control of a value.
@@ -143,6 +156,18 @@ impl AnalogLatch {
         self.is_latched
     }
 
+    /// Returns how far the fader has to travel to pick up the active layer's value.
+    ///
+    /// Positive means the fader has to move up, negative means down. Only `Pickup` mode
+    /// waits for the fader to reach the value, so this is `None` for the other modes
+    /// and whenever the fader is latched.
+    pub fn pickup_distance(&self) -> Option<i16> {
+        if self.is_latched || self.mode != TakeoverMode::Pickup {
+            return None;
+        }
+        Some(self.prev_target as i16 - self.prev_value as i16)
+    }
+
     /// Updates the latch's internal state based on new fader input.
     ///
     /// # Arguments
@@ -807,6 +832,47 @@ mod tests {
         assert!(latch.is_latched());
     }
 
+    #[test]
+    fn test_takeover_mode_from_app_param() {
+        let crate::Param::Enum { variants, .. } = crate::APP_TAKEOVER_PARAM else {
+            panic!("APP_TAKEOVER_PARAM must be an enum param");
+        };
+        assert_eq!(variants.len(), 4);
+        assert_eq!(TakeoverMode::from_app_param(0), None);
+        assert_eq!(TakeoverMode::from_app_param(1), Some(TakeoverMode::Pickup));
+        assert_eq!(TakeoverMode::from_app_param(2), Some(TakeoverMode::Jump));
+        assert_eq!(TakeoverMode::from_app_param(3), Some(TakeoverMode::Scale));
+        assert_eq!(TakeoverMode::from_app_param(variants.len()), None);
+    }
+
+    #[test]
+    fn test_pickup_distance() {
+        let mut latch = AnalogLatch::new(1000, TakeoverMode::Pickup);
+        assert_eq!(latch.pickup_distance(), None);
+
+        // Stored value is above the fader
+        latch.update(1000, LatchLayer::Alt, 3000);
+        assert_eq!(latch.pickup_distance(), Some(2000));
+
+        // Moving towards the stored value shrinks the distance
+        latch.update(1500, LatchLayer::Alt, 3000);
+        assert_eq!(latch.pickup_distance(), Some(1500));
+
+        // Stored value is below the fader
+        latch.update(1500, LatchLayer::Main, 200);
+        assert_eq!(latch.pickup_distance(), Some(-1300));
+
+        latch.update(100, LatchLayer::Main, 200);
+        assert!(latch.is_latched());
+        assert_eq!(latch.pickup_distance(), None);
+
+        // Other modes never wait for a pickup
+        let mut scale = AnalogLatch::new(1000, TakeoverMode::Scale);
+        scale.update(1000, LatchLayer::Alt, 3000);
+        assert!(!scale.is_latched());
+        assert_eq!(scale.pickup_distance(), None);
+    }
+
     #[test]
     fn test_pickup_layer_switch_unlatch() {
         let mut latch = AnalogLatch::new(1000, TakeoverMode::Pickup);
@@ -2,7 +2,9 @@
 
 use core::ops::Add;
 
+use crate::custom_curve::CUSTOM_CURVES;
 use crate::quantizer::Pitch;
+use crate::types::{CalibFile, FaderCalibration, PiecewiseFile};
 use embassy_time::Duration;
 use heapless::Vec;
 use max11300::config::{ADCRANGE, DACRANGE};
@@ -11,19 +13,28 @@ use minicbor::{Decode, Encode};
 use postcard_bindgen::PostcardBindings;
 use serde::{Deserialize, Serialize};
 
+pub mod cc14;
 pub mod colors;
 pub mod constants;
+pub mod custom_curve;
 pub mod ext;
 pub mod fp_grids_lib;
+pub mod i2c_follower;
+pub mod i2c_leader;
 pub mod i2c_proto;
+pub mod key_follow;
 pub mod latch;
+pub mod mtc;
 pub mod quantizer;
 pub mod sysex;
+pub mod tuning;
 pub mod types;
 pub mod utils;
 
-// Re-export commonly used latch types
+// Re-export commonly used types
+pub use custom_curve::CustomCurve;
 pub use latch::{AnalogLatch, LatchLayer, TakeoverMode};
+pub use tuning::Tuning;
 
 use constants::{
     CURVE_EXP, CURVE_LOG, WAVEFORM_SAW, WAVEFORM_SAW_INV, WAVEFORM_SINE, WAVEFORM_SQUARE,
@@ -47,6 +58,13 @@ pub const I2C_ADDRESS_CALIBRATION: u16 = 0x57;
 /// Maximum number of params per app
 pub const APP_MAX_PARAMS: usize = 16;
 
+/// Param the firmware appends to every app's own params. The first variant follows
+/// `GlobalConfig::takeover_mode`, see `TakeoverMode::from_app_param` for the others.
+pub const APP_TAKEOVER_PARAM: Param = Param::Enum {
+    name: "Takeover",
+    variants: &["Global", "Pickup", "Jump", "Scale"],
+};
+
 /// Length of the startup animation
 pub const STARTUP_ANIMATION_DURATION: Duration = Duration::from_secs(2);
 
@@ -54,8 +72,9 @@ pub const STARTUP_ANIMATION_DURATION: Duration = Duration::from_secs(2);
 pub const LED_BRIGHTNESS_RANGE: core::ops::Range<u8> = 100..255;
 
 pub const CALIBRATION_SCALE_FACTOR: i64 = 1 << 16;
-pub const CALIBRATION_VERSION_LATEST: u8 = 2;
+pub const CALIBRATION_VERSION_LATEST: u8 = 3;
 pub const CALIB_FILE_MAGIC: [u8; 4] = *b"FPBC";
+pub const CALIB_POINTS_FILE_MAGIC: [u8; 4] = *b"FPPC";
 
 pub type ConfigMeta<'a> = (usize, &'a str, &'a str, Color, AppIcon, &'a [Param]);
 
@@ -242,6 +261,91 @@ pub enum I2cMode {
     Follower,
 }
 
+/// Persisted in `GlobalConfig` via CBOR (inside `I2cMapping`). New variants
+/// may be appended with the next free `#[n(N)]` tag without a migration.
+/// **Removing** a variant requires a one-shot FRAM migration (see
+/// `storage::migrate_fram`).
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize, PostcardBindings, Encode, Decode,
+)]
+#[cbor(index_only)]
+#[repr(u8)]
+pub enum I2cDeviceType {
+    /// Every follower found on the bus, at fixed ports (the 16n layout)
+    #[default]
+    #[n(0)]
+    Auto,
+    #[n(1)]
+    Off,
+    #[n(2)]
+    Ansible,
+    #[n(3)]
+    Er301,
+    #[n(4)]
+    Txo,
+    #[n(5)]
+    JustFriends,
+    #[n(6)]
+    WSynth,
+    /// Faders set the speed, gates start and stop playback
+    #[n(7)]
+    WTape,
+    #[n(8)]
+    DistingEx,
+    #[n(9)]
+    Crow,
+}
+
+/// Persisted in `GlobalConfig` via CBOR (inside `I2cMapping`). New variants
+/// may be appended with the next free `#[n(N)]` tag without a migration.
+/// **Removing** a variant requires a one-shot FRAM migration (see
+/// `storage::migrate_fram`).
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize, PostcardBindings, Encode, Decode,
+)]
+#[cbor(index_only)]
+#[repr(u8)]
+pub enum I2cScaling {
+    /// The full 12-bit value to 0-10V, whatever the app's range (like the 16n)
+    #[default]
+    #[n(0)]
+    Full,
+    /// The voltage the jack would put out in the app's range
+    #[n(1)]
+    Volts,
+}
+
+/// Where the I2C leader sends a channel's values
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize, PostcardBindings, Encode, Decode,
+)]
+pub struct I2cMapping {
+    #[n(0)]
+    #[cbor(default)]
+    pub device: I2cDeviceType,
+    #[n(1)]
+    #[cbor(default)]
+    pub address: u8,
+    /// Output on the device, from 0
+    #[n(2)]
+    #[cbor(default)]
+    pub port: u8,
+    #[n(3)]
+    #[cbor(default)]
+    pub scaling: I2cScaling,
+}
+
+impl I2cMapping {
+    pub const fn new() -> Self {
+        Self {
+            device: I2cDeviceType::Auto,
+            address: 0,
+            port: 0,
+            scaling: I2cScaling::Full,
+        }
+    }
+}
+
 /// Persisted in `GlobalConfig` via CBOR (inside `QuantizerConfig`). New
 /// variants may be appended with the next free `#[n(N)]` tag without a
 /// migration. **Removing** a variant requires a one-shot FRAM migration (see
@@ -309,56 +413,105 @@ impl FromValue for Note {
 }
 
 /// Persisted in `GlobalConfig` via CBOR (inside `QuantizerConfig`). New
-/// variants may be appended with the next free `#[n(N)]` tag without a
-/// migration. **Removing** a variant requires a one-shot FRAM migration (see
+/// variants may be appended with the next free index without a migration.
+/// **Removing** a variant requires a one-shot FRAM migration (see
 /// `storage::migrate_fram`).
-#[derive(
-    Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize, PostcardBindings, Encode, Decode,
-)]
-#[cbor(index_only)]
+///
+/// The CBOR impls are written by hand: built-in scales encode as their bare
+/// index, like `#[cbor(index_only)]` did before `Custom` existed, and
+/// `Custom(idx)` encodes as `[17, idx]`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize, PostcardBindings)]
 #[repr(u8)]
 pub enum Key {
     #[default]
-    #[n(0)]
     Chromatic,
-    #[n(1)]
     Ionian,
-    #[n(2)]
     Dorian,
-    #[n(3)]
     Phrygian,
-    #[n(4)]
     Lydian,
-    #[n(5)]
     Mixolydian,
-    #[n(6)]
     Aeolian,
-    #[n(7)]
     Locrian,
-    #[n(8)]
     BluesMaj,
-    #[n(9)]
     BluesMin,
-    #[n(10)]
     PentatonicMaj,
-    #[n(11)]
     PentatonicMin,
-    #[n(12)]
     Folk,
-    #[n(13)]
     Japanese,
-    #[n(14)]
     Gamelan,
-    #[n(15)]
     HungarianMin,
     /// CV passes through unmodified; MIDI output still uses nearest chromatic semitone.
-    #[n(16)]
     Off,
+    /// Index into `GlobalConfig::custom_scales` (0–3).
+    Custom(u8),
 }
 
+/// CBOR index of `Key::Custom`
+const KEY_CUSTOM_INDEX: u8 = 17;
+
 impl Key {
-    /// Get the u16 bitmask
+    /// Number of positions `from_index` covers: the built-in scales followed
+    /// by one per custom scale
+    pub const COUNT: u8 = KEY_CUSTOM_INDEX + CUSTOM_SCALES as u8;
+
+    /// Position in the list of scales, `Custom(idx)` coming after `Off`
+    pub const fn index(&self) -> u8 {
+        match self {
+            Key::Chromatic => 0,
+            Key::Ionian => 1,
+            Key::Dorian => 2,
+            Key::Phrygian => 3,
+            Key::Lydian => 4,
+            Key::Mixolydian => 5,
+            Key::Aeolian => 6,
+            Key::Locrian => 7,
+            Key::BluesMaj => 8,
+            Key::BluesMin => 9,
+            Key::PentatonicMaj => 10,
+            Key::PentatonicMin => 11,
+            Key::Folk => 12,
+            Key::Japanese => 13,
+            Key::Gamelan => 14,
+            Key::HungarianMin => 15,
+            Key::Off => 16,
+            Key::Custom(idx) => KEY_CUSTOM_INDEX + *idx,
+        }
+    }
+
+    /// Inverse of `index`, clamping past the last custom scale
+    pub const fn from_index(index: u8) -> Self {
+        match index {
+            0 => Key::Chromatic,
+            1 => Key::Ionian,
+            2 => Key::Dorian,
+            3 => Key::Phrygian,
+            4 => Key::Lydian,
+            5 => Key::Mixolydian,
+            6 => Key::Aeolian,
+            7 => Key::Locrian,
+            8 => Key::BluesMaj,
+            9 => Key::BluesMin,
+            10 => Key::PentatonicMaj,
+            11 => Key::PentatonicMin,
+            12 => Key::Folk,
+            13 => Key::Japanese,
+            14 => Key::Gamelan,
+            15 => Key::HungarianMin,
+            16 => Key::Off,
+            i if i >= Self::COUNT => Key::Custom(CUSTOM_SCALES as u8 - 1),
+            i => Key::Custom(i - KEY_CUSTOM_INDEX),
+        }
+    }
+
+    /// Get the u16 bitmask. `Custom` scales live in `GlobalConfig` and read as
+    /// chromatic here, use `as_u16_key_with_scales` to resolve them.
     pub fn as_u16_key(&self) -> u16 {
+        self.as_u16_key_with_scales(&[CustomScale::new(); CUSTOM_SCALES])
+    }
+
+    /// Like `as_u16_key` but resolves `Custom` scales. An empty custom scale
+    /// stays empty; the quantizer falls back to chromatic for it.
+    pub fn as_u16_key_with_scales(&self, scales: &[CustomScale; CUSTOM_SCALES]) -> u16 {
         match self {
             Key::Chromatic => 0b111111111111,
             Key::Ionian => 0b101011010101,
@@ -378,8 +531,89 @@ impl Key {
             Key::HungarianMin => 0b101100111001,
             // Off: internally treated as Chromatic for MIDI output
             Key::Off => 0b111111111111,
+            Key::Custom(idx) => scales
+                .get(*idx as usize)
+                .map(|scale| scale.mask & 0b111111111111)
+                .unwrap_or(0b111111111111),
+        }
+    }
+}
+
+impl<C> Encode<C> for Key {
+    fn encode<W: minicbor::encode::Write>(
+        &self,
+        e: &mut minicbor::Encoder<W>,
+        _ctx: &mut C,
+    ) -> Result<(), minicbor::encode::Error<W::Error>> {
+        match self {
+            Key::Custom(idx) => {
+                e.array(2)?.u8(KEY_CUSTOM_INDEX)?.u8(*idx)?;
+            }
+            key => {
+                e.u8(key.index())?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<'b, C> Decode<'b, C> for Key {
+    fn decode(
+        d: &mut minicbor::Decoder<'b>,
+        _ctx: &mut C,
+    ) -> Result<Self, minicbor::decode::Error> {
+        if d.datatype()? == minicbor::data::Type::Array {
+            d.array()?;
+            if d.u8()? != KEY_CUSTOM_INDEX {
+                return Err(minicbor::decode::Error::message("unknown Key variant"));
+            }
+            let idx = d.u8()?;
+            if idx as usize >= CUSTOM_SCALES {
+                return Err(minicbor::decode::Error::message(
+                    "custom scale out of range",
+                ));
+            }
+            return Ok(Key::Custom(idx));
+        }
+        match d.u8()? {
+            i if i < KEY_CUSTOM_INDEX => Ok(Key::from_index(i)),
+            _ => Err(minicbor::decode::Error::message("unknown Key variant")),
+        }
+    }
+}
+
+/// Number of user-defined scales in `GlobalConfig::custom_scales`
+pub const CUSTOM_SCALES: usize = 4;
+/// Bytes in a custom scale name, ASCII padded with zeroes
+pub const CUSTOM_SCALE_NAME_LEN: usize = 8;
+
+/// One user-defined scale stored in `GlobalConfig`, selected as
+/// `Key::Custom(idx)`. The mask is laid out like `Key::as_u16_key`, C at
+/// bit 11 down to B at bit 0. An empty mask quantizes chromatically.
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize, PostcardBindings, Encode, Decode,
+)]
+pub struct CustomScale {
+    #[n(0)]
+    #[cbor(default)]
+    pub name: [u8; CUSTOM_SCALE_NAME_LEN],
+    #[n(1)]
+    #[cbor(default)]
+    pub mask: u16,
+}
+
+impl CustomScale {
+    pub const fn new() -> Self {
+        Self {
+            name: [0; CUSTOM_SCALE_NAME_LEN],
+            mask: 0,
         }
     }
+
+    /// Flip note `note` (0 = C) in or out of the scale
+    pub fn toggle(&mut self, note: Note) {
+        self.mask ^= 1 << (11 - note as u16);
+    }
 }
 
 /// Volts-per-octave standard. Selects pitch calibration for quantizer and pitch apps.
@@ -466,6 +700,66 @@ pub struct CustomVoOctCurve {
     pub counts_per_oct: u16,
 }
 
+/// Largest offset trim in cents, one octave either way
+pub const VOCT_TRIM_MAX_CENTS: i16 = 1200;
+/// Largest scale trim in ppm (±10%)
+pub const VOCT_TRIM_MAX_PPM: i32 = 100_000;
+
+/// Per-output-jack fine tuning so an oscillator patched to that jack tracks
+/// in tune. Applied by the firmware to the pitch values apps set on the jack,
+/// including 0V, but not to gates, envelopes or LFOs. The scale
+/// stretches around 0V, the offset is added after it. Stored in FRAM as CBOR
+/// next to the app storage.
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize, PostcardBindings, Encode, Decode,
+)]
+pub struct VoOctTrim {
+    #[n(0)]
+    #[cbor(default)]
+    pub offset_cents: i16,
+    #[n(1)]
+    #[cbor(default)]
+    pub scale_ppm: i32,
+}
+
+impl VoOctTrim {
+    pub const fn new(offset_cents: i16, scale_ppm: i32) -> Self {
+        Self {
+            offset_cents,
+            scale_ppm,
+        }
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.offset_cents.abs() <= VOCT_TRIM_MAX_CENTS && self.scale_ppm.abs() <= VOCT_TRIM_MAX_PPM
+    }
+
+    pub fn is_neutral(&self) -> bool {
+        self.offset_cents == 0 && self.scale_ppm == 0
+    }
+
+    /// Trim a DAC value in `range`. 1V/Oct is 409.5 counts per octave in
+    /// every range.
+    pub fn apply(&self, counts: u16, range: Range) -> u16 {
+        if self.is_neutral() {
+            return counts;
+        }
+        let zero = if range == Range::_Neg5_5V { 2048 } else { 0 };
+        let rel = counts as i64 - zero;
+        let scaled = div_round(rel * (1_000_000 + self.scale_ppm as i64), 1_000_000);
+        let offset = div_round(self.offset_cents as i64 * 4095, 12_000);
+        (zero + scaled + offset).clamp(0, 4095) as u16
+    }
+}
+
+fn div_round(num: i64, den: i64) -> i64 {
+    if num >= 0 {
+        (num + den / 2) / den
+    } else {
+        (num - den / 2) / den
+    }
+}
+
 impl From<VoltPerOct> for Value {
     fn from(value: VoltPerOct) -> Self {
         Value::VoltPerOct(value)
@@ -481,6 +775,33 @@ impl FromValue for VoltPerOct {
     }
 }
 
+/// Scale an app quantizes to: the global quantizer scale, or its own key and
+/// tonic. Custom scales and the tuning always come from the global config.
+#[derive(Clone, Copy, Default, Debug, PartialEq, Serialize, Deserialize, PostcardBindings)]
+pub enum AppScale {
+    #[default]
+    Global,
+    Fixed {
+        key: Key,
+        tonic: Note,
+    },
+}
+
+impl From<AppScale> for Value {
+    fn from(value: AppScale) -> Self {
+        Value::Scale(value)
+    }
+}
+
+impl FromValue for AppScale {
+    fn from_value(value: Value) -> Self {
+        match value {
+            Value::Scale(v) => v,
+            _ => Self::default(),
+        }
+    }
+}
+
 /// Persisted in `GlobalConfig` via CBOR (inside `MidiOutConfig`). New variants
 /// may be appended with the next free `#[n(N)]` tag without a migration.
 /// **Removing** a variant requires a one-shot FRAM migration (see
@@ -506,6 +827,63 @@ pub enum MidiOutMode {
     },
 }
 
+/// MIDI Timecode frame rate. The tag matches the rate code carried in the
+/// timecode hours byte.
+///
+/// Persisted in `GlobalConfig` via CBOR. New variants may be appended with the
+/// next free `#[n(N)]` tag without a migration. **Removing** a variant
+/// requires a one-shot FRAM migration (see `storage::migrate_fram`).
+#[derive(
+    Clone, Copy, Default, PartialEq, Serialize, Deserialize, PostcardBindings, Encode, Decode,
+)]
+#[cbor(index_only)]
+#[repr(u8)]
+pub enum MtcFps {
+    #[n(0)]
+    Fps24,
+    #[default]
+    #[n(1)]
+    Fps25,
+    /// 29.97 drop-frame
+    #[n(2)]
+    Fps2997,
+    #[n(3)]
+    Fps30,
+}
+
+/// Input to chase incoming MIDI Timecode from.
+///
+/// Persisted in `GlobalConfig` via CBOR. New variants may be appended with the
+/// next free `#[n(N)]` tag without a migration. **Removing** a variant
+/// requires a one-shot FRAM migration (see `storage::migrate_fram`).
+#[derive(
+    Clone, Copy, Default, PartialEq, Serialize, Deserialize, PostcardBindings, Encode, Decode,
+)]
+#[cbor(index_only)]
+#[repr(u8)]
+pub enum MtcChaseSrc {
+    #[default]
+    #[n(0)]
+    None,
+    #[n(1)]
+    MidiIn,
+    #[n(2)]
+    MidiUsb,
+}
+
+impl From<MtcChaseSrc> for ClockSrc {
+    fn from(value: MtcChaseSrc) -> Self {
+        match value {
+            MtcChaseSrc::None => ClockSrc::None,
+            MtcChaseSrc::MidiIn => ClockSrc::MidiIn,
+            MtcChaseSrc::MidiUsb => ClockSrc::MidiUsb,
+        }
+    }
+}
+
+/// Largest per-output MIDI clock offset either way, in ms
+pub const MIDI_CLOCK_OFFSET_MAX_MS: i8 = 100;
+
 #[derive(Clone, Copy, Serialize, Deserialize, PostcardBindings, PartialEq, Encode, Decode)]
 pub struct MidiOutConfig {
     #[n(0)]
@@ -517,6 +895,20 @@ pub struct MidiOutConfig {
     #[n(2)]
     #[cbor(default)]
     pub mode: MidiOutMode,
+    /// Send MIDI Timecode generated from the internal clock's position
+    #[n(3)]
+    #[cbor(default)]
+    pub send_mtc: bool,
+    /// Shifts clock and transport on this output, in ms, within
+    /// ±[`MIDI_CLOCK_OFFSET_MAX_MS`]. Negative values send ahead of the beat,
+    /// which only the internal clock can do; on external clock they act as 0.
+    #[n(4)]
+    #[cbor(default)]
+    pub clock_offset_ms: i8,
+    /// Send every n-th clock tick, e.g. `_24` for one pulse per quarter note
+    #[n(5)]
+    #[cbor(default)]
+    pub clock_div: ClockDivision,
 }
 
 impl Default for MidiOutConfig {
@@ -532,6 +924,137 @@ impl MidiOutConfig {
             send_clock: true,
             send_transport: true,
             mode: MidiOutMode::Local,
+            send_mtc: false,
+            clock_offset_ms: 0,
+            clock_div: ClockDivision::_1,
+        }
+    }
+}
+
+/// Outgoing scheduler counters for one DIN MIDI output, since boot.
+#[derive(Clone, Copy, Default, Serialize, Deserialize, PostcardBindings, PartialEq)]
+pub struct MidiOutStats {
+    /// Messages discarded because the output queue was full
+    pub dropped: u32,
+    /// Pending CC/pitch bend/NRPN values replaced by a newer value before
+    /// they were sent
+    pub coalesced: u32,
+}
+
+/// Where the MIDI activity log saw a message
+#[derive(Clone, Copy, Serialize, Deserialize, PostcardBindings, PartialEq)]
+pub enum MidiLogPort {
+    UsbIn,
+    DinIn,
+    UsbOut,
+    Out1,
+    Out2,
+}
+
+impl MidiLogPort {
+    /// Output port for index `i` of a [`MidiOut`] target
+    pub const fn output(i: usize) -> Self {
+        match i {
+            0 => Self::UsbOut,
+            1 => Self::Out1,
+            _ => Self::Out2,
+        }
+    }
+
+    pub const fn is_input(self) -> bool {
+        matches!(self, Self::UsbIn | Self::DinIn)
+    }
+}
+
+/// One message in the MIDI activity log
+#[derive(Clone, Copy, Serialize, Deserialize, PostcardBindings, PartialEq)]
+pub struct MidiLogEntry {
+    /// Position in the log. Pass the last seen one + 1 to `GetMidiLog`.
+    pub seq: u32,
+    /// Milliseconds since boot
+    pub time_ms: u32,
+    pub port: MidiLogPort,
+    /// Incoming: forwarded to a thru/merge output. Outgoing: a thru copy
+    /// rather than a local message.
+    pub thru: bool,
+    /// Length of the whole message in bytes (SysEx including F0/F7), up to 255
+    pub len: u8,
+    /// First bytes of the message
+    pub bytes: [u8; 3],
+}
+
+/// What the MIDI activity log records. Clock and timecode are off by default
+/// so they don't push everything else out of the log.
+#[derive(Clone, Copy, Serialize, Deserialize, PostcardBindings, PartialEq)]
+pub struct MidiLogFilter {
+    pub inputs: bool,
+    pub outputs: bool,
+    /// Timing clock and active sensing
+    pub clock: bool,
+    /// MTC quarter frames
+    pub timecode: bool,
+    /// Channel messages on these channels, bit n for channel n + 1
+    pub channels: u16,
+}
+
+impl Default for MidiLogFilter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MidiLogFilter {
+    pub const fn new() -> Self {
+        Self {
+            inputs: true,
+            outputs: true,
+            clock: false,
+            timecode: false,
+            channels: 0xFFFF,
+        }
+    }
+
+    /// Whether a message starting with `status` seen on `port` is recorded
+    pub fn accepts(&self, port: MidiLogPort, status: u8) -> bool {
+        let direction = if port.is_input() {
+            self.inputs
+        } else {
+            self.outputs
+        };
+        direction
+            && match status {
+                0xF8 | 0xFE => self.clock,
+                0xF1 => self.timecode,
+                0x80..=0xEF => self.channels & (1 << (status & 0x0F)) != 0,
+                _ => true,
+            }
+    }
+}
+
+/// One step of a calibration check: an output set to a test voltage and read
+/// back on an input jack.
+#[derive(Clone, Copy, Serialize, Deserialize, PostcardBindings, PartialEq)]
+pub struct CalibrationCheckPoint {
+    pub range: Range,
+    /// Voltage the output was set to
+    pub volts: f32,
+    /// Voltage read back minus the one set, in millivolts
+    pub error_mv: f32,
+    /// The same error in cents at 1V/oct
+    pub error_cents: f32,
+}
+
+impl CalibrationCheckPoint {
+    /// Compare the `ideal` counts an output was set to with the `measured`
+    /// input counts, both spanning 10V over the 12-bit range.
+    pub fn new(range: Range, ideal: u16, measured: u16) -> Self {
+        let offset = if range == Range::_Neg5_5V { -5.0 } else { 0.0 };
+        let error_mv = (measured as f32 - ideal as f32) * 10_000.0 / 4095.0;
+        Self {
+            range,
+            volts: ideal as f32 * 10.0 / 4095.0 + offset,
+            error_mv,
+            error_cents: error_mv * 1.2,
         }
     }
 }
@@ -542,6 +1065,14 @@ pub struct MidiConfig {
     #[n(0)]
     #[cbor(default)]
     pub outs: [MidiOutConfig; 3],
+    /// Frame rate of generated MIDI Timecode
+    #[n(1)]
+    #[cbor(default)]
+    pub mtc_fps: MtcFps,
+    /// Locate and run the internal clock from incoming MIDI Timecode
+    #[n(2)]
+    #[cbor(default)]
+    pub mtc_chase: MtcChaseSrc,
 }
 
 impl Default for MidiConfig {
@@ -555,8 +1086,21 @@ impl MidiConfig {
     pub const fn new() -> Self {
         Self {
             outs: [MidiOutConfig::new(); 3],
+            mtc_fps: MtcFps::Fps25,
+            mtc_chase: MtcChaseSrc::None,
         }
     }
+
+    /// How far ahead of the beat the internal clock has to send MIDI clock so
+    /// the output with the most negative offset can be early, in ms.
+    pub fn clock_lead_ms(&self) -> u8 {
+        self.outs
+            .iter()
+            .filter(|o| o.send_clock)
+            .map(|o| o.clock_offset_ms.min(0).unsigned_abs())
+            .max()
+            .unwrap_or(0)
+    }
 }
 
 #[derive(Clone, Serialize, Deserialize, PostcardBindings, PartialEq, Encode, Decode)]
@@ -671,6 +1215,170 @@ pub enum AuxJackMode {
     ResetOut,
 }
 
+/// Change in counts a fader needs before it counts as moved
+pub const FADER_HYSTERESIS_DEFAULT: u8 = 4;
+pub const FADER_HYSTERESIS_MAX: u8 = 64;
+/// Strongest fader smoothing, see `FaderConfig::smoothing`
+pub const FADER_SMOOTHING_MAX: u8 = 4;
+
+/// How fader readings are cleaned up before apps see them
+#[derive(
+    Clone, Copy, Debug, PartialEq, Serialize, Deserialize, PostcardBindings, Encode, Decode,
+)]
+pub struct FaderConfig {
+    /// Change in counts a fader needs before a `FaderChange` is published,
+    /// `1..=FADER_HYSTERESIS_MAX`
+    #[n(0)]
+    #[cbor(default)]
+    pub hysteresis: u8,
+    /// Moving average over the readings, each step doubling its length. `0`
+    /// is off.
+    #[n(1)]
+    #[cbor(default)]
+    pub smoothing: u8,
+}
+
+impl Default for FaderConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FaderConfig {
+    pub const fn new() -> Self {
+        Self {
+            hysteresis: FADER_HYSTERESIS_DEFAULT,
+            smoothing: 0,
+        }
+    }
+
+    pub const fn validate(&mut self) {
+        if self.hysteresis == 0 {
+            self.hysteresis = 1;
+        } else if self.hysteresis > FADER_HYSTERESIS_MAX {
+            self.hysteresis = FADER_HYSTERESIS_MAX;
+        }
+        if self.smoothing > FADER_SMOOTHING_MAX {
+            self.smoothing = FADER_SMOOTHING_MAX;
+        }
+    }
+}
+
+/// Longest idle timeout in minutes, see `LedConfig::idle_dim_minutes`
+pub const LED_IDLE_DIM_MAX_MINUTES: u8 = 60;
+
+/// Persisted in `GlobalConfig` via CBOR. New variants may be appended with the
+/// next free `#[n(N)]` tag without a migration.
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize, PostcardBindings, Encode, Decode,
+)]
+#[cbor(index_only)]
+pub enum LedTheme {
+    #[default]
+    #[n(0)]
+    Day,
+    /// Dimmer and warmer for dark stages
+    #[n(1)]
+    Night,
+}
+
+impl LedTheme {
+    /// Brightest the LEDs get with this theme
+    pub fn max_brightness(&self) -> u8 {
+        match self {
+            LedTheme::Day => 255,
+            LedTheme::Night => 60,
+        }
+    }
+
+    pub fn apply(&self, color: RGB8) -> RGB8 {
+        match self {
+            LedTheme::Day => color,
+            // Take out most of the blue and some of the green
+            LedTheme::Night => RGB8::new(color.r, color.g / 4 * 3, color.b / 4),
+        }
+    }
+}
+
+/// Persisted in `GlobalConfig` via CBOR. New variants may be appended with the
+/// next free `#[n(N)]` tag without a migration.
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize, PostcardBindings, Encode, Decode,
+)]
+#[cbor(index_only)]
+pub enum LedPalette {
+    #[default]
+    #[n(0)]
+    Standard,
+    /// Moves red and green apart onto orange and blue, for red-green color
+    /// blindness
+    #[n(1)]
+    ColorBlind,
+}
+
+impl LedPalette {
+    pub fn remap(&self, color: RGB8) -> RGB8 {
+        match self {
+            LedPalette::Standard => color,
+            LedPalette::ColorBlind => {
+                // How much greener than red the color is
+                let green = color.g.saturating_sub(color.r);
+                RGB8::new(
+                    color.r.saturating_sub(green),
+                    ((color.r as u16 + color.g as u16) / 2) as u8,
+                    color.b.max(green.saturating_mul(2)),
+                )
+            }
+        }
+    }
+}
+
+/// How the LEDs look, applied to every LED just before it is written out.
+/// Stored in FRAM as CBOR next to the app storage, as `GlobalConfig` has no
+/// room left for it.
+#[derive(
+    Clone, Copy, Debug, PartialEq, Serialize, Deserialize, PostcardBindings, Encode, Decode,
+)]
+pub struct LedConfig {
+    #[n(0)]
+    #[cbor(default)]
+    pub theme: LedTheme,
+    #[n(1)]
+    #[cbor(default)]
+    pub palette: LedPalette,
+    /// Minutes without any input before the LEDs dim, `0` never dims.
+    #[n(2)]
+    #[cbor(default)]
+    pub idle_dim_minutes: u8,
+}
+
+impl Default for LedConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LedConfig {
+    pub const fn new() -> Self {
+        Self {
+            theme: LedTheme::Day,
+            palette: LedPalette::Standard,
+            idle_dim_minutes: 0,
+        }
+    }
+
+    pub const fn validate(&mut self) {
+        if self.idle_dim_minutes > LED_IDLE_DIM_MAX_MINUTES {
+            self.idle_dim_minutes = LED_IDLE_DIM_MAX_MINUTES;
+        }
+    }
+
+    /// Runs `color` through the palette and then the theme
+    pub fn apply(&self, color: RGB8) -> RGB8 {
+        self.theme.apply(self.palette.remap(color))
+    }
+}
+
 /// `GlobalConfig` is persisted to FRAM as CBOR. To keep the on-FRAM format
 /// forward/backward compatible without writing a migration:
 ///
@@ -718,6 +1426,16 @@ pub struct GlobalConfig {
     #[n(7)]
     #[cbor(default)]
     pub custom_voct_curves: [CustomVoOctCurve; 4],
+    #[n(8)]
+    #[cbor(default)]
+    pub i2c_map: [I2cMapping; GLOBAL_CHANNELS],
+    #[n(9)]
+    #[cbor(default)]
+    pub custom_scales: [CustomScale; CUSTOM_SCALES],
+    #[n(10)]
+    #[cbor(default)]
+    pub fader: FaderConfig,
+    // #[n(11)] held the `LedConfig`, now stored on its own
 }
 
 impl Default for GlobalConfig {
@@ -742,6 +1460,9 @@ impl GlobalConfig {
             quantizer: QuantizerConfig::new(),
             takeover_mode: TakeoverMode::Pickup,
             custom_voct_curves: [CustomVoOctCurve { counts_per_oct: 0 }; 4],
+            i2c_map: [I2cMapping::new(); GLOBAL_CHANNELS],
+            custom_scales: [CustomScale::new(); CUSTOM_SCALES],
+            fader: FaderConfig::new(),
         }
     }
 
@@ -770,6 +1491,17 @@ impl GlobalConfig {
             }
             _ => {}
         }
+        let mut i = 0;
+        while i < self.midi.outs.len() {
+            let offset = &mut self.midi.outs[i].clock_offset_ms;
+            if *offset > MIDI_CLOCK_OFFSET_MAX_MS {
+                *offset = MIDI_CLOCK_OFFSET_MAX_MS;
+            } else if *offset < -MIDI_CLOCK_OFFSET_MAX_MS {
+                *offset = -MIDI_CLOCK_OFFSET_MAX_MS;
+            }
+            i += 1;
+        }
+        self.fader.validate();
     }
 
     /// Convert a quantized pitch to DAC counts, resolving any Custom V/Oct
@@ -780,6 +1512,14 @@ impl GlobalConfig {
         pitch.as_counts_with_curves(range, vpo, &self.custom_voct_curves)
     }
 
+    /// Bitmask of the global quantizer scale, resolving `Key::Custom` from
+    /// this config's `custom_scales`
+    pub fn scale_mask(&self) -> u16 {
+        self.quantizer
+            .key
+            .as_u16_key_with_scales(&self.custom_scales)
+    }
+
     /// Return DAC counts-per-octave for `vpo`, resolving any Custom V/Oct
     /// curve from this config's `custom_voct_curves`.
     pub fn vpo_counts_per_oct(&self, vpo: VoltPerOct) -> i16 {
@@ -794,6 +1534,8 @@ pub enum Curve {
     Logarithmic,
     Exponential,
     Deadzone,
+    /// Index of a user-drawn curve, see `custom_curve`
+    Custom(u8),
 }
 
 /// Center output value of [`deadzone_curve`]'s flat zone.
@@ -844,6 +1586,7 @@ impl Curve {
             Curve::Exponential => CURVE_EXP[value as usize],
             Curve::Logarithmic => CURVE_LOG[value as usize],
             Curve::Deadzone => deadzone_curve(value),
+            Curve::Custom(idx) => custom_curve::custom_curve_at(*idx as usize, value),
         }
     }
 
@@ -852,7 +1595,7 @@ impl Curve {
             Curve::Linear => Curve::Exponential,
             Curve::Exponential => Curve::Logarithmic,
             Curve::Logarithmic => Curve::Linear,
-            Curve::Deadzone => Curve::Linear,
+            Curve::Deadzone | Curve::Custom(_) => Curve::Linear,
         }
     }
 }
@@ -1086,6 +1829,8 @@ pub enum Param {
     MidiOut,
     MidiNrpn,
     VoltPerOct,
+    MidiCc14,
+    Scale,
 }
 
 impl Param {
@@ -1096,7 +1841,9 @@ impl Param {
             | Self::MidiMode
             | Self::MidiOut
             | Self::MidiNrpn
-            | Self::VoltPerOct => true,
+            | Self::VoltPerOct
+            | Self::MidiCc14
+            | Self::Scale => true,
             Self::i32 { name, .. }
             | Self::f32 { name, .. }
             | Self::bool { name }
@@ -1145,6 +1892,8 @@ pub enum Value {
     MidiOut(MidiOut),
     MidiNrpn(bool),
     VoltPerOct(VoltPerOct),
+    MidiCc14(bool),
+    Scale(AppScale),
 }
 
 impl From<Curve> for Value {
@@ -1238,6 +1987,7 @@ impl From<usize> for Value {
 }
 
 #[derive(Deserialize, PostcardBindings)]
+#[allow(clippy::large_enum_variant)]
 pub enum ConfigMsgIn {
     Ping,
     GetAllApps,
@@ -1276,6 +2026,68 @@ pub enum ConfigMsgIn {
     ReleaseVoOctOutput {
         output_jack: u8,
     },
+    /// Responds with `MidiOutStats` for DIN out1 and out2.
+    GetMidiOutStats,
+    /// Responds with a batch of `MidiLogEntry` for the logged messages from
+    /// `since` on that are still held. Poll with the last seen `seq` + 1 for
+    /// a live monitor.
+    GetMidiLog {
+        since: u32,
+    },
+    /// Sets what the MIDI activity log records and clears it. No response.
+    SetMidiLogFilter(MidiLogFilter),
+    /// Rescans the I2C bus in leader mode. Responds with a batch of
+    /// `I2cDevice` for every follower found, empty in the other modes.
+    ScanI2c,
+    /// Responds with `Tuning`, inactive while the quantizer is in 12-TET.
+    GetTuning,
+    /// Stores a microtonal tuning and applies it to the quantizer, an inactive
+    /// one going back to 12-TET. Invalid tables are ignored. No response.
+    SetTuning(Tuning),
+    /// Set `output_jack` to each test mode step in both ranges and read it
+    /// back on `input_jack`, patched to it. Apps on either jack are evicted
+    /// for the duration. Responds with a batch of `CalibrationCheckPoint`,
+    /// empty for invalid jacks.
+    RunCalibrationCheck {
+        output_jack: u8,
+        input_jack: u8,
+    },
+    /// Responds with `CalibFile`, the stored linear fits for backing them up.
+    GetCalibFile,
+    /// Responds with `PiecewiseFile`, the stored multi-point corrections.
+    GetPiecewiseFile,
+    /// Replaces the stored calibration, dropping the multi-point corrections
+    /// made for the previous fits. Responds with `CalibrationStored`, false
+    /// when the magic, version or fits are off. Applies from the next start.
+    SetCalibFile(CalibFile),
+    /// Stores multi-point corrections for the current `CalibFile`, sent after
+    /// it when restoring. Responds with `CalibrationStored`, false on an
+    /// uncalibrated device.
+    SetPiecewiseFile(PiecewiseFile),
+    /// Responds with `VoOctTrims`, one per output jack.
+    GetVoOctTrims,
+    /// Stores the per-jack V/Oct trims and applies them right away. Ignored
+    /// if any trim is out of bounds. No response.
+    SetVoOctTrims([VoOctTrim; GLOBAL_CHANNELS]),
+    /// Responds with `FaderCalibration`.
+    GetFaderCalibration,
+    /// Track the ends of every fader while the user sweeps them, until
+    /// `FinishFaderCalibration` or a five minute timeout. Apps keep seeing the
+    /// faders. No response.
+    StartFaderCalibration,
+    /// Stores the ends of the faders swept far enough, keeping the others.
+    /// Responds with `FaderCalibration`.
+    FinishFaderCalibration,
+    /// Responds with `CustomCurves`.
+    GetCustomCurves,
+    /// Stores a user-drawn curve and applies it to every `Curve::Custom`
+    /// param using it. Ignored if the curve or index is invalid. No response.
+    SetCustomCurve(u8, CustomCurve),
+    /// Responds with `LedConfig`.
+    GetLedConfig,
+    /// Stores the LED theme, palette and idle dimming and applies them right
+    /// away. No response.
+    SetLedConfig(LedConfig),
 }
 
 #[derive(Clone, Serialize, PostcardBindings)]
@@ -1301,6 +2113,33 @@ pub enum ConfigMsgOut<'a> {
     VoOctCalError,
     /// Acknowledges `SetVoOctOutput` / `ReleaseVoOctOutput`.
     VoOctOutputSet,
+    /// DIN output scheduler counters, [out1, out2].
+    MidiOutStats([MidiOutStats; 2]),
+    /// One message from the MIDI activity log, part of a batch.
+    MidiLogEntry(MidiLogEntry),
+    /// A follower found by `ScanI2c`, part of a batch.
+    I2cDevice {
+        device: I2cDeviceType,
+        address: u8,
+    },
+    /// The stored microtonal tuning.
+    Tuning(Tuning),
+    /// One step of `RunCalibrationCheck`, part of a batch.
+    CalibrationCheckPoint(CalibrationCheckPoint),
+    /// The stored linear fits, `None` on an uncalibrated device.
+    CalibFile(Option<CalibFile>),
+    /// The stored multi-point corrections, `None` on an uncalibrated device.
+    PiecewiseFile(Option<PiecewiseFile>),
+    /// Whether `SetCalibFile` or `SetPiecewiseFile` stored the data.
+    CalibrationStored(bool),
+    /// The stored per-jack V/Oct trims.
+    VoOctTrims([VoOctTrim; GLOBAL_CHANNELS]),
+    /// The stored fader ends.
+    FaderCalibration(FaderCalibration),
+    /// The stored user-drawn curves.
+    CustomCurves([CustomCurve; CUSTOM_CURVES]),
+    /// The stored LED theme, palette and idle dimming.
+    LedConfig(LedConfig),
 }
 
 pub struct Config<const N: usize> {
@@ -1319,7 +2158,8 @@ impl<const N: usize> Config<N> {
         color: Color,
         icon: AppIcon,
     ) -> Self {
-        assert!(N <= APP_MAX_PARAMS, "Too many params");
+        // The last slot is taken by APP_TAKEOVER_PARAM
+        assert!(N < APP_MAX_PARAMS, "Too many params");
         // postcard-bindgen's JavaScript codec only round-trips ASCII strings.
         assert!(
             name.is_ascii(),
@@ -1614,7 +2454,8 @@ impl MidiOut {
 #[cfg(test)]
 mod tests {
     use super::{
-        AppIcon, Color, Config, CustomVoOctCurve, Layout, Param, VoltPerOct, GLOBAL_CHANNELS,
+        AppIcon, CalibrationCheckPoint, Color, Config, CustomVoOctCurve, Layout, Param, Range,
+        VoOctTrim, VoltPerOct, GLOBAL_CHANNELS,
     };
     use heapless::Vec;
 
@@ -1695,6 +2536,40 @@ mod tests {
         );
     }
 
+    #[test]
+    fn voct_trim_scales_around_zero_volts_then_offsets() {
+        assert_eq!(VoOctTrim::default().apply(1234, Range::_0_10V), 1234);
+
+        // +1% on 4V, then +100 cents (34 counts)
+        let trim = VoOctTrim::new(100, 10_000);
+        assert_eq!(trim.apply(1638, Range::_0_10V), 1638 + 16 + 34);
+        // 0V stays put in the bipolar range apart from the offset
+        assert_eq!(trim.apply(2048, Range::_Neg5_5V), 2048 + 34);
+        assert_eq!(trim.apply(2048 - 410, Range::_Neg5_5V), 2048 - 414 + 34);
+
+        assert_eq!(VoOctTrim::new(-1200, 0).apply(100, Range::_0_10V), 0);
+        assert_eq!(VoOctTrim::new(1200, 0).apply(4000, Range::_0_10V), 4095);
+    }
+
+    #[test]
+    fn voct_trim_validation() {
+        assert!(VoOctTrim::new(-1200, 100_000).is_valid());
+        assert!(!VoOctTrim::new(1201, 0).is_valid());
+        assert!(!VoOctTrim::new(0, -100_001).is_valid());
+    }
+
+    #[test]
+    fn calibration_check_point_reports_mv_and_cents() {
+        let point = CalibrationCheckPoint::new(Range::_Neg5_5V, 2048, 2050);
+        assert!(point.volts.abs() < 0.01);
+        assert!((point.error_mv - 4.884).abs() < 0.01);
+        assert!((point.error_cents - 5.861).abs() < 0.01);
+
+        let point = CalibrationCheckPoint::new(Range::_0_10V, 4095, 4090);
+        assert!((point.volts - 10.0).abs() < 1e-4);
+        assert!(point.error_mv < 0.0);
+    }
+
     fn mock_get_channels(app_id: u8) -> Option<usize> {
         match app_id {
             1 => Some(1), // App 1 takes 2 channels
@@ -1823,8 +2698,8 @@ mod tests {
     use minicbor::{Decode, Encode};
     use serde::{Deserialize, Serialize};
 
-    fn cbor_encode_to_vec<T: Encode<()>>(value: &T) -> heapless::Vec<u8, 256> {
-        let mut buf = [0u8; 256];
+    fn cbor_encode_to_vec<T: Encode<()>>(value: &T) -> heapless::Vec<u8, 512> {
+        let mut buf = [0u8; 512];
         let initial = buf.len();
         let mut writer: &mut [u8] = &mut buf[..];
         minicbor::encode(value, &mut writer).unwrap();
@@ -1847,6 +2722,164 @@ mod tests {
         assert_eq!(decoded.i2c_mode as u8, I2cMode::Leader as u8);
     }
 
+    #[test]
+    fn cbor_round_trip_i2c_map() {
+        let mut original = GlobalConfig::new();
+        for mapping in original.i2c_map.iter_mut() {
+            *mapping = I2cMapping {
+                device: I2cDeviceType::Txo,
+                address: 0x67,
+                port: 3,
+                scaling: I2cScaling::Volts,
+            };
+        }
+        let encoded = cbor_encode_to_vec(&original);
+        let decoded: GlobalConfig = minicbor::decode(&encoded).unwrap();
+
+        assert_eq!(decoded.i2c_map, original.i2c_map);
+    }
+
+    #[test]
+    fn cbor_worst_case_global_config_fits_fram() {
+        // Every field at its longest encoding
+        let mut config = GlobalConfig::new();
+        config.aux = [
+            AuxJackMode::ClockOut(ClockDivision::_384),
+            AuxJackMode::ClockOut(ClockDivision::_384),
+            AuxJackMode::ClockOut(ClockDivision::_384),
+        ];
+        config.clock = ClockConfig {
+            clock_src: ClockSrc::MidiUsb,
+            ext_ppqn: u8::MAX,
+            reset_src: ResetSrc::Cube,
+            internal_bpm: 123.45,
+            swing_amount: i8::MIN,
+        };
+        config.led_brightness = u8::MAX;
+        config.midi.outs = [MidiOutConfig {
+            send_clock: true,
+            send_transport: true,
+            mode: MidiOutMode::MidiMerge {
+                sources: MidiIn([true; 2]),
+            },
+            send_mtc: true,
+            clock_offset_ms: -MIDI_CLOCK_OFFSET_MAX_MS,
+            clock_div: ClockDivision::_384,
+        }; 3];
+        config.midi.mtc_fps = MtcFps::Fps30;
+        config.midi.mtc_chase = MtcChaseSrc::MidiUsb;
+        config.quantizer.key = Key::Custom(CUSTOM_SCALES as u8 - 1);
+        config.quantizer.tonic = Note::B;
+        config.custom_voct_curves = [CustomVoOctCurve {
+            counts_per_oct: u16::MAX,
+        }; 4];
+        config.i2c_map = [I2cMapping {
+            device: I2cDeviceType::Crow,
+            address: 0x7F,
+            port: u8::MAX,
+            scaling: I2cScaling::Volts,
+        }; GLOBAL_CHANNELS];
+        config.custom_scales = [CustomScale {
+            name: *b"Hijazkar",
+            mask: 0xFFF,
+        }; CUSTOM_SCALES];
+        config.fader = FaderConfig {
+            hysteresis: FADER_HYSTERESIS_MAX,
+            smoothing: FADER_SMOOTHING_MAX,
+        };
+
+        // `GLOBAL_CONFIG_RANGE` in the firmware storage is 320 bytes, the FRAM
+        // header takes 3 of them
+        let encoded = cbor_encode_to_vec(&config);
+        assert!(encoded.len() + 3 <= 320, "{} bytes", encoded.len());
+    }
+
+    #[test]
+    fn cbor_key_keeps_index_encoding() {
+        // Built-in scales encode as a bare index, as they did before `Custom`
+        assert_eq!(cbor_encode_to_vec(&Key::Dorian).as_slice(), &[0x02]);
+        let decoded: Key = minicbor::decode(&[0x10]).unwrap();
+        assert_eq!(decoded, Key::Off);
+
+        let mut original = GlobalConfig::new();
+        original.quantizer.key = Key::Custom(2);
+        original.custom_scales[2] = CustomScale {
+            name: *b"Hijaz\0\0\0",
+            mask: 0b110011011010,
+        };
+        let encoded = cbor_encode_to_vec(&original);
+        let decoded: GlobalConfig = minicbor::decode(&encoded).unwrap();
+
+        assert_eq!(decoded.quantizer.key, Key::Custom(2));
+        assert_eq!(decoded.custom_scales, original.custom_scales);
+        assert_eq!(decoded.scale_mask(), 0b110011011010);
+        assert!(minicbor::decode::<Key>(&[0x82, 0x11, 0x04]).is_err());
+    }
+
+    #[test]
+    fn cbor_round_trip_led_config() {
+        let original = LedConfig {
+            theme: LedTheme::Night,
+            palette: LedPalette::ColorBlind,
+            idle_dim_minutes: 15,
+        };
+        let encoded = cbor_encode_to_vec(&original);
+        let decoded: LedConfig = minicbor::decode(&encoded).unwrap();
+        assert_eq!(decoded, original);
+
+        // Configs stored while the LED settings were part of them still decode
+        let mut older = cbor_encode_to_vec(&GlobalConfig::new());
+        older.extend_from_slice(&encoded).unwrap();
+        older[0] += 1;
+        let decoded: GlobalConfig = minicbor::decode(&older).unwrap();
+        assert_eq!(decoded.fader, GlobalConfig::new().fader);
+    }
+
+    #[test]
+    fn color_blind_palette_keeps_red_and_green_apart() {
+        let palette = LedPalette::ColorBlind;
+        let red = palette.remap(Color::Red.into());
+        let green = palette.remap(Color::Green.into());
+        // Red leans towards orange, green towards blue
+        assert!(red.r > red.b);
+        assert!(green.b > green.r);
+        // Yellow and white look the same
+        let yellow = RGB8::new(255, 255, 0);
+        assert_eq!(palette.remap(yellow), yellow);
+        let white: RGB8 = Color::White.into();
+        assert_eq!(palette.remap(white), white);
+        assert_eq!(LedPalette::Standard.remap(green), green);
+    }
+
+    #[test]
+    fn night_theme_is_dim_and_warm() {
+        let config = LedConfig {
+            theme: LedTheme::Night,
+            ..LedConfig::new()
+        };
+        let white = config.apply(Color::White.into());
+        assert!(white.r > white.g && white.g > white.b);
+        assert!(LedTheme::Night.max_brightness() < LED_BRIGHTNESS_RANGE.start);
+
+        let mut config = LedConfig {
+            idle_dim_minutes: u8::MAX,
+            ..config
+        };
+        config.validate();
+        assert_eq!(config.idle_dim_minutes, LED_IDLE_DIM_MAX_MINUTES);
+    }
+
+    #[test]
+    fn key_index_round_trips() {
+        for i in 0..Key::COUNT {
+            assert_eq!(Key::from_index(i).index(), i);
+        }
+        assert_eq!(
+            Key::from_index(u8::MAX),
+            Key::Custom(CUSTOM_SCALES as u8 - 1)
+        );
+    }
+
     #[test]
     fn cbor_field_added_decodes_with_default() {
         // V1: a struct shaped like an "older" version.
@@ -2038,6 +3071,9 @@ mod tests {
             quantizer: decoded_v0.quantizer,
             takeover_mode: decoded_v0.takeover_mode,
             custom_voct_curves: Default::default(),
+            i2c_map: Default::default(),
+            custom_scales: Default::default(),
+            fader: Default::default(),
         };
         assert_eq!(migrated.led_brightness, 111);
 
@@ -2136,6 +3172,9 @@ mod tests {
             quantizer: decoded_v17.quantizer,
             takeover_mode: TakeoverMode::Pickup,
             custom_voct_curves: Default::default(),
+            i2c_map: Default::default(),
+            custom_scales: Default::default(),
+            fader: Default::default(),
         };
         assert_eq!(migrated.led_brightness, 111);
         assert_eq!(migrated.clock.swing_amount, 0);
@@ -2192,4 +3231,52 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn midi_clock_lead_follows_most_negative_clock_output() {
+        let mut midi = MidiConfig::new();
+        assert_eq!(midi.clock_lead_ms(), 0);
+        midi.outs[0].clock_offset_ms = 20;
+        midi.outs[1].clock_offset_ms = -15;
+        midi.outs[2].clock_offset_ms = -40;
+        midi.outs[2].send_clock = false;
+        assert_eq!(midi.clock_lead_ms(), 15);
+    }
+
+    #[test]
+    fn validate_clamps_midi_clock_offsets() {
+        let mut config = GlobalConfig::new();
+        config.midi.outs[0].clock_offset_ms = i8::MIN;
+        config.midi.outs[1].clock_offset_ms = i8::MAX;
+        config.midi.outs[2].clock_offset_ms = -12;
+        config.validate();
+        let offsets = config.midi.outs.map(|o| o.clock_offset_ms);
+        assert_eq!(
+            offsets,
+            [-MIDI_CLOCK_OFFSET_MAX_MS, MIDI_CLOCK_OFFSET_MAX_MS, -12]
+        );
+    }
+
+    #[test]
+    fn midi_log_filter_skips_clock_and_timecode_by_default() {
+        let filter = MidiLogFilter::new();
+        assert!(!filter.accepts(MidiLogPort::DinIn, 0xF8));
+        assert!(!filter.accepts(MidiLogPort::UsbOut, 0xF1));
+        assert!(filter.accepts(MidiLogPort::DinIn, 0xFA));
+        assert!(filter.accepts(MidiLogPort::Out1, 0x90));
+        assert!(filter.accepts(MidiLogPort::Out2, 0xF0));
+    }
+
+    #[test]
+    fn midi_log_filter_matches_direction_and_channel() {
+        let filter = MidiLogFilter {
+            inputs: false,
+            channels: 1 << 9,
+            ..MidiLogFilter::new()
+        };
+        assert!(!filter.accepts(MidiLogPort::UsbIn, 0x99));
+        assert!(filter.accepts(MidiLogPort::Out1, 0x99));
+        assert!(!filter.accepts(MidiLogPort::Out1, 0x90));
+        assert!(filter.accepts(MidiLogPort::Out1, 0xF2));
+    }
 }
@@ -0,0 +1,302 @@
+//! MIDI Timecode (MTC) math shared by generation and chase: frame counts to
+//! SMPTE labels (including 29.97 drop-frame), quarter-frame pieces and the
+//! Full Frame SysEx message.
+//!
+//! Positions are counted in quarter frames from timecode zero. Quarter frame
+//! `q` carries piece `q % 8` of the timecode of frame `(q - q % 8) / 4`, so a
+//! full timecode spans two frames and always starts on an even frame.
+
+use crate::MtcFps;
+
+/// Full Frame SysEx body length (without F0/F7)
+pub const FULL_FRAME_LEN: usize = 8;
+
+/// Universal Realtime SysEx ID
+const UNIVERSAL_REALTIME: u8 = 0x7F;
+/// Device ID addressing every device
+const ALL_CALL: u8 = 0x7F;
+/// MIDI Time Code sub-ID, with Full Message sub-ID#2
+const MTC: u8 = 0x01;
+const FULL_MESSAGE: u8 = 0x01;
+
+/// Frames in ten minutes of 29.97 drop-frame
+const DF_FRAMES_PER_10_MIN: u64 = 17_982;
+/// Frames in a minute of 29.97 drop-frame that drops two labels
+const DF_FRAMES_PER_MIN: u64 = 1_798;
+
+impl MtcFps {
+    /// Frame rate as numerator/denominator
+    const fn rate(self) -> (u64, u64) {
+        match self {
+            Self::Fps24 => (24, 1),
+            Self::Fps25 => (25, 1),
+            Self::Fps2997 => (30_000, 1_001),
+            Self::Fps30 => (30, 1),
+        }
+    }
+
+    /// Frame labels per second (30 for 29.97 drop-frame)
+    pub const fn nominal(self) -> u8 {
+        match self {
+            Self::Fps24 => 24,
+            Self::Fps25 => 25,
+            Self::Fps2997 | Self::Fps30 => 30,
+        }
+    }
+
+    /// Rate code carried in quarter-frame piece 7 and the Full Frame hours byte
+    pub const fn rate_code(self) -> u8 {
+        self as u8
+    }
+
+    pub const fn from_rate_code(code: u8) -> Self {
+        match code & 0x03 {
+            0 => Self::Fps24,
+            1 => Self::Fps25,
+            2 => Self::Fps2997,
+            _ => Self::Fps30,
+        }
+    }
+
+    const fn frames_per_day(self) -> u64 {
+        match self {
+            Self::Fps2997 => DF_FRAMES_PER_10_MIN * 6 * 24,
+            _ => self.nominal() as u64 * 86_400,
+        }
+    }
+
+    /// Time from timecode zero to quarter frame `qf`
+    pub const fn quarter_frames_to_micros(self, qf: u64) -> u64 {
+        let (num, den) = self.rate();
+        qf * 1_000_000 * den / (4 * num)
+    }
+
+    /// Quarter frames elapsed after `micros` from timecode zero, rounded down
+    pub const fn micros_to_quarter_frames(self, micros: u64) -> u64 {
+        let (num, den) = self.rate();
+        micros * 4 * num / (1_000_000 * den)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Timecode {
+    pub hours: u8,
+    pub minutes: u8,
+    pub seconds: u8,
+    pub frames: u8,
+}
+
+impl Timecode {
+    /// Label of the frame `frames` frames after timecode zero, wrapping at 24h
+    pub fn from_frames(frames: u64, fps: MtcFps) -> Self {
+        let nominal = fps.nominal() as u64;
+        let mut label = frames % fps.frames_per_day();
+        if fps == MtcFps::Fps2997 {
+            // Labels ;00 and ;01 are skipped at the start of every minute
+            // except each tenth
+            let tens = label / DF_FRAMES_PER_10_MIN;
+            let rem = label % DF_FRAMES_PER_10_MIN;
+            label += 18 * tens;
+            if rem > 1 {
+                label += 2 * ((rem - 2) / DF_FRAMES_PER_MIN);
+            }
+        }
+        let secs = label / nominal;
+        Self {
+            hours: (secs / 3600) as u8,
+            minutes: (secs / 60 % 60) as u8,
+            seconds: (secs % 60) as u8,
+            frames: (label % nominal) as u8,
+        }
+    }
+
+    /// Frames from timecode zero to this label
+    pub fn to_frames(self, fps: MtcFps) -> u64 {
+        let total_minutes = self.hours as u64 * 60 + self.minutes as u64;
+        let frames =
+            (total_minutes * 60 + self.seconds as u64) * fps.nominal() as u64 + self.frames as u64;
+        if fps == MtcFps::Fps2997 {
+            frames - 2 * (total_minutes - total_minutes / 10)
+        } else {
+            frames
+        }
+    }
+}
+
+/// Data nibble of quarter-frame `piece` (0-7) for `tc`
+pub fn quarter_frame_nibble(tc: Timecode, fps: MtcFps, piece: u8) -> u8 {
+    match piece & 0x07 {
+        0 => tc.frames & 0x0F,
+        1 => (tc.frames >> 4) & 0x01,
+        2 => tc.seconds & 0x0F,
+        3 => (tc.seconds >> 4) & 0x03,
+        4 => tc.minutes & 0x0F,
+        5 => (tc.minutes >> 4) & 0x03,
+        6 => tc.hours & 0x0F,
+        _ => ((tc.hours >> 4) & 0x01) | (fps.rate_code() << 1),
+    }
+}
+
+/// Piece and data nibble to send for quarter frame `qf`
+pub fn quarter_frame_at(qf: u64, fps: MtcFps) -> (u8, u8) {
+    let piece = (qf % 8) as u8;
+    let tc = Timecode::from_frames((qf - piece as u64) / 4, fps);
+    (piece, quarter_frame_nibble(tc, fps, piece))
+}
+
+/// Collects incoming quarter frames into a position. Pieces must arrive in
+/// forward order; any gap or reversal restarts at the next piece 0.
+#[derive(Clone, Copy, Default)]
+pub struct QuarterFrameDecoder {
+    nibbles: [u8; 8],
+    next: u8,
+}
+
+impl QuarterFrameDecoder {
+    /// Feed one quarter frame. On piece 7 of a complete timecode, returns the
+    /// position of that quarter frame and the sender's frame rate.
+    pub fn feed(&mut self, piece: u8, nibble: u8) -> Option<(u64, MtcFps)> {
+        let piece = piece & 0x07;
+        if piece != self.next {
+            self.next = 0;
+            if piece != 0 {
+                return None;
+            }
+        }
+        self.nibbles[piece as usize] = nibble & 0x0F;
+        if piece < 7 {
+            self.next = piece + 1;
+            return None;
+        }
+        self.next = 0;
+        let n = &self.nibbles;
+        let fps = MtcFps::from_rate_code(n[7] >> 1);
+        let tc = Timecode {
+            hours: n[6] | ((n[7] & 0x01) << 4),
+            minutes: n[4] | ((n[5] & 0x03) << 4),
+            seconds: n[2] | ((n[3] & 0x03) << 4),
+            frames: n[0] | ((n[1] & 0x01) << 4),
+        };
+        Some((tc.to_frames(fps) * 4 + 7, fps))
+    }
+}
+
+/// Full Frame SysEx body (without F0/F7), sent to locate receivers
+pub fn full_frame(tc: Timecode, fps: MtcFps) -> [u8; FULL_FRAME_LEN] {
+    [
+        UNIVERSAL_REALTIME,
+        ALL_CALL,
+        MTC,
+        FULL_MESSAGE,
+        (fps.rate_code() << 5) | (tc.hours & 0x1F),
+        tc.minutes & 0x3F,
+        tc.seconds & 0x3F,
+        tc.frames & 0x1F,
+    ]
+}
+
+/// Parses a Full Frame SysEx body (without F0/F7) addressed to any device
+pub fn parse_full_frame(body: &[u8]) -> Option<(Timecode, MtcFps)> {
+    match *body {
+        [UNIVERSAL_REALTIME, _, MTC, FULL_MESSAGE, hr, mn, sc, fr] => Some((
+            Timecode {
+                hours: hr & 0x1F,
+                minutes: mn & 0x3F,
+                seconds: sc & 0x3F,
+                frames: fr & 0x1F,
+            },
+            MtcFps::from_rate_code(hr >> 5),
+        )),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL_FPS: [MtcFps; 4] = [MtcFps::Fps24, MtcFps::Fps25, MtcFps::Fps2997, MtcFps::Fps30];
+
+    #[test]
+    fn drop_frame_skips_labels_at_minute_boundaries() {
+        let fps = MtcFps::Fps2997;
+        let tc = Timecode::from_frames(1799, fps);
+        assert_eq!((tc.minutes, tc.seconds, tc.frames), (0, 59, 29));
+        let tc = Timecode::from_frames(1800, fps);
+        assert_eq!((tc.minutes, tc.seconds, tc.frames), (1, 0, 2));
+        // Every tenth minute keeps ;00
+        let tc = Timecode::from_frames(DF_FRAMES_PER_10_MIN, fps);
+        assert_eq!((tc.minutes, tc.seconds, tc.frames), (10, 0, 0));
+    }
+
+    #[test]
+    fn frames_round_trip_through_labels() {
+        for fps in ALL_FPS {
+            for frames in (0..fps.frames_per_day()).step_by(997) {
+                assert_eq!(Timecode::from_frames(frames, fps).to_frames(fps), frames);
+            }
+        }
+    }
+
+    #[test]
+    fn labels_wrap_at_24_hours() {
+        for fps in ALL_FPS {
+            assert_eq!(
+                Timecode::from_frames(fps.frames_per_day() + 5, fps),
+                Timecode::from_frames(5, fps)
+            );
+        }
+    }
+
+    #[test]
+    fn quarter_frames_decode_to_sent_position() {
+        for fps in ALL_FPS {
+            let mut decoder = QuarterFrameDecoder::default();
+            // Start mid-timecode: the first partial timecode is ignored
+            let start = 4 * 12_345 + 3;
+            let mut decoded = None;
+            for qf in start..start + 16 {
+                let (piece, nibble) = quarter_frame_at(qf, fps);
+                if let Some(pos) = decoder.feed(piece, nibble) {
+                    decoded = Some((qf, pos));
+                }
+            }
+            let (qf, (pos, rate)) = decoded.unwrap();
+            assert_eq!(pos, qf);
+            assert!(rate == fps);
+        }
+    }
+
+    #[test]
+    fn quarter_frame_gap_restarts_decoding() {
+        let fps = MtcFps::Fps25;
+        let mut decoder = QuarterFrameDecoder::default();
+        for qf in 0..8 {
+            if qf == 4 {
+                continue;
+            }
+            let (piece, nibble) = quarter_frame_at(qf, fps);
+            assert!(decoder.feed(piece, nibble).is_none());
+        }
+    }
+
+    #[test]
+    fn full_frame_round_trips() {
+        let fps = MtcFps::Fps2997;
+        let tc = Timecode::from_frames(123_456, fps);
+        let body = full_frame(tc, fps);
+        assert!(body.iter().all(|b| b & 0x80 == 0));
+        let (parsed, rate) = parse_full_frame(&body).unwrap();
+        assert_eq!(parsed, tc);
+        assert!(rate == fps);
+    }
+
+    #[test]
+    fn drop_frame_time_runs_slow() {
+        let fps = MtcFps::Fps2997;
+        // 30 frames of 29.97 take 1.001s
+        assert_eq!(fps.quarter_frames_to_micros(4 * 30), 1_001_000);
+        assert_eq!(fps.micros_to_quarter_frames(1_001_000), 4 * 30);
+        assert_eq!(MtcFps::Fps25.micros_to_quarter_frames(1_000_000), 100);
+    }
+}
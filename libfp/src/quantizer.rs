@@ -1,11 +1,59 @@
 // V/oct quantizer, based on the ideas in
 // https://github.com/pichenettes/eurorack/blob/master/braids/quantizer_scales.h
 
-use crate::{CustomVoOctCurve, Key, MidiNote, Note, Range, VoltPerOct};
+use crate::tuning::{decicents_to_fixed, Tuning, MAX_TUNING_STEPS};
+use crate::{CustomScale, CustomVoOctCurve, Key, MidiNote, Note, Range, VoltPerOct, CUSTOM_SCALES};
 use heapless::Vec;
 use libm::roundf;
 
-const CODEBOOK_SIZE: usize = 216;
+// 18 octaves of the largest tuning
+const CODEBOOK_SIZE: usize = 18 * MAX_TUNING_STEPS;
+
+/// Most voices `Quantizer::harmonize` and `Quantizer::voice_lead` fill
+pub const MAX_CHORD_VOICES: usize = 4;
+
+/// Octaves each voice of a close chord is raised by per spread amount, from
+/// the lowest voice up
+const SPREAD_OCTAVES: [[i32; MAX_CHORD_VOICES]; 4] =
+    [[0, 0, 0, 0], [0, 1, 0, 0], [0, 1, 0, 1], [0, 1, 1, 2]];
+
+/// Chords stacked from the degrees of the current scale, so they stay
+/// diatonic to the global key and tonic
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Chord {
+    Triad,
+    Sus2,
+    Sus4,
+    Sixth,
+    Seventh,
+    Add9,
+    Quartal,
+}
+
+impl Chord {
+    pub const ALL: [Chord; 7] = [
+        Chord::Triad,
+        Chord::Sus2,
+        Chord::Sus4,
+        Chord::Sixth,
+        Chord::Seventh,
+        Chord::Add9,
+        Chord::Quartal,
+    ];
+
+    /// Scale degrees of the chord tones above the root
+    pub const fn degrees(self) -> &'static [i32] {
+        match self {
+            Chord::Triad => &[0, 2, 4],
+            Chord::Sus2 => &[0, 1, 4],
+            Chord::Sus4 => &[0, 3, 4],
+            Chord::Sixth => &[0, 2, 4, 5],
+            Chord::Seventh => &[0, 2, 4, 6],
+            Chord::Add9 => &[0, 2, 4, 8],
+            Chord::Quartal => &[0, 3, 6, 9],
+        }
+    }
+}
 
 #[derive(Clone, Copy, Debug, Default, PartialEq)]
 pub struct Pitch {
@@ -14,11 +62,14 @@ pub struct Pitch {
     /// When set, `as_counts()` returns this raw ADC value instead of the quantized voltage.
     /// Used for global (`Key::Off`) and per-app bypass passthrough mode.
     pub raw: Option<u16>,
+    /// Offset from `note` in cents (±50), set for the degrees of a microtonal
+    /// tuning that fall between semitones.
+    pub cents: i8,
 }
 
 impl Pitch {
     pub fn as_v_oct(&self) -> f32 {
-        self.octave as f32 + (self.note as u8 as f32 / 12.0)
+        self.octave as f32 + (self.note as u8 as f32 / 12.0) + (self.cents as f32 / 1200.0)
     }
 
     pub fn as_counts(&self, range: Range, vpo: VoltPerOct) -> u16 {
@@ -56,10 +107,34 @@ impl Pitch {
         roundf(counts).clamp(0.0, 4095.0) as u16
     }
 
+    /// Semitones * 128 above C0, as in the codebook
+    fn as_fixed(&self) -> i32 {
+        (self.octave as i32 * 12 + self.note as u8 as i32) * 128 + self.cents as i32 * 128 / 100
+    }
+
+    fn from_fixed(codeword: i16) -> Self {
+        let semitones = roundf(codeword as f32 / 128.0) as i32;
+        // Whatever a microtonal degree has on top of the nearest semitone
+        let cents = roundf((codeword as i32 - semitones * 128) as f32 * 100.0 / 128.0) as i8;
+        Self {
+            octave: semitones.div_euclid(12) as i8,
+            note: (semitones.rem_euclid(12) as u8).into(),
+            raw: None,
+            cents,
+        }
+    }
+
     pub fn as_midi(&self) -> MidiNote {
         let midi_note = (self.octave as i32 + 1) * 12 + self.note as u8 as i32;
         MidiNote::from(midi_note)
     }
+
+    /// 14-bit MIDI pitch bend carrying `cents` on top of `as_midi`, for a
+    /// receiver bending `range` semitones either way
+    pub fn pitch_bend(&self, range: u8) -> u16 {
+        let range = range.max(1) as i32;
+        (8192 + self.cents as i32 * 8192 / (100 * range)).clamp(0, 16383) as u16
+    }
 }
 
 pub struct QuantizerState {
@@ -96,53 +171,140 @@ pub struct Quantizer {
     version: u64,
     key: Key,
     tonic: Note,
+    mask: u16,
+    tuning: Tuning,
+    scales: [CustomScale; CUSTOM_SCALES],
+    /// Global version, key and tonic the codebook was last built from by `follow`
+    followed: Option<(u64, Key, Note)>,
+    /// Scale degrees per period
+    degrees_per_period: i32,
+    /// Codebook entries before the padding with the highest note
+    filled: usize,
 }
 
 impl Quantizer {
     pub fn set_scale(&mut self, key: Key, tonic: Note) {
+        self.set_scale_with_scales(key, tonic, &[CustomScale::new(); CUSTOM_SCALES]);
+    }
+
+    /// Like `set_scale` but resolves `Key::Custom` from the user scales.
+    pub fn set_scale_with_scales(
+        &mut self,
+        key: Key,
+        tonic: Note,
+        scales: &[CustomScale; CUSTOM_SCALES],
+    ) {
         // Store the key and tonic
         self.key = key;
         self.tonic = tonic;
+        self.scales = *scales;
 
         // When Off, use chromatic internally so MIDI quantizes to nearest semitone
         let effective_key = if key == Key::Off { Key::Chromatic } else { key };
-        let mask = effective_key.as_u16_key();
-        let notes: Vec<i16, 12> = (0..12)
-            .filter(|i| (mask >> (11 - i)) & 1 != 0) // Read from MSB (C) to LSB (B)
-            .map(|i| i as i16)
-            .collect();
-        if notes.is_empty() {
-            // Fallback to chromatic for an empty scale
-            self.set_scale(Key::Chromatic, tonic);
+        self.mask = effective_key.as_u16_key_with_scales(scales);
+        if !self.build_codebook() {
+            // Fallback to chromatic for an empty scale, keeping the custom scales
+            self.key = Key::Chromatic;
+            self.mask = Key::Chromatic.as_u16_key_with_scales(scales);
+            if !self.build_codebook() {
+                self.fall_back_to_12_tet();
+            }
+        }
+    }
+
+    /// Quantizes to the degrees of `tuning` instead of 12-TET, or back to
+    /// 12-TET for an inactive one. A tuning with 12 degrees still follows the
+    /// scale, degree by degree. Falls back to 12-TET if no codebook can be
+    /// built from `tuning`.
+    pub fn set_tuning(&mut self, tuning: Tuning) {
+        self.tuning = tuning;
+        if !self.build_codebook() {
+            self.fall_back_to_12_tet();
+        }
+    }
+
+    /// Drops the tuning. 12-TET builds from any non-empty mask, and chromatic
+    /// is used otherwise.
+    fn fall_back_to_12_tet(&mut self) {
+        self.tuning = Tuning::new();
+        if !self.build_codebook() {
+            self.key = Key::Chromatic;
+            self.mask = Key::Chromatic.as_u16_key_with_scales(&self.scales);
+            self.build_codebook();
+        }
+    }
+
+    pub fn get_tuning(&self) -> &Tuning {
+        &self.tuning
+    }
+
+    /// Quantizes to its own key and tonic with the tuning and custom scales
+    /// of `global`, for apps overriding the global scale. The codebook is
+    /// only rebuilt when either side changed.
+    pub fn follow(&mut self, global: &Quantizer, key: Key, tonic: Note) {
+        let source = Some((global.version, key, tonic));
+        if self.followed == source {
             return;
         }
+        self.tuning = global.tuning;
+        self.set_scale_with_scales(key, tonic, &global.scales);
+        self.followed = source;
+    }
 
-        let tonic_offset = tonic as i16;
+    /// Fills the codebook from the scale mask or tuning. False if no degree
+    /// is left to quantize to.
+    fn build_codebook(&mut self) -> bool {
+        // Degrees within one period and the period, in semitones * 128
+        let mut degrees: Vec<i32, MAX_TUNING_STEPS> = Vec::new();
+        let period = if self.tuning.is_active() {
+            let steps = self.tuning.degrees();
+            let masked = steps.len() == 12;
+            for (i, &step) in steps.iter().enumerate() {
+                if !masked || (self.mask >> (11 - i)) & 1 != 0 {
+                    let _ = degrees.push(decicents_to_fixed(step));
+                }
+            }
+            decicents_to_fixed(self.tuning.period)
+        } else {
+            for i in 0..12 {
+                // Read from MSB (C) to LSB (B)
+                if (self.mask >> (11 - i)) & 1 != 0 {
+                    let _ = degrees.push(i * 128);
+                }
+            }
+            12 * 128
+        };
+        if degrees.is_empty() || period <= 0 {
+            return false;
+        }
+
+        let tonic_offset = self.tonic as i32 * 128;
 
         // Build codebook directly with scale notes spanning useful range
         let mut codebook_idx = 0;
 
-        // Cover a wide range of octaves to ensure we can quantize any reasonable input
-        for octave in -6..=11 {
-            for &note_offset in &notes {
+        // Cover octaves -6 to 11 to ensure we can quantize any reasonable input
+        let lowest: i32 = -6 * 12 * 128;
+        let highest = 12 * 12 * 128;
+        let mut base = lowest.div_euclid(period) * period;
+        while base < highest && codebook_idx < CODEBOOK_SIZE {
+            for &degree in &degrees {
                 if codebook_idx >= CODEBOOK_SIZE {
                     break;
                 }
 
-                // Calculate semitone: base octave + scale note + tonic transposition
-                let semitone = octave * 12 + note_offset + tonic_offset;
-
-                // Convert to fixed-point format and store
+                // Period + degree + tonic transposition, clamped to fixed-point
                 let fixed_point =
-                    (semitone as i32 * 128).clamp(i16::MIN as i32, i16::MAX as i32) as i16;
+                    (base + degree + tonic_offset).clamp(i16::MIN as i32, i16::MAX as i32) as i16;
                 self.codebook[codebook_idx] = fixed_point;
                 codebook_idx += 1;
             }
-            if codebook_idx >= CODEBOOK_SIZE {
-                break;
-            }
+            base += period;
         }
 
+        self.degrees_per_period = degrees.len() as i32;
+        self.filled = codebook_idx;
+
         // Fill any remaining slots with the last note (highest)
         if codebook_idx > 0 {
             let last_note = self.codebook[codebook_idx - 1];
@@ -155,6 +317,7 @@ impl Quantizer {
         self.codebook.sort_unstable();
 
         self.version = self.version.wrapping_add(1);
+        true
     }
 
     pub fn get_key(&self) -> Key {
@@ -219,14 +382,124 @@ impl Quantizer {
             state.next_boundary = (9 * next_codeword + 7 * state.codeword as i32) / 16;
         }
 
-        let final_semitones = roundf(state.codeword as f32 / 128.0) as i32;
-        let octave = final_semitones.div_euclid(12) as i8;
-        let note = final_semitones.rem_euclid(12) as u8;
+        Pitch::from_fixed(state.codeword)
+    }
 
-        Pitch {
-            octave,
-            note: note.into(),
-            raw: None,
+    /// Codebook index of the scale degree nearest `pitch`
+    fn degree_index(&self, pitch: &Pitch) -> i32 {
+        let codebook = &self.codebook[..self.filled.max(1)];
+        let fixed = pitch.as_fixed();
+        let upper = codebook.partition_point(|&x| (x as i32) < fixed);
+        if upper == 0 {
+            return 0;
+        }
+        if upper >= codebook.len() {
+            return codebook.len() as i32 - 1;
+        }
+        let dist_lo = fixed - codebook[upper - 1] as i32;
+        let dist_hi = codebook[upper] as i32 - fixed;
+        if dist_lo <= dist_hi {
+            upper as i32 - 1
+        } else {
+            upper as i32
+        }
+    }
+
+    fn pitch_at(&self, index: i32) -> Pitch {
+        let last = self.filled.max(1) as i32 - 1;
+        Pitch::from_fixed(self.codebook[index.clamp(0, last) as usize])
+    }
+
+    /// `pitch` moved by `steps` degrees of the current scale, snapping to the
+    /// nearest degree first
+    pub fn transpose_degrees(&self, pitch: &Pitch, steps: i32) -> Pitch {
+        self.pitch_at(self.degree_index(pitch) + steps)
+    }
+
+    /// Codebook indices of the chord tones on `root` in close position, the
+    /// tones repeating an octave up when there are more voices than tones
+    fn chord_indices(&self, root: &Pitch, chord: Chord, voices: usize) -> [i32; MAX_CHORD_VOICES] {
+        let root = self.degree_index(root);
+        let tones = chord.degrees();
+        let mut indices = [root; MAX_CHORD_VOICES];
+        for (k, index) in indices.iter_mut().take(voices).enumerate() {
+            let octaves = (k / tones.len()) as i32;
+            *index += tones[k % tones.len()] + octaves * self.degrees_per_period;
+        }
+        indices
+    }
+
+    /// Fills `voices` (up to `MAX_CHORD_VOICES`, lowest first) with `chord`
+    /// on the scale degree nearest `root`. `inversion` raises that many of
+    /// the lowest voices by an octave, `spread` (0-3) opens the voicing by
+    /// raising inner voices by octaves.
+    pub fn harmonize(
+        &self,
+        root: &Pitch,
+        chord: Chord,
+        inversion: u8,
+        spread: u8,
+        voices: &mut [Pitch],
+    ) {
+        let count = voices.len().min(MAX_CHORD_VOICES);
+        let mut indices = self.chord_indices(root, chord, count);
+        let indices = &mut indices[..count];
+        for index in indices.iter_mut().take(inversion as usize % count.max(1)) {
+            *index += self.degrees_per_period;
+        }
+        indices.sort_unstable();
+        let spread = SPREAD_OCTAVES[(spread as usize).min(SPREAD_OCTAVES.len() - 1)];
+        for (index, octaves) in indices.iter_mut().zip(spread) {
+            *index += octaves * self.degrees_per_period;
+        }
+        indices.sort_unstable();
+        for (voice, &index) in voices.iter_mut().zip(indices.iter()) {
+            *voice = self.pitch_at(index);
+        }
+    }
+
+    /// Moves `voices` to the tones of `chord` on `root` with the least total
+    /// movement from their current pitches. Every chord tone is used once per
+    /// voice, in whichever octave is nearest to the voice.
+    pub fn voice_lead(&self, root: &Pitch, chord: Chord, voices: &mut [Pitch]) {
+        let count = voices.len().min(MAX_CHORD_VOICES);
+        let tones = self.chord_indices(root, chord, count);
+        let period = self.degrees_per_period.max(1);
+        let mut previous = [0; MAX_CHORD_VOICES];
+        for (prev, voice) in previous.iter_mut().zip(voices.iter()) {
+            *prev = self.degree_index(voice);
+        }
+        // Nearest octave of `tone` to `prev`
+        let nearest =
+            |prev: i32, tone: i32| tone + (prev - tone + period / 2).div_euclid(period) * period;
+
+        // Try every assignment of tones to voices, at most 4! of them
+        let mut best = (i32::MAX, [0; MAX_CHORD_VOICES]);
+        for code in 0..count.pow(count as u32) {
+            let mut assignment = [0; MAX_CHORD_VOICES];
+            let mut used = [false; MAX_CHORD_VOICES];
+            let mut rest = code;
+            let mut distinct = true;
+            for slot in assignment.iter_mut().take(count) {
+                *slot = rest % count;
+                rest /= count;
+                distinct &= !core::mem::replace(&mut used[*slot], true);
+            }
+            if !distinct {
+                continue;
+            }
+            let mut indices = [0; MAX_CHORD_VOICES];
+            let mut cost = 0;
+            for k in 0..count {
+                indices[k] = nearest(previous[k], tones[assignment[k]]);
+                cost += (indices[k] - previous[k]).abs();
+            }
+            if cost < best.0 {
+                best = (cost, indices);
+            }
+        }
+        for (voice, &index) in voices.iter_mut().zip(best.1.iter()) {
+            *voice = self.pitch_at(index);
         }
     }
 }
@@ -239,6 +512,12 @@ impl Default for Quantizer {
             // Default to C Chromatic
             key: Key::Chromatic,
             tonic: Note::C,
+            mask: 0,
+            tuning: Tuning::new(),
+            scales: [CustomScale::new(); CUSTOM_SCALES],
+            followed: None,
+            degrees_per_period: 12,
+            filled: 0,
         };
         q.set_scale(q.get_key(), q.get_tonic());
         q
@@ -261,7 +540,8 @@ mod tests {
             Pitch {
                 octave: 0,
                 note: Note::C,
-                raw: None
+                raw: None,
+                cents: 0
             }
         );
 
@@ -271,7 +551,8 @@ mod tests {
             Pitch {
                 octave: 1,
                 note: Note::C,
-                raw: None
+                raw: None,
+                cents: 0
             }
         );
 
@@ -283,7 +564,8 @@ mod tests {
             Pitch {
                 octave: 0,
                 note: Note::C,
-                raw: None
+                raw: None,
+                cents: 0
             }
         );
 
@@ -294,7 +576,8 @@ mod tests {
             Pitch {
                 octave: 0,
                 note: Note::D,
-                raw: None
+                raw: None,
+                cents: 0
             }
         );
 
@@ -307,11 +590,102 @@ mod tests {
             Pitch {
                 octave: 0,
                 note: Note::G,
-                raw: None
+                raw: None,
+                cents: 0
             }
         );
     }
 
+    #[test]
+    fn test_quantize_custom_scale() {
+        let mut q = Quantizer::default();
+        let mut scales = [CustomScale::new(); CUSTOM_SCALES];
+        // C and E only
+        scales[1].toggle(Note::C);
+        scales[1].toggle(Note::E);
+        q.set_scale_with_scales(Key::Custom(1), Note::C, &scales);
+        let mut state = QuantizerState::default();
+
+        assert_eq!(q.get_key(), Key::Custom(1));
+        // 0.16V, just under D0, is nearer C0 than E0
+        assert_eq!(
+            q.get_quantized_note(&mut state, 65, Range::_0_10V, VoltPerOct::Standard),
+            Pitch {
+                octave: 0,
+                note: Note::C,
+                raw: None,
+                cents: 0
+            }
+        );
+        // 0.33V (4 semitones) is E0
+        let mut state = QuantizerState::default();
+        assert_eq!(
+            q.get_quantized_note(&mut state, 137, Range::_0_10V, VoltPerOct::Standard),
+            Pitch {
+                octave: 0,
+                note: Note::E,
+                raw: None,
+                cents: 0
+            }
+        );
+    }
+
+    #[test]
+    fn test_quantize_edo_tuning() {
+        let mut q = Quantizer::default();
+        q.set_tuning(Tuning::edo(19));
+        let mut state = QuantizerState::default();
+
+        // 0.0526V is one step of 19-EDO (63 cents), nearest to C0 + 63 cents
+        let pitch = q.get_quantized_note(&mut state, 22, Range::_0_10V, VoltPerOct::Standard);
+        assert_eq!(
+            (pitch.octave, pitch.note, pitch.cents),
+            (0, Note::CSharp, -37)
+        );
+        assert!((pitch.as_v_oct() - 1.0 / 19.0).abs() < 0.001);
+        // Reaches MIDI as C#0 bent down 37 cents
+        assert_eq!(pitch.pitch_bend(2), 6677);
+
+        // Back to 12-TET, no cents left over
+        q.set_tuning(Tuning::new());
+        let pitch = q.get_quantized_note(&mut state, 22, Range::_0_10V, VoltPerOct::Standard);
+        assert_eq!((pitch.note, pitch.cents), (Note::CSharp, 0));
+        assert_eq!(pitch.pitch_bend(2), 8192);
+    }
+
+    #[test]
+    fn test_tiny_period_tuning_falls_back_to_12_tet() {
+        // Slips past validation as it was stored before it was tightened
+        let mut tuning = Tuning::edo(1);
+        tuning.period = 1;
+        let mut q = Quantizer::default();
+        q.set_tuning(tuning);
+        assert!(!q.get_tuning().is_active());
+        q.set_scale(Key::Ionian, Note::D);
+        assert_eq!(q.get_key(), Key::Ionian);
+        let mut state = QuantizerState::default();
+        let pitch = q.get_quantized_note(&mut state, 68, Range::_0_10V, VoltPerOct::Standard);
+        assert_eq!((pitch.note, pitch.cents), (Note::D, 0));
+    }
+
+    #[test]
+    fn test_twelve_degree_tuning_follows_scale() {
+        let mut q = Quantizer::default();
+        // 12-TET with E 14 cents flat, as in just intonation
+        let mut tuning = Tuning::edo(12);
+        tuning.steps[4] = 3863;
+        q.set_tuning(tuning);
+        q.set_scale(Key::Ionian, Note::C);
+        let mut state = QuantizerState::default();
+
+        let pitch = q.get_quantized_note(&mut state, 137, Range::_0_10V, VoltPerOct::Standard);
+        assert_eq!((pitch.note, pitch.cents), (Note::E, -14));
+        // D# is outside the scale
+        let mut state = QuantizerState::default();
+        let pitch = q.get_quantized_note(&mut state, 102, Range::_0_10V, VoltPerOct::Standard);
+        assert_ne!(pitch.note, Note::DSharp);
+    }
+
     #[test]
     fn test_quantize_a_minor_bipolar() {
         let mut q = Quantizer::default();
@@ -324,7 +698,8 @@ mod tests {
             Pitch {
                 octave: 0,
                 note: Note::C,
-                raw: None
+                raw: None,
+                cents: 0
             }
         );
 
@@ -334,7 +709,8 @@ mod tests {
             Pitch {
                 octave: -5,
                 note: Note::C,
-                raw: None
+                raw: None,
+                cents: 0
             }
         );
 
@@ -344,7 +720,8 @@ mod tests {
             Pitch {
                 octave: 5,
                 note: Note::C,
-                raw: None
+                raw: None,
+                cents: 0
             }
         );
     }
@@ -362,7 +739,8 @@ mod tests {
             Pitch {
                 octave: 10,
                 note: Note::C,
-                raw: None
+                raw: None,
+                cents: 0
             }
         );
 
@@ -373,7 +751,8 @@ mod tests {
             Pitch {
                 octave: -5,
                 note: Note::C,
-                raw: None
+                raw: None,
+                cents: 0
             }
         );
 
@@ -384,7 +763,8 @@ mod tests {
             Pitch {
                 octave: 5,
                 note: Note::C,
-                raw: None
+                raw: None,
+                cents: 0
             }
         );
     }
@@ -405,7 +785,8 @@ mod tests {
             Pitch {
                 octave: 0,
                 note: Note::D,
-                raw: None
+                raw: None,
+                cents: 0
             }
         );
 
@@ -417,7 +798,8 @@ mod tests {
             Pitch {
                 octave: 0,
                 note: Note::D,
-                raw: None
+                raw: None,
+                cents: 0
             }
         );
 
@@ -428,7 +810,8 @@ mod tests {
             Pitch {
                 octave: 0,
                 note: Note::C,
-                raw: None
+                raw: None,
+                cents: 0
             }
         );
     }
@@ -440,11 +823,13 @@ mod tests {
             octave: 4,
             note: Note::C,
             raw: None,
+            cents: 0,
         };
         let a4 = Pitch {
             octave: 4,
             note: Note::A,
             raw: None,
+            cents: 0,
         };
         assert_eq!(c4.as_counts(Range::_0_10V, VoltPerOct::Standard), 1638);
         assert_eq!(a4.as_counts(Range::_0_10V, VoltPerOct::Standard), 1945);
@@ -458,18 +843,21 @@ mod tests {
             octave: 4,
             note: Note::C,
             raw: None,
+            cents: 0,
         };
         assert_eq!(c4.as_midi(), MidiNote(60));
         let a4 = Pitch {
             octave: 4,
             note: Note::A,
             raw: None,
+            cents: 0,
         };
         assert_eq!(a4.as_midi(), MidiNote(69));
         let c_minus_1 = Pitch {
             octave: -1,
             note: Note::C,
             raw: None,
+            cents: 0,
         };
         assert_eq!(c_minus_1.as_midi(), MidiNote(0));
     }
@@ -505,17 +893,144 @@ mod tests {
     fn test_get_key_tonic_after_empty_scale_fallback() {
         let mut q = Quantizer::default();
 
-        // Create an empty scale (all bits zero)
-        let empty_key: Key = unsafe { core::mem::transmute(0u8) };
+        // An empty custom scale (all bits zero)
+        let scales = [CustomScale::new(); CUSTOM_SCALES];
 
         // Set scale with empty key, should fallback to Chromatic
-        q.set_scale(empty_key, Note::E);
+        q.set_scale_with_scales(Key::Custom(0), Note::E, &scales);
 
         // After fallback, key should be Chromatic and tonic should be preserved
         assert_eq!(q.get_key(), Key::Chromatic);
         assert_eq!(q.get_tonic(), Note::E);
     }
 
+    #[test]
+    fn test_empty_scale_fallback_keeps_custom_scales() {
+        let mut global = Quantizer::default();
+        let mut scales = [CustomScale::new(); CUSTOM_SCALES];
+        scales[1].toggle(Note::D);
+        global.set_scale_with_scales(Key::Custom(0), Note::C, &scales);
+        assert_eq!(global.get_key(), Key::Chromatic);
+
+        // Custom 1 still only holds D after Custom 0 fell back to chromatic
+        let mut own = Quantizer::default();
+        own.follow(&global, Key::Custom(1), Note::C);
+        let mut state = QuantizerState::default();
+        let c_sharp = 1.0 + 1.0 / 12.0;
+        let value = roundf(c_sharp / 10.0 * 4095.0) as u16;
+        let pitch = own.get_quantized_note(&mut state, value, Range::_0_10V, VoltPerOct::Standard);
+        assert_eq!((pitch.octave, pitch.note), (1, Note::D));
+    }
+
+    #[test]
+    fn test_follow_overrides_scale_but_keeps_global_changes() {
+        let mut global = Quantizer::default();
+        global.set_scale(Key::Ionian, Note::C);
+        let mut own = Quantizer::default();
+        own.follow(&global, Key::Ionian, Note::D);
+        assert_eq!(own.get_key(), Key::Ionian);
+        assert_eq!(own.get_tonic(), Note::D);
+
+        // C#1 is out of C major but in D major
+        let mut state = QuantizerState::default();
+        let c_sharp = 1.0 + 1.0 / 12.0;
+        let value = roundf(c_sharp / 10.0 * 4095.0) as u16;
+        let pitch = own.get_quantized_note(&mut state, value, Range::_0_10V, VoltPerOct::Standard);
+        assert_eq!((pitch.octave, pitch.note), (1, Note::CSharp));
+
+        // Custom scales and the tuning still come from the global quantizer
+        let mut scales = [CustomScale::new(); CUSTOM_SCALES];
+        scales[1].toggle(Note::D);
+        global.set_scale_with_scales(Key::Ionian, Note::C, &scales);
+        own.follow(&global, Key::Custom(1), Note::C);
+        let pitch = own.get_quantized_note(&mut state, value, Range::_0_10V, VoltPerOct::Standard);
+        assert_eq!((pitch.octave, pitch.note), (1, Note::D));
+
+        global.set_tuning(Tuning::edo(19));
+        own.follow(&global, Key::Custom(1), Note::C);
+        assert_eq!(own.get_tuning(), &Tuning::edo(19));
+    }
+
+    fn notes(voices: &[Pitch]) -> [(i8, Note); 4] {
+        let mut out = [(0, Note::C); 4];
+        for (o, v) in out.iter_mut().zip(voices) {
+            *o = (v.octave, v.note);
+        }
+        out
+    }
+
+    fn pitch(octave: i8, note: Note) -> Pitch {
+        Pitch {
+            octave,
+            note,
+            raw: None,
+            cents: 0,
+        }
+    }
+
+    #[test]
+    fn test_transpose_degrees_follows_scale() {
+        let mut q = Quantizer::default();
+        q.set_scale(Key::Ionian, Note::C);
+        let up = q.transpose_degrees(&pitch(2, Note::E), 2);
+        assert_eq!((up.octave, up.note), (2, Note::G));
+        let down = q.transpose_degrees(&pitch(2, Note::C), -1);
+        assert_eq!((down.octave, down.note), (1, Note::B));
+        // Out of scale notes snap first, a tie going down
+        let up = q.transpose_degrees(&pitch(2, Note::CSharp), 7);
+        assert_eq!((up.octave, up.note), (3, Note::C));
+    }
+
+    #[test]
+    fn test_harmonize_stays_diatonic() {
+        let mut q = Quantizer::default();
+        q.set_scale(Key::Ionian, Note::C);
+        let mut voices = [Pitch::default(); 4];
+
+        // D minor on the second degree, the root doubled an octave up
+        q.harmonize(&pitch(1, Note::D), Chord::Triad, 0, 0, &mut voices);
+        assert_eq!(
+            notes(&voices),
+            [(1, Note::D), (1, Note::F), (1, Note::A), (2, Note::D)]
+        );
+
+        // G7 in first inversion
+        q.harmonize(&pitch(1, Note::G), Chord::Seventh, 1, 0, &mut voices);
+        assert_eq!(
+            notes(&voices),
+            [(1, Note::B), (2, Note::D), (2, Note::F), (2, Note::G)]
+        );
+
+        // Spread raises the second and fourth voices an octave
+        q.harmonize(&pitch(1, Note::C), Chord::Triad, 0, 2, &mut voices[..3]);
+        assert_eq!(
+            notes(&voices[..3]),
+            [(1, Note::C), (1, Note::G), (2, Note::E), (0, Note::C)]
+        );
+    }
+
+    #[test]
+    fn test_voice_lead_moves_least() {
+        let mut q = Quantizer::default();
+        q.set_scale(Key::Ionian, Note::C);
+        let mut voices = [Pitch::default(); 3];
+        q.harmonize(&pitch(2, Note::C), Chord::Triad, 0, 0, &mut voices);
+
+        // C E G to F major keeps C and moves the others up a step
+        q.voice_lead(&pitch(1, Note::F), Chord::Triad, &mut voices);
+        assert_eq!(
+            notes(&voices),
+            [(2, Note::C), (2, Note::F), (2, Note::A), (0, Note::C)]
+        );
+
+        // Then every voice a step up to G major, whatever octave the root is in
+        q.voice_lead(&pitch(3, Note::G), Chord::Triad, &mut voices);
+        assert_eq!(
+            notes(&voices),
+            [(2, Note::D), (2, Note::G), (2, Note::B), (0, Note::C)]
+        );
+    }
+
     #[test]
     fn test_key_tonic_preserved_during_quantization() {
         let mut q = Quantizer::default();
@@ -6,6 +6,9 @@
 //! prefix followed by the postcard-serialized `ConfigMsgIn`/`ConfigMsgOut`.
 //! The configurator mirrors this codec in `configurator/src/utils/sysex.ts` —
 //! keep both sides in sync.
+//!
+//! Also holds the MIDI Identity Request/Reply used for device inquiry on the
+//! performance ports.
 
 pub const SYSEX_START: u8 = 0xF0;
 pub const SYSEX_EOX: u8 = 0xF7;
@@ -25,6 +28,18 @@ pub const MAX_PACKED_SIZE: usize = packed_len(MAX_PLAIN_SIZE);
 /// Full frame: F0 + header + packed payload + F7.
 pub const MAX_SYSEX_FRAME: usize = 1 + SYSEX_HEADER.len() + MAX_PACKED_SIZE + 1;
 
+/// Universal Non-Realtime SysEx ID
+const UNIVERSAL_NON_REALTIME: u8 = 0x7E;
+/// Device ID addressing every device
+const ALL_CALL: u8 = 0x7F;
+/// General Information sub-ID, with Identity Request/Reply sub-ID#2
+const GENERAL_INFO: u8 = 0x06;
+const IDENTITY_REQUEST: u8 = 0x01;
+const IDENTITY_REPLY: u8 = 0x02;
+
+/// Identity Reply body length (without F0/F7)
+pub const IDENTITY_REPLY_LEN: usize = 13;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SysexError {
     /// A packed byte had its top bit set.
@@ -84,6 +99,37 @@ pub fn unpack_7bit(src: &[u8], dst: &mut [u8]) -> Result<usize, SysexError> {
     Ok(written)
 }
 
+/// True if `body` (without F0/F7) is a MIDI Identity Request addressed to
+/// any device.
+pub fn is_identity_request(body: &[u8]) -> bool {
+    matches!(
+        body,
+        [UNIVERSAL_NON_REALTIME, _, GENERAL_INFO, IDENTITY_REQUEST]
+    )
+}
+
+/// Identity Reply body (without F0/F7): manufacturer and "FP" family from
+/// [`SYSEX_HEADER`], model 1, then the firmware version as
+/// major/minor/patch/0.
+pub fn identity_reply(version: (u8, u8, u8)) -> [u8; IDENTITY_REPLY_LEN] {
+    let (major, minor, patch) = version;
+    [
+        UNIVERSAL_NON_REALTIME,
+        ALL_CALL,
+        GENERAL_INFO,
+        IDENTITY_REPLY,
+        SYSEX_HEADER[0],
+        SYSEX_HEADER[1],
+        SYSEX_HEADER[2],
+        0x01,
+        0x00,
+        major & 0x7F,
+        minor & 0x7F,
+        patch & 0x7F,
+        0x00,
+    ]
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -135,6 +181,22 @@ mod tests {
         assert_eq!(unpack_7bit(&[0x01], &mut dst), Err(SysexError::Truncated));
     }
 
+    #[test]
+    fn identity_request_matches_any_device_id() {
+        assert!(is_identity_request(&[0x7E, 0x7F, 0x06, 0x01]));
+        assert!(is_identity_request(&[0x7E, 0x10, 0x06, 0x01]));
+        assert!(!is_identity_request(&[0x7E, 0x7F, 0x06, 0x02]));
+        assert!(!is_identity_request(&[0x7E, 0x7F, 0x06, 0x01, 0x00]));
+    }
+
+    #[test]
+    fn identity_reply_is_7bit() {
+        let reply = identity_reply((1, 200, 3));
+        assert_eq!(&reply[..4], &[0x7E, 0x7F, 0x06, 0x02]);
+        assert_eq!(reply[4], SYSEX_HEADER[0]);
+        assert!(reply.iter().all(|b| b & 0x80 == 0));
+    }
+
     #[test]
     fn frame_size_consts() {
         assert_eq!(MAX_PACKED_SIZE, 588);
@@ -0,0 +1,189 @@
+//! Microtonal tunings for the quantizer: N-EDO and cent tables imported from
+//! Scala `.scl` files by the configurator.
+//!
+//! A tuning lists the scale degrees above the tonic within one period (the
+//! octave for most scales), in tenths of a cent. The quantizer repeats them
+//! every period and transposes them by the global tonic. Without a tuning
+//! the quantizer stays in 12-TET and uses the scale masks of `Key`.
+
+use minicbor::{Decode, Encode};
+use postcard_bindgen::PostcardBindings;
+use serde::{Deserialize, Serialize};
+
+/// Most degrees a tuning can have, enough for 31-EDO
+pub const MAX_TUNING_STEPS: usize = 32;
+
+/// An octave in tenths of a cent
+pub const OCTAVE_DECICENTS: u16 = 12000;
+
+/// Stored in FRAM as CBOR next to the app storage. Tags are append-only like
+/// `GlobalConfig`.
+#[derive(
+    Clone, Copy, Debug, PartialEq, Serialize, Deserialize, PostcardBindings, Encode, Decode,
+)]
+pub struct Tuning {
+    /// Degrees above the tonic in tenths of a cent, ascending and starting
+    /// at 0. Only the first `len` are used.
+    #[n(0)]
+    #[cbor(default)]
+    pub steps: [u16; MAX_TUNING_STEPS],
+    /// Number of degrees, 0 for plain 12-TET
+    #[n(1)]
+    #[cbor(default)]
+    pub len: u8,
+    /// Interval the degrees repeat at in tenths of a cent, usually an octave
+    #[n(2)]
+    #[cbor(default)]
+    pub period: u16,
+}
+
+/// Tenths of a cent to the quantizer codebook's semitones * 128
+pub(crate) fn decicents_to_fixed(decicents: u16) -> i32 {
+    (decicents as i32 * 128 + 500) / 1000
+}
+
+impl Default for Tuning {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Tuning {
+    /// 12-TET, which leaves the quantizer on the `Key` scale masks
+    pub const fn new() -> Self {
+        Self {
+            steps: [0; MAX_TUNING_STEPS],
+            len: 0,
+            period: OCTAVE_DECICENTS,
+        }
+    }
+
+    /// `divisions` equal steps to the octave, clamped to 1-32
+    pub fn edo(divisions: u8) -> Self {
+        let divisions = divisions.clamp(1, MAX_TUNING_STEPS as u8);
+        let mut tuning = Self::new();
+        for i in 0..divisions as u32 {
+            let step =
+                (i * OCTAVE_DECICENTS as u32 * 2 + divisions as u32) / (divisions as u32 * 2);
+            tuning.steps[i as usize] = step as u16;
+        }
+        tuning.len = divisions;
+        tuning
+    }
+
+    /// Builds a tuning from cents in the order of a Scala file: every degree
+    /// above the tonic, the last one being the period. `None` if they don't
+    /// ascend, there are too many, the period is over 6553.5 cents or they
+    /// don't pass [`Tuning::is_valid`].
+    pub fn from_cents(cents: &[f32]) -> Option<Self> {
+        let (&period, degrees) = cents.split_last()?;
+        if degrees.len() + 1 > MAX_TUNING_STEPS {
+            return None;
+        }
+        let mut tuning = Self::new();
+        let mut previous = 0.0;
+        for (i, &c) in degrees.iter().chain(core::iter::once(&period)).enumerate() {
+            if c <= previous || c * 10.0 > u16::MAX as f32 {
+                return None;
+            }
+            previous = c;
+            let decicents = libm::roundf(c * 10.0) as u16;
+            if i < degrees.len() {
+                tuning.steps[i + 1] = decicents;
+            } else {
+                tuning.period = decicents;
+            }
+        }
+        tuning.len = degrees.len() as u8 + 1;
+        tuning.is_valid().then_some(tuning)
+    }
+
+    /// Whether the quantizer should use this tuning instead of 12-TET
+    pub fn is_active(&self) -> bool {
+        self.len > 0
+    }
+
+    /// Checks data from the configurator or FRAM before it's used. Degrees
+    /// and the period have to stay apart at the quantizer's resolution of
+    /// 1/128 semitone, so the codebook can always be built from them.
+    pub fn is_valid(&self) -> bool {
+        if !self.is_active() {
+            return true;
+        }
+        let degrees = self.degrees();
+        degrees.len() <= MAX_TUNING_STEPS
+            && degrees[0] == 0
+            && degrees
+                .iter()
+                .chain(core::iter::once(&self.period))
+                .map(|&d| decicents_to_fixed(d))
+                .collect::<heapless::Vec<i32, { MAX_TUNING_STEPS + 1 }>>()
+                .windows(2)
+                .all(|w| w[0] < w[1])
+    }
+
+    /// The degrees in use
+    pub fn degrees(&self) -> &[u16] {
+        &self.steps[..(self.len as usize).min(MAX_TUNING_STEPS)]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn edo_divides_the_octave_evenly() {
+        let tuning = Tuning::edo(19);
+        assert!(tuning.is_active() && tuning.is_valid());
+        assert_eq!(tuning.degrees().len(), 19);
+        // 1200 / 19 = 63.16 cents
+        assert_eq!(tuning.steps[1], 632);
+        assert_eq!(tuning.steps[18], 11368);
+        assert_eq!(tuning.period, OCTAVE_DECICENTS);
+        assert_eq!(Tuning::edo(12).steps[7], 7000);
+    }
+
+    #[test]
+    fn cents_follow_scala_order() {
+        // Just major triad with the octave as period
+        let tuning = Tuning::from_cents(&[386.3137, 701.955, 1200.0]).unwrap();
+        assert_eq!(tuning.degrees(), &[0, 3863, 7020]);
+        assert_eq!(tuning.period, 12000);
+        assert!(tuning.is_valid());
+
+        // Tritave period, as in Bohlen-Pierce
+        let bp = Tuning::from_cents(&[146.3, 1901.955]).unwrap();
+        assert_eq!(bp.period, 19020);
+
+        assert!(Tuning::from_cents(&[]).is_none());
+        assert!(Tuning::from_cents(&[700.0, 400.0, 1200.0]).is_none());
+        assert!(Tuning::from_cents(&[0.0, 1200.0]).is_none());
+        assert!(Tuning::from_cents(&[100.0; 40]).is_none());
+    }
+
+    #[test]
+    fn invalid_tables_are_rejected() {
+        assert!(Tuning::new().is_valid());
+        let mut tuning = Tuning::edo(5);
+        tuning.steps[2] = tuning.steps[3];
+        assert!(!tuning.is_valid());
+        let mut tuning = Tuning::edo(5);
+        tuning.period = 100;
+        assert!(!tuning.is_valid());
+        // Degrees closer than the quantizer resolves
+        let mut tuning = Tuning::edo(5);
+        tuning.steps[2] = tuning.steps[1] + 1;
+        assert!(!tuning.is_valid());
+    }
+
+    #[test]
+    fn tiny_period_is_rejected() {
+        // A period of 0.1 cents is 0 in codebook units
+        assert!(Tuning::from_cents(&[0.05]).is_none());
+        assert!(Tuning::from_cents(&[0.02, 0.05]).is_none());
+        let mut tuning = Tuning::edo(1);
+        tuning.period = 1;
+        assert!(!tuning.is_valid());
+    }
+}
@@ -1,6 +1,10 @@
+use minicbor::{Decode, Encode};
+use postcard_bindgen::PostcardBindings;
 use serde::{Deserialize, Serialize};
 
-use crate::{CALIBRATION_SCALE_FACTOR, CALIBRATION_VERSION_LATEST, CALIB_FILE_MAGIC};
+use crate::{
+    CALIBRATION_SCALE_FACTOR, CALIBRATION_VERSION_LATEST, CALIB_FILE_MAGIC, CALIB_POINTS_FILE_MAGIC,
+};
 
 // --- V1 (Old) Format Definition ---
 #[derive(Deserialize, Default, Copy, Clone)]
@@ -9,19 +13,57 @@ pub struct MaxCalibrationV1 {
     pub outputs: [[(f32, f32); 2]; 20],
 }
 
-// --- V2 (New) Format Definition ---
-// This is the canonical format for the application.
+// --- V2 Format Definition ---
 pub type RegressionValues = (i64, i64);
 pub type RegressionValuesInput = [RegressionValues; 2];
 pub type RegressionValuesOutput = [[RegressionValues; 2]; 20];
 
 #[derive(Serialize, Deserialize, Default, Copy, Clone)]
-pub struct MaxCalibration {
+pub struct MaxCalibrationV2 {
     pub inputs: RegressionValuesInput,
     pub outputs: RegressionValuesOutput,
 }
 
 #[derive(Serialize, Deserialize, Copy, Clone)]
+pub struct CalibFileV2 {
+    pub magic: [u8; 4],
+    pub version: u8,
+    pub data: MaxCalibrationV2,
+}
+
+// --- V3 Format Definition ---
+// This is the canonical format for the application.
+
+/// Points of the multi-point calibration: every volt of the 0-10V range from
+/// 1V up. 0V needs none, the outputs are held at 0 there.
+pub const CALIB_POINTS: usize = 10;
+/// Jacks with multi-point calibration, the aux jacks only output gates
+pub const CALIB_POINTS_JACKS: usize = 16;
+
+/// What the linear fit is off by at each point, in counts
+pub type CalibPoints = [i8; CALIB_POINTS];
+
+/// Piecewise-linear corrections on top of the linear fits for the 0-10V
+/// range. `None` where only the linear fit was measured.
+#[derive(Serialize, Deserialize, PostcardBindings, Default, Copy, Clone, Debug, PartialEq)]
+pub struct PiecewiseCalibration {
+    pub input: Option<CalibPoints>,
+    pub outputs: [Option<CalibPoints>; CALIB_POINTS_JACKS],
+}
+
+#[derive(Serialize, Deserialize, PostcardBindings, Default, Copy, Clone)]
+pub struct MaxCalibration {
+    pub inputs: RegressionValuesInput,
+    pub outputs: RegressionValuesOutput,
+    /// Too big to share a FRAM record with the linear fits, so it is stored
+    /// in its own `PiecewiseFile` and left out of `CalibFile`
+    #[serde(skip)]
+    pub piecewise: PiecewiseCalibration,
+}
+
+/// Holds the linear fits. Since V3 the multi-point corrections are in a
+/// `PiecewiseFile` next to it.
+#[derive(Serialize, Deserialize, PostcardBindings, Copy, Clone)]
 pub struct CalibFile {
     pub magic: [u8; 4],
     pub version: u8,
@@ -36,13 +78,116 @@ impl CalibFile {
             data,
         }
     }
+
+    /// Whether the file holds current calibration data with plausible fits
+    /// for every input and output. V2 files encode the same.
+    pub fn is_valid(&self) -> bool {
+        self.magic == CALIB_FILE_MAGIC
+            && (2..=CALIBRATION_VERSION_LATEST).contains(&self.version)
+            && self.data.inputs.iter().all(|&fit| fit_is_sane(fit))
+            && self.data.outputs[..CALIB_POINTS_JACKS]
+                .iter()
+                .flatten()
+                .all(|&fit| fit_is_sane(fit))
+    }
+}
+
+#[derive(Serialize, Deserialize, PostcardBindings, Copy, Clone)]
+pub struct PiecewiseFile {
+    pub magic: [u8; 4],
+    pub data: PiecewiseCalibration,
+}
+
+impl PiecewiseFile {
+    pub fn new(data: PiecewiseCalibration) -> Self {
+        Self {
+            magic: CALIB_POINTS_FILE_MAGIC,
+            data,
+        }
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.magic == CALIB_POINTS_FILE_MAGIC
+    }
+}
+
+/// Slopes further than this from 1.0 don't come from a working jack
+const MAX_SLOPE_DEVIATION: i64 = CALIBRATION_SCALE_FACTOR / 10;
+/// Largest plausible offset of a fit, in counts
+const MAX_INTERCEPT_COUNTS: i64 = 400;
+
+fn fit_is_sane((slope, intercept): RegressionValues) -> bool {
+    (slope - CALIBRATION_SCALE_FACTOR).abs() <= MAX_SLOPE_DEVIATION
+        && intercept.abs() <= MAX_INTERCEPT_COUNTS * CALIBRATION_SCALE_FACTOR
+}
+
+/// Ideal value in counts of calibration point `k`
+pub const fn calib_point_value(k: usize) -> i32 {
+    ((k as i32 + 1) * 4095 + 5) / 10
+}
+
+/// Correction at `ideal` counts, interpolated between the points. Below 1V
+/// it fades in from 0 at 0V.
+fn interpolate(points: &CalibPoints, ideal: i32) -> i32 {
+    let mut x0 = 0;
+    let mut y0 = 0;
+    for (k, &y1) in points.iter().enumerate() {
+        let x1 = calib_point_value(k);
+        let y1 = y1 as i32;
+        if ideal <= x1 {
+            let t = (ideal - x0).max(0);
+            // Rounded to the nearest count
+            return y0 + ((y1 - y0) * t * 2 + (x1 - x0)).div_euclid((x1 - x0) * 2);
+        }
+        x0 = x1;
+        y0 = y1;
+    }
+    y0
+}
+
+/// Residuals of measured values from a linear fit at the calibration points,
+/// clamped to what an `i8` holds
+pub fn calib_points_from_residuals(residuals: &[f32; CALIB_POINTS]) -> CalibPoints {
+    residuals.map(|r| libm::roundf(r).clamp(i8::MIN as f32, i8::MAX as f32) as i8)
+}
+
+/// `slope * value + intercept` in fixed point. Adding half the scale factor
+/// before the shift rounds to the nearest count.
+pub fn apply_regression((slope, intercept): RegressionValues, value: u16) -> i32 {
+    ((value as i64 * slope + intercept + (CALIBRATION_SCALE_FACTOR / 2)) >> 16) as i32
+}
+
+impl MaxCalibration {
+    /// DAC value putting out `ideal` on output `chan`. `range` is 0 for 0-10V
+    /// and 1 for -5V to 5V.
+    pub fn output_value(&self, chan: usize, range: usize, ideal: u16) -> u16 {
+        let mut value = apply_regression(self.outputs[chan][range], ideal);
+        if range == 0 {
+            if let Some(Some(points)) = self.piecewise.outputs.get(chan) {
+                value += interpolate(points, ideal as i32);
+            }
+        }
+        value.clamp(0, 4095) as u16
+    }
+
+    /// Ideal value of a raw ADC reading. `range` is 0 for 0-10V and 1 for
+    /// -5V to 5V.
+    pub fn input_value(&self, range: usize, raw: u16) -> u16 {
+        let mut value = apply_regression(self.inputs[range], raw);
+        if range == 0 {
+            if let Some(points) = &self.piecewise.input {
+                value += interpolate(points, value);
+            }
+        }
+        value.clamp(0, 4095) as u16
+    }
 }
 
 // --- Migration Logic ---
-// This function converts the old V1 data into the new V2 format.
-impl From<MaxCalibrationV1> for MaxCalibration {
+// This function converts the old V1 data into the V2 format.
+impl From<MaxCalibrationV1> for MaxCalibrationV2 {
     fn from(old: MaxCalibrationV1) -> Self {
-        let mut new = MaxCalibration::default();
+        let mut new = MaxCalibrationV2::default();
 
         // Convert inputs
         for i in 0..old.inputs.len() {
@@ -79,3 +224,205 @@ impl From<MaxCalibrationV1> for MaxCalibration {
         new
     }
 }
+
+// V3 only adds the multi-point corrections, which V2 data has none of
+impl From<MaxCalibrationV2> for MaxCalibration {
+    fn from(old: MaxCalibrationV2) -> Self {
+        Self {
+            inputs: old.inputs,
+            outputs: old.outputs,
+            piecewise: PiecewiseCalibration::default(),
+        }
+    }
+}
+
+/// Smallest sweep a fader must cover to be calibrated, anything less is
+/// left as is
+pub const FADER_CALIB_MIN_SPAN: u16 = 2048;
+/// Counts kept off each measured end so worn faders reliably reach 0 and 4095
+const FADER_CALIB_MARGIN: u16 = 8;
+
+/// Ends of each fader's travel in raw counts, rescaled to the full 0-4095.
+/// Stored in FRAM as CBOR, kept through a factory reset like `MaxCalibration`.
+/// Faders whose ends are less than `FADER_CALIB_MIN_SPAN` apart, like the
+/// all-zero default, are read raw.
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize, PostcardBindings, Encode, Decode,
+)]
+pub struct FaderCalibration {
+    #[n(0)]
+    #[cbor(default)]
+    pub min: [u16; 16],
+    #[n(1)]
+    #[cbor(default)]
+    pub max: [u16; 16],
+}
+
+impl FaderCalibration {
+    pub const fn new() -> Self {
+        Self {
+            min: [0; 16],
+            max: [0; 16],
+        }
+    }
+
+    pub fn is_calibrated(&self, fader: usize) -> bool {
+        self.max[fader] >= self.min[fader].saturating_add(FADER_CALIB_MIN_SPAN)
+    }
+
+    /// Take over the ends seen while sweeping `fader`, if it went far enough
+    pub fn set_from_sweep(&mut self, fader: usize, min: u16, max: u16) -> bool {
+        let min = min.saturating_add(FADER_CALIB_MARGIN);
+        let max = max.saturating_sub(FADER_CALIB_MARGIN);
+        if max < min.saturating_add(FADER_CALIB_MIN_SPAN) {
+            return false;
+        }
+        self.min[fader] = min;
+        self.max[fader] = max.min(4095);
+        true
+    }
+
+    /// Rescale a raw reading, `None` if the fader isn't calibrated
+    pub fn apply(&self, fader: usize, raw: u16) -> Option<u16> {
+        if !self.is_calibrated(fader) {
+            return None;
+        }
+        let min = self.min[fader] as u32;
+        let span = self.max[fader] as u32 - min;
+        let rel = (raw as u32).clamp(min, min + span) - min;
+        Some(((rel * 4095 + span / 2) / span) as u16)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn linear() -> MaxCalibrationV2 {
+        let mut data = MaxCalibrationV2::default();
+        for (i, input) in data.inputs.iter_mut().enumerate() {
+            *input = (66_000 + i as i64 * 100, -40_000);
+        }
+        for (i, output) in data.outputs.iter_mut().enumerate() {
+            output[0] = (65_000 + i as i64 * 37, 120_000 - i as i64 * 9_000);
+            output[1] = (65_800 - i as i64 * 21, -300_000);
+        }
+        data
+    }
+
+    #[test]
+    fn linear_data_gives_the_v2_output() {
+        let v2 = linear();
+        let v3 = MaxCalibration::from(v2);
+        for value in 0..=4095 {
+            for range in 0..2 {
+                for chan in 0..20 {
+                    let (slope, intercept) = v2.outputs[chan][range];
+                    let expected =
+                        (((value as i64 * slope) + intercept + (CALIBRATION_SCALE_FACTOR / 2))
+                            >> 16)
+                            .clamp(0, 4095) as u16;
+                    assert_eq!(v3.output_value(chan, range, value), expected);
+                }
+                let (slope, intercept) = v2.inputs[range];
+                let expected =
+                    (((value as i64 * slope) + intercept + (CALIBRATION_SCALE_FACTOR / 2)) >> 16)
+                        .clamp(0, 4095) as u16;
+                assert_eq!(v3.input_value(range, value), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn calib_file_validation() {
+        let data = MaxCalibration::from(linear());
+        assert!(CalibFile::new(data).is_valid());
+
+        let mut file = CalibFile::new(data);
+        file.magic = *b"FPXX";
+        assert!(!file.is_valid());
+
+        let mut file = CalibFile::new(data);
+        file.version = CALIBRATION_VERSION_LATEST + 1;
+        assert!(!file.is_valid());
+
+        let mut file = CalibFile::new(data);
+        file.data.outputs[3][1].0 = CALIBRATION_SCALE_FACTOR * 2;
+        assert!(!file.is_valid());
+
+        let mut file = CalibFile::new(data);
+        file.data.inputs[0].1 = -1000 * CALIBRATION_SCALE_FACTOR;
+        assert!(!file.is_valid());
+
+        // The fader port and the aux jacks are never checked
+        let mut file = CalibFile::new(data);
+        file.data.outputs[16] = [(0, 0); 2];
+        file.data.outputs[19] = [(0, 0); 2];
+        assert!(file.is_valid());
+    }
+
+    #[test]
+    fn v3_file_reads_v2_bytes() {
+        let mut buf = [0; 512];
+        let v2 = CalibFileV2 {
+            magic: CALIB_FILE_MAGIC,
+            version: 2,
+            data: linear(),
+        };
+        let bytes = postcard::to_slice(&v2, &mut buf).unwrap();
+        let v3 = postcard::from_bytes::<CalibFile>(bytes).unwrap();
+        assert_eq!(v3.version, 2);
+        assert_eq!(v3.data.outputs, v2.data.outputs);
+        assert_eq!(v3.data.inputs, v2.data.inputs);
+        assert_eq!(v3.data.piecewise, PiecewiseCalibration::default());
+    }
+
+    #[test]
+    fn points_correct_between_volts() {
+        let mut data = MaxCalibration::from(MaxCalibrationV2::default());
+        data.outputs = [[(CALIBRATION_SCALE_FACTOR, 0); 2]; 20];
+        let mut points = [0; CALIB_POINTS];
+        points[0] = 4;
+        points[8] = -6;
+        points[9] = 10;
+        data.piecewise.outputs[3] = Some(points);
+
+        // 1V, and halfway to it from 0V
+        assert_eq!(data.output_value(3, 0, 410), 414);
+        assert_eq!(data.output_value(3, 0, 205), 207);
+        // 9V to 10V goes from -6 to +10
+        assert_eq!(data.output_value(3, 0, 3686), 3680);
+        assert_eq!(data.output_value(3, 0, 3890), 3892);
+        assert_eq!(data.output_value(3, 0, 4095), 4095);
+        // Only 0-10V is corrected, and only on that output
+        assert_eq!(data.output_value(3, 1, 410), 410);
+        assert_eq!(data.output_value(4, 0, 410), 410);
+    }
+
+    #[test]
+    fn residuals_are_clamped() {
+        let mut residuals = [0.0; CALIB_POINTS];
+        residuals[0] = 2.6;
+        residuals[1] = -300.0;
+        let points = calib_points_from_residuals(&residuals);
+        assert_eq!(points[0], 3);
+        assert_eq!(points[1], i8::MIN);
+    }
+
+    #[test]
+    fn fader_calibration_rescales_worn_faders() {
+        let mut cal = FaderCalibration::default();
+        assert_eq!(cal.apply(0, 1000), None);
+
+        assert!(cal.set_from_sweep(0, 38, 4021));
+        assert_eq!(cal.apply(0, 30), Some(0));
+        assert_eq!(cal.apply(0, 46), Some(0));
+        assert_eq!(cal.apply(0, 4013), Some(4095));
+        assert_eq!(cal.apply(0, 2030), Some(2048));
+
+        // A fader that was barely moved keeps its previous ends
+        assert!(!cal.set_from_sweep(0, 1500, 3000));
+        assert_eq!(cal.min[0], 46);
+        assert_eq!(cal.apply(1, 1000), None);
+    }
+}
@@ -16,9 +16,10 @@ pub fn scale_bits_12_7(value: u16) -> u7 {
 }
 
 /// Resolution at which a 12-bit value should be de-duplicated before
-/// emitting MIDI: full 12-bit in NRPN mode, 7-bit-quantized in CC mode.
-pub fn midi_gate(value: u16, nrpn: bool) -> u16 {
-    if nrpn {
+/// emitting MIDI: full 12-bit in NRPN or 14-bit CC mode, 7-bit-quantized in
+/// plain CC mode.
+pub fn midi_gate(value: u16, high_res: bool) -> u16 {
+    if high_res {
         value
     } else {
         scale_bits_12_7(value).as_int() as u16